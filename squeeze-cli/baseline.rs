@@ -0,0 +1,103 @@
+use squeeze::codetag::Tag;
+use std::collections::HashMap;
+use std::io;
+use std::path::{Component, Path};
+
+/// Identifies a codetag regardless of its position in the file, so that it is still recognized
+/// once the code around it has moved.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Fingerprint {
+    file: Option<String>,
+    mnemonic: String,
+    text: String,
+}
+
+impl Fingerprint {
+    pub fn new(file: Option<&Path>, tag: &Tag) -> Self {
+        Fingerprint {
+            // `./src/main.rs` and `src/main.rs` are the same file
+            file: file.map(|file| {
+                file.components()
+                    .filter(|c| !matches!(c, Component::CurDir))
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/")
+            }),
+            mnemonic: tag.mnemonic.to_uppercase(),
+            text: tag
+                .description
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
+
+/// The codetags known at some point in time, e.g. to only report the ones added since.
+#[derive(Default)]
+pub struct Baseline {
+    // the same codetag can appear several times in a file
+    counts: HashMap<Fingerprint, usize>,
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message);
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        let codetags = json["codetags"]
+            .as_array()
+            .ok_or_else(|| invalid("missing the codetags array"))?;
+
+        let mut baseline = Baseline::default();
+        for codetag in codetags {
+            let field = |name: &str| {
+                codetag[name]
+                    .as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| invalid(&format!("missing the codetag {}", name)))
+            };
+            let fingerprint = Fingerprint {
+                file: codetag["file"].as_str().map(str::to_string),
+                mnemonic: field("mnemonic")?,
+                text: field("text")?,
+            };
+            let count = codetag["count"].as_u64().unwrap_or(1) as usize;
+            *baseline.counts.entry(fingerprint).or_default() += count;
+        }
+        Ok(baseline)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        // sorted, to keep the diffs small once the baseline is committed
+        let mut counts: Vec<_> = self.counts.iter().collect();
+        counts.sort();
+        let codetags: Vec<_> = counts
+            .into_iter()
+            .map(|(fingerprint, count)| {
+                serde_json::json!({
+                    "file": fingerprint.file,
+                    "mnemonic": fingerprint.mnemonic,
+                    "text": fingerprint.text,
+                    "count": count,
+                })
+            })
+            .collect();
+        let json = serde_json::json!({ "version": 1, "codetags": codetags });
+        std::fs::write(path, serde_json::to_string_pretty(&json)? + "\n")
+    }
+
+    pub fn insert(&mut self, fingerprint: Fingerprint) {
+        *self.counts.entry(fingerprint).or_default() += 1;
+    }
+
+    /// Removes one occurrence of the codetag, returns whether it was known.
+    pub fn remove(&mut self, fingerprint: &Fingerprint) -> bool {
+        match self.counts.get_mut(fingerprint) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+}
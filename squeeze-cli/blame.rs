@@ -0,0 +1,111 @@
+use squeeze::datetime::Timestamp;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::rc::Rc;
+
+/// The last change of a line, as reported by `git blame`.
+pub struct Blame {
+    pub commit: String,
+    pub author: String,
+    pub author_mail: String,
+    /// The author time, in seconds since the Unix epoch.
+    pub time: i64,
+    /// The author time zone offset, in seconds.
+    pub offset: i64,
+}
+
+impl Blame {
+    /// Returns the author date in the RFC 3339 format, in the author time zone.
+    pub fn date(&self) -> String {
+        let timestamp = Timestamp {
+            offset: Some(self.offset as i32),
+            ..Timestamp::from_unix(self.time + self.offset)
+        };
+        timestamp
+            .strftime("%FT%T%:z")
+            .expect("implementation error: invalid blame date pattern")
+    }
+}
+
+/// Runs `git blame` on the given file, and returns the last change of each of its lines.
+pub fn blame(file: &Path) -> io::Result<Vec<Rc<Blame>>> {
+    let dir = match file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["blame", "--porcelain", "--"])
+        .arg(file.file_name().unwrap_or(file.as_os_str()))
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(parse_porcelain(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// See the "porcelain format" section of git-blame(1): each line is introduced by a header
+// naming its commit, followed by the commit information the first time it appears.
+fn parse_porcelain(s: &str) -> Vec<Rc<Blame>> {
+    let mut commits: HashMap<&str, Rc<Blame>> = HashMap::new();
+    let mut lines = Vec::new();
+    let mut current: Option<(&str, Blame)> = None;
+
+    for line in s.lines() {
+        if line.starts_with('\t') {
+            let Some((commit, blame)) = current.take() else {
+                continue;
+            };
+            let blame = commits
+                .entry(commit)
+                .or_insert_with(|| Rc::new(blame))
+                .clone();
+            lines.push(blame);
+        } else if current.is_none() {
+            let commit = line.split(' ').next().unwrap_or_default();
+            current = Some((
+                commit,
+                Blame {
+                    commit: commit.to_string(),
+                    author: String::new(),
+                    author_mail: String::new(),
+                    time: 0,
+                    offset: 0,
+                },
+            ));
+        } else if let Some((_, ref mut blame)) = current {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "author" => blame.author = value.to_string(),
+                "author-mail" => {
+                    blame.author_mail = value.trim_matches(['<', '>']).to_string();
+                }
+                "author-time" => blame.time = value.parse().unwrap_or_default(),
+                "author-tz" => blame.offset = parse_tz(value).unwrap_or_default(),
+                _ => {}
+            }
+        }
+    }
+
+    lines
+}
+
+// +HHMM or -HHMM
+fn parse_tz(s: &str) -> Option<i64> {
+    let (sign, digits) = match s.split_at_checked(1)? {
+        ("+", digits) => (1, digits),
+        ("-", digits) => (-1, digits),
+        _ => return None,
+    };
+    if digits.len() != 4 {
+        return None;
+    }
+    let hours: i64 = digits[..2].parse().ok()?;
+    let minutes: i64 = digits[2..].parse().ok()?;
+    Some(sign * (hours * 3600 + minutes * 60))
+}
@@ -1,18 +1,152 @@
-use clap::Parser;
+mod baseline;
+mod blame;
+mod report;
+
+use baseline::{Baseline, Fingerprint};
+use blame::Blame;
+use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand, ValueEnum};
+use squeeze::cidr::{self, Network};
+use squeeze::codetag::{Fields, Mnemonic, Occurrence, Scanner, Tag};
+use squeeze::color::{self, CssColor, Space};
+use squeeze::datetime::{self, Duration, Timestamp};
+use squeeze::email::{self, Mailbox, Normalizer};
+use squeeze::ip::{self, Scope};
 use squeeze::{
-    cidr::Cidr, codetag::Codetag, color::Color, datetime::Datetime, email::Email, env::Env,
-    hash::Hash, ip::Ip, json::Json, jwt::Jwt, mac::Mac, mirror::Mirror, path::Path, phone::Phone,
-    semver::Semver, uri::URI, uuid::Uuid, Finder,
+    cidr::Cidr,
+    codetag::Codetag,
+    color::Color,
+    datetime::Datetime,
+    email::Email,
+    env::Env,
+    hash::Hash,
+    ip::Ip,
+    json::Json,
+    jwt::Jwt,
+    mac::Mac,
+    mirror::Mirror,
+    path::Path,
+    phone::Phone,
+    semver::{Semver, Version, VersionReq},
+    uri::URI,
+    uuid::Uuid,
+    Finder,
 };
+use squeeze::{clean::Cleaner, comment::Language, markup, rules::RuleError, uri};
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
-use std::io::{self, BufRead};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, IsTerminal};
 use std::process::ExitCode;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 const VERSION: &str = match option_env!("SQUEEZE_VERSION") {
     Some(v) => v,
     None => env!("CARGO_PKG_VERSION"),
 };
 
+#[derive(Clone, Copy, ValueEnum)]
+enum Input {
+    /// search the raw text, line by line
+    Text,
+    /// search the link targets of an HTML document
+    Html,
+    /// search the link targets of a Markdown document
+    Markdown,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// one result per line
+    Text,
+    /// a JSON array of objects describing the results
+    Json,
+    /// one JSON object per line describing a result
+    Jsonl,
+}
+
+#[derive(Clone)]
+enum Select {
+    Scheme,
+    Host,
+    Port,
+    Path,
+    Query,
+    Fragment,
+    Param(String),
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CodetagSort {
+    /// by ascending priority, the most urgent first
+    Priority,
+    /// by ascending origination date, the oldest first
+    Date,
+    /// by ascending due date, the most pressing first
+    Due,
+    /// by author
+    Author,
+    /// by mnemonic
+    Mnemonic,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ColorFormat {
+    /// #rrggbb, or #rrggbbaa if not opaque
+    Hex,
+    /// rgb(r g b), the components from 0 to 255
+    Rgb,
+    /// hsl(h s% l%)
+    Hsl,
+    /// oklch(l% c h)
+    Oklch,
+}
+
+#[derive(Clone)]
+enum DatetimeOutput {
+    Rfc3339,
+    Epoch,
+    Strftime(String),
+}
+
+#[derive(Clone)]
+enum TimeBound {
+    At(Timestamp),
+    // the number of seconds before now
+    Ago(i64),
+}
+
+impl TimeBound {
+    // Returns the bound in seconds since the Unix epoch.
+    fn unix(&self, now: i64, offset: i32) -> i64 {
+        match self {
+            // has a Unix time, its offset being assumed
+            TimeBound::At(timestamp) => timestamp.assume_offset(offset).unix().unwrap_or(now),
+            TimeBound::Ago(duration) => now - duration,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct PriorityFilter {
+    orderings: &'static [Ordering],
+    priority: u8,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Summarize the results of a finder
+    #[command(subcommand)]
+    Report(Report),
+}
+
+#[derive(Subcommand)]
+enum Report {
+    /// Summarize the codetags per category, per file and per author
+    Codetags(report::CodetagsReport),
+}
+
 #[derive(Parser)]
 #[command(
     name = "squeeze",
@@ -26,42 +160,266 @@ struct Opts {
     first: bool,
     #[arg(long = "open", help = "open the results")]
     open: bool,
+    #[arg(
+        long = "format",
+        value_enum,
+        default_value = "text",
+        help = "how to output the results"
+    )]
+    format: Format,
+    #[arg(
+        short = 'n',
+        long = "line-number",
+        help = "prefix the results with their line numbers, and their file names when searching files"
+    )]
+    line_number: bool,
+
+    // input
+    #[arg(
+        value_name = "FILE",
+        help = "the files to search, instead of the standard input"
+    )]
+    files: Vec<std::path::PathBuf>,
+    #[arg(
+        long = "input",
+        value_enum,
+        default_value = "text",
+        help = "how to interpret the input, the URI finder only searching the link targets of the HTML and Markdown documents, and the other finders their text with its entities decoded"
+    )]
+    input: Input,
+    #[arg(
+        long = "base-url",
+        value_parser = parse_base_url,
+        help = "resolve relative links against this absolute URL"
+    )]
+    base_url: Option<String>,
 
     // cidr
     #[arg(long = "cidr", help = "search for CIDR notation")]
     cidr: bool,
+    #[arg(
+        long = "cidr-masks",
+        help = "also search for IPv4 networks written with a netmask (10.0.0.0 255.255.255.0) or a wildcard mask (10.0.0.0 0.0.0.255), implies --cidr"
+    )]
+    cidr_masks: bool,
+    #[arg(
+        long = "cidr-ranges",
+        help = "also search for ranges of IPs (10.0.0.1-10.0.0.50), implies --cidr"
+    )]
+    cidr_ranges: bool,
+    #[arg(
+        long = "to-cidr",
+        help = "convert the networks found to canonical CIDR blocks (e.g. 10.0.0.0/24 for 10.0.0.0 255.255.255.0), the IPs found by --ip being left as is"
+    )]
+    to_cidr: bool,
+    #[arg(
+        long = "within",
+        value_name = "NETWORK",
+        value_parser = parse_network,
+        help = "only output the IPs and CIDRs part of this network (e.g. 10.0.0.0/8)"
+    )]
+    within: Vec<Network>,
+    #[arg(
+        long = "not-within",
+        value_name = "NETWORK",
+        value_parser = parse_network,
+        help = "only output the IPs and CIDRs not part of this network, or subtract it from the networks with --aggregate"
+    )]
+    not_within: Vec<Network>,
+    #[arg(
+        long = "aggregate",
+        help = "output the minimal list of networks covering the IPs and CIDRs found, once the whole input has been read"
+    )]
+    aggregate: bool,
 
     // codetag
-    #[arg(long = "codetag", help = "search for codetags")]
+    #[arg(
+        long = "codetag",
+        value_name = "MNEMONIC",
+        value_parser = parse_mnemonics,
+        help = "search for codetags, optionally only the given comma-separated mnemonics or categories (e.g. 'fixme,note')"
+    )]
     mnemonic: Option<Option<String>>,
+    #[arg(
+        long = "codetag-define",
+        value_name = "[CATEGORY=]MNEMONIC[:?]",
+        value_parser = parse_mnemonic,
+        help = "search for this mnemonic in addition to the default ones, as part of a category (e.g. 'note=SAFETY'), the colon following it being optional with ':?'"
+    )]
+    codetag_define: Vec<Mnemonic>,
     #[arg(
         long = "hide-mnemonic",
         help = "whether to show the mnemonics in the results"
     )]
     hide_mnemonic: bool,
-    #[arg(long = "fixme", help = "alias for: --codetag=fixme")]
+    #[arg(
+        long = "codetag-author",
+        value_name = "AUTHOR",
+        help = "only output the codetags with this author"
+    )]
+    codetag_author: Option<String>,
+    #[arg(
+        long = "codetag-priority",
+        value_name = "[OP]PRIORITY",
+        value_parser = parse_priority_filter,
+        help = "only output the codetags with a matching priority (e.g. '<=2')"
+    )]
+    codetag_priority: Option<PriorityFilter>,
+    #[arg(
+        long = "codetag-sort",
+        value_enum,
+        help = "sort the codetags, once the whole input has been read"
+    )]
+    codetag_sort: Option<CodetagSort>,
+    #[arg(
+        long = "codetag-continuation",
+        help = "join the comment lines continuing a codetag"
+    )]
+    codetag_continuation: bool,
+    #[arg(
+        long = "codetag-blame",
+        help = "attach the author, commit and date of the last change of the codetags found in git repositories (see --format)"
+    )]
+    codetag_blame: bool,
+    #[arg(
+        long = "older-than",
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        help = "only output the codetags last changed before this duration (e.g. 180d, 12w, 1y), implies --codetag-blame"
+    )]
+    older_than: Option<i64>,
+    #[arg(
+        long = "baseline",
+        value_name = "FILE",
+        help = "only output the codetags missing from this baseline, and fail if there are any (see --write-baseline)"
+    )]
+    baseline: Option<std::path::PathBuf>,
+    #[arg(
+        long = "write-baseline",
+        value_name = "FILE",
+        help = "record the codetags found into this baseline"
+    )]
+    write_baseline: Option<std::path::PathBuf>,
+    #[arg(
+        long = "lang",
+        value_parser = parse_lang,
+        help = "only search the comments of this language for codetags (detected from the file extensions by default)"
+    )]
+    lang: Option<&'static Language>,
+    #[arg(
+        long = "fixme",
+        help = "alias for: --codetag=fixme (FIXME, XXX, BROKEN, etc)"
+    )]
     fixme: bool,
-    #[arg(long = "todo", help = "alias for: --codetag=todo")]
+    #[arg(
+        long = "todo",
+        help = "alias for: --codetag=todo (TODO, MILESTONE, TBD, etc)"
+    )]
     todo: bool,
 
     // color
     #[arg(long = "color", help = "search for colors")]
     color: bool,
+    #[arg(
+        long = "color-names",
+        help = "also search for the named colors (e.g. rebeccapurple), transparent and currentColor, implies --color"
+    )]
+    color_names: bool,
+    #[arg(
+        long = "color-format",
+        value_name = "FORMAT",
+        help = "output the colors in this format, mapped into the sRGB gamut but for oklch, implies --color"
+    )]
+    color_format: Option<ColorFormat>,
+    #[arg(
+        long = "palette",
+        require_equals = true,
+        num_args = 0..=1,
+        default_missing_value = "2",
+        value_name = "DELTA_E",
+        help = "output the palette of the colors found once the whole input has been read, with swatches in a terminal: the near-duplicates within this perceptual distance (CIEDE2000, 2 by default) are merged, the most frequent first, implies --color"
+    )]
+    palette: Option<f64>,
 
     // datetime
-    #[arg(long = "datetime", help = "search for datetimes")]
-    datetime: bool,
+    #[arg(
+        long = "datetime",
+        require_equals = true,
+        num_args = 0..=1,
+        value_delimiter = ',',
+        value_name = "FORMAT",
+        value_parser = parse_datetime_format,
+        help = "search for datetimes, optionally only in the given comma-separated formats: iso, rfc2822, syslog, clf, epoch, or duration (all but epoch and duration by default)"
+    )]
+    datetime: Option<Vec<datetime::Format>>,
+    #[arg(
+        long = "datetime-format",
+        value_name = "FORMAT",
+        value_parser = parse_datetime_output,
+        help = "output the datetimes in UTC, in this format: rfc3339, epoch, or a strftime pattern (e.g. '%F %T'); the datetimes without a UTC offset are output as is, see --assume-tz"
+    )]
+    datetime_format: Option<DatetimeOutput>,
+    #[arg(
+        long = "since",
+        value_name = "DATETIME",
+        allow_hyphen_values = true,
+        value_parser = parse_time_bound,
+        help = "only output the datetimes from this one, absolute (e.g. 2024-01-15T10:00:00Z) or relative to now (e.g. -2h); the datetimes without a UTC offset are discarded, see --assume-tz"
+    )]
+    since: Option<TimeBound>,
+    #[arg(
+        long = "until",
+        value_name = "DATETIME",
+        allow_hyphen_values = true,
+        value_parser = parse_time_bound,
+        help = "only output the datetimes before this one, absolute (e.g. 2024-01-15) or relative to now (e.g. -30m); the datetimes without a UTC offset are discarded, see --assume-tz"
+    )]
+    until: Option<TimeBound>,
+    #[arg(
+        long = "assume-tz",
+        value_name = "OFFSET",
+        allow_hyphen_values = true,
+        value_parser = parse_offset,
+        help = "assume this UTC offset (e.g. Z, +02:00, -0500 or EST) for the datetimes without one, and for the bounds of --since and --until (UTC by default)"
+    )]
+    assume_tz: Option<i32>,
 
     // email
     #[arg(long = "email", help = "search for email addresses")]
     email: bool,
+    #[arg(
+        long = "email-rfc5322",
+        help = "search for the mailboxes of the mail headers, with their display names, comments and quoted local parts (e.g. '\"Smith, John\" <john@example.com>'), implies --email"
+    )]
+    email_rfc5322: bool,
+    #[arg(
+        long = "email-obfuscated",
+        help = "also search for the obfuscated email addresses (e.g. 'john at example dot com' or 'john[at]example[dot]com'), and output their address, implies --email"
+    )]
+    email_obfuscated: bool,
+    #[arg(
+        long = "normalize",
+        help = "output the normalized addresses of the emails, e.g. to dedupe them: with lowercase domains, and the rules of their providers applied (e.g. the case, subaddress (+tag) and dots ignored by Gmail)"
+    )]
+    normalize: bool,
+    #[arg(
+        long = "normalize-rules",
+        value_name = "FILE",
+        help = "add the email normalization rules of this file to the embedded ones (implies --normalize)"
+    )]
+    normalize_rules: Vec<std::path::PathBuf>,
 
     // env
     #[arg(long = "env", help = "search for environment variables")]
     env: bool,
 
     // hash
-    #[arg(long = "hash", help = "search for hashes")]
+    #[arg(
+        long = "hash",
+        value_name = "ALGORITHM",
+        value_parser = parse_algorithms,
+        help = "search for hashes, optionally only the given comma-separated algorithms (e.g. 'md5,sha256')"
+    )]
     hash_algo: Option<Option<String>>,
     #[arg(long = "md5", help = "alias for: --hash=md5")]
     md5: bool,
@@ -79,6 +437,24 @@ struct Opts {
     ipv4: bool,
     #[arg(long = "ipv6", help = "search for IPv6 addresses")]
     ipv6: bool,
+    #[arg(
+        long = "ip-scope",
+        value_name = "SCOPE",
+        value_delimiter = ',',
+        value_parser = parse_scope,
+        help = "only output the IPs, CIDRs and URIs whose addresses are of these scopes: loopback, private, link-local, multicast, documentation, cgnat, reserved, or public"
+    )]
+    ip_scope: Vec<Scope>,
+    #[arg(
+        long = "ip-canonical",
+        help = "output the IPs in their canonical form, without brackets and as recommended by RFC 5952 for IPv6 (e.g. 2001:db8::1 for [2001:DB8:0:0::1])"
+    )]
+    ip_canonical: bool,
+    #[arg(
+        long = "ip-unmap",
+        help = "output the IPv4-mapped IPv6 addresses as IPv4 addresses (e.g. 192.0.2.1 for ::ffff:192.0.2.1), implies --ip-canonical"
+    )]
+    ip_unmap: bool,
 
     // json
     #[arg(long = "json", help = "search for JSON objects and arrays")]
@@ -107,9 +483,31 @@ struct Opts {
     // semver
     #[arg(long = "semver", help = "search for semantic versions")]
     semver: bool,
+    #[arg(
+        long = "semver-sort",
+        help = "output the semantic versions sorted by precedence once the whole input has been read, the invalid ones (e.g. 01.2.3) first, implies --semver"
+    )]
+    semver_sort: bool,
+    #[arg(
+        long = "semver-max",
+        help = "only output the greatest semantic version once the whole input has been read, implies --semver"
+    )]
+    semver_max: bool,
+    #[arg(
+        long = "semver-matches",
+        value_name = "REQ",
+        value_parser = parse_version_req,
+        help = "only output the semantic versions matching this requirement, in the Cargo or npm syntax (e.g. '^1.2', '~1.2.3', '>=1, <2', '1.x' or '<1 || >=2'), implies --semver"
+    )]
+    semver_matches: Option<VersionReq>,
 
     // uri
-    #[arg(long = "uri", help = "search for uris")]
+    #[arg(
+        long = "uri",
+        value_name = "SCHEME",
+        value_parser = parse_schemes,
+        help = "search for uris, optionally only the given comma-separated schemes (e.g. 'https,ftp')"
+    )]
     scheme: Option<Option<String>>,
     #[arg(
         long = "strict",
@@ -121,6 +519,23 @@ struct Opts {
         help = "alias for: --uri=data,ftp,ftps,http,https,mailto,sftp,ws,wss"
     )]
     url: bool,
+    #[arg(
+        long = "clean",
+        help = "remove the tracking parameters and unwrap the redirectors of the uris"
+    )]
+    clean: bool,
+    #[arg(
+        long = "clean-rules",
+        value_name = "FILE",
+        help = "add the cleaning rules of this file to the embedded ones (implies --clean)"
+    )]
+    clean_rules: Vec<std::path::PathBuf>,
+    #[arg(
+        long = "select",
+        value_parser = parse_select,
+        help = "only output a component of the uris: scheme, host, port, path, query, fragment, or param:NAME"
+    )]
+    select: Option<Select>,
     #[arg(long = "http", help = "alias for: --uri=http")]
     http: bool,
     #[arg(long = "https", help = "alias for: --uri=https")]
@@ -129,17 +544,23 @@ struct Opts {
     // uuid
     #[arg(long = "uuid", help = "search for UUIDs")]
     uuid: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
 impl TryFrom<&Opts> for Cidr {
     type Error = ();
 
     fn try_from(opts: &Opts) -> Result<Self, Self::Error> {
-        if !opts.cidr {
+        if !(opts.cidr || opts.cidr_masks || opts.cidr_ranges) {
             return Err(());
         }
 
-        Ok(Cidr::default())
+        Ok(Cidr {
+            masks: opts.cidr_masks,
+            ranges: opts.cidr_ranges,
+        })
     }
 }
 
@@ -147,11 +568,17 @@ impl TryFrom<&Opts> for Color {
     type Error = ();
 
     fn try_from(opts: &Opts) -> Result<Self, Self::Error> {
-        if !opts.color {
+        if !(opts.color
+            || opts.color_names
+            || opts.color_format.is_some()
+            || opts.palette.is_some())
+        {
             return Err(());
         }
 
-        Ok(Color::default())
+        Ok(Color {
+            names: opts.color_names,
+        })
     }
 }
 
@@ -159,11 +586,15 @@ impl TryFrom<&Opts> for Datetime {
     type Error = ();
 
     fn try_from(opts: &Opts) -> Result<Self, Self::Error> {
-        if !opts.datetime {
+        let Some(ref formats) = opts.datetime else {
             return Err(());
-        }
+        };
 
-        Ok(Datetime::default())
+        let mut finder = Datetime::default();
+        for format in formats {
+            finder.add_format(*format);
+        }
+        Ok(finder)
     }
 }
 
@@ -171,11 +602,14 @@ impl TryFrom<&Opts> for Email {
     type Error = ();
 
     fn try_from(opts: &Opts) -> Result<Self, Self::Error> {
-        if !opts.email {
+        if !(opts.email || opts.email_rfc5322 || opts.email_obfuscated) {
             return Err(());
         }
 
-        Ok(Email::default())
+        Ok(Email {
+            rfc5322: opts.email_rfc5322,
+            obfuscated: opts.email_obfuscated,
+        })
     }
 }
 
@@ -282,6 +716,9 @@ impl TryFrom<&Opts> for Codetag {
 
         let mut finder = Codetag::default();
         finder.hide_mnemonic = opts.hide_mnemonic;
+        for mnemonic in &opts.codetag_define {
+            finder.define(mnemonic.clone());
+        }
         if let Some(Some(ref mnemonic)) = opts.mnemonic {
             for m in mnemonic.split(',') {
                 finder.add_mnemonic(m);
@@ -340,7 +777,7 @@ impl TryFrom<&Opts> for Semver {
     type Error = ();
 
     fn try_from(opts: &Opts) -> Result<Self, Self::Error> {
-        if !opts.semver {
+        if !(opts.semver || opts.semver_sort || opts.semver_max || opts.semver_matches.is_some()) {
             return Err(());
         }
 
@@ -357,7 +794,9 @@ impl TryFrom<&Opts> for URI {
         }
 
         let mut finder = URI::default();
-        finder.strict = opts.strict;
+        // link targets extracted from markup are already delimited
+        finder.strict = opts.strict || !matches!(opts.input, Input::Text);
+        finder.relative = opts.base_url.is_some();
         if let Some(Some(ref scheme)) = opts.scheme {
             for s in scheme.split(',') {
                 finder.add_scheme(s);
@@ -396,10 +835,904 @@ impl TryFrom<&Opts> for Uuid {
     }
 }
 
+/// A result, along with where it has been found.
+struct Found {
+    finder: &'static str,
+    text: String,
+    file: Option<Rc<std::path::Path>>,
+    // 0 when the result does not come from the input lines (e.g. an aggregated network)
+    line: usize,
+    end_line: usize,
+    tag: Option<Tag>,
+    blame: Option<Rc<Blame>>,
+    swatch: Option<Swatch>,
+}
+
+/// A color of a palette, merging near-duplicate colors.
+struct Swatch {
+    color: CssColor,
+    // the number of occurrences of its colors
+    count: usize,
+    // its distinct colors, the most frequent first
+    colors: Vec<String>,
+}
+
+impl Found {
+    // `assume_tz` is the offset of the datetimes without one, see --assume-tz
+    fn to_json(&self, assume_tz: Option<i32>) -> serde_json::Value {
+        let mut value = serde_json::json!({
+            "finder": self.finder,
+            "text": self.text,
+            "file": self.file.as_ref().map(|file| file.display().to_string()),
+            "line": self.line,
+            "end_line": self.end_line,
+        });
+        if let Some(ref tag) = self.tag {
+            value["mnemonic"] = tag.mnemonic.clone().into();
+            value["description"] = tag.description.clone().into();
+            value["fields"] = fields_json(&tag.fields);
+        }
+        if self.finder == "color" {
+            if let Some(color) = CssColor::parse(&self.text) {
+                let contrast = |other: &str| {
+                    let other = CssColor::parse(other).unwrap_or(color);
+                    (color.contrast(&other) * 100.0).round() / 100.0
+                };
+                value["hex"] = color.to_hex().into();
+                value["contrast"] = serde_json::json!({
+                    "white": contrast("white"),
+                    "black": contrast("black"),
+                });
+            }
+        }
+        if let Some(ref swatch) = self.swatch {
+            value["count"] = swatch.count.into();
+            value["colors"] = swatch.colors.clone().into();
+        }
+        if self.finder == "datetime" {
+            if let Some(timestamp) = Timestamp::parse(&self.text) {
+                let timestamp = match assume_tz {
+                    Some(offset) => timestamp.assume_offset(offset),
+                    None => timestamp,
+                };
+                value["unix"] = timestamp.unix().into();
+            } else if let Some(duration) = Duration::parse(&self.text) {
+                value["seconds"] = duration.seconds().into();
+            }
+        }
+        if self.finder == "email" {
+            if let Some(mailbox) = Mailbox::parse(&self.text) {
+                value["name"] = mailbox.name.clone().into();
+                value["address"] = mailbox.address().into();
+                value["local"] = mailbox.local.into();
+                value["domain"] = mailbox.domain.into();
+            }
+        }
+        if let Some(ref blame) = self.blame {
+            value["blame"] = serde_json::json!({
+                "commit": blame.commit,
+                "author": blame.author,
+                "author_mail": blame.author_mail,
+                "date": blame.date(),
+            });
+        }
+        value
+    }
+}
+
+fn fields_json(fields: &Fields) -> serde_json::Value {
+    serde_json::json!({
+        "authors": fields.authors,
+        "date": fields.date,
+        "due": fields.due,
+        "priority": fields.priority,
+        "ticket": fields.ticket,
+        "tracker": fields.tracker,
+        "status": fields.status,
+        "category": fields.category,
+    })
+}
+
+/// Searches the input, line by line, for the results of a finder.
+trait Extractor {
+    fn id(&self) -> &'static str;
+
+    /// Signals the start of a new document, read from the given file or from the standard input.
+    fn begin(&mut self, file: Option<&std::path::Path>);
+
+    /// Returns the results found in the given line (1-based).
+    fn extract(&mut self, number: usize, line: &str) -> Vec<Found>;
+
+    /// Signals the end of the current document, returns the results it still holds.
+    fn end(&mut self) -> Vec<Found> {
+        Vec::new()
+    }
+
+    /// Returns the results held back until the end of the input.
+    fn finish(&mut self) -> Vec<Found> {
+        Vec::new()
+    }
+}
+
+/// Extracts the results of any finder, and runs them through its post-processing stages.
+struct FinderExtractor<'a> {
+    finder: &'a dyn Finder,
+    stages: Vec<Stage>,
+    file: Option<Rc<std::path::Path>>,
+}
+
+impl Extractor for FinderExtractor<'_> {
+    fn id(&self) -> &'static str {
+        self.finder.id()
+    }
+
+    fn begin(&mut self, file: Option<&std::path::Path>) {
+        self.file = file.map(Rc::from);
+    }
+
+    fn extract(&mut self, number: usize, line: &str) -> Vec<Found> {
+        let mut results = Vec::new();
+        let mut idx = 0;
+        while idx < line.len() {
+            let segment = &line[idx..];
+            log::debug!("[{}] searching in \"{}\"", self.id(), segment);
+            let Some(range) = self.finder.find(segment) else {
+                break;
+            };
+            log::debug!("[{}] found at [{};{}[", self.id(), range.start, range.end);
+            idx += range.end;
+            let found = segment[range].trim();
+            if found.is_empty() {
+                continue;
+            }
+            let mut texts = vec![found.to_string()];
+            for stage in &self.stages {
+                texts = texts.iter().flat_map(|text| stage(text)).collect();
+            }
+            results.extend(texts.into_iter().map(|text| Found {
+                finder: self.finder.id(),
+                text,
+                file: self.file.clone(),
+                line: number,
+                end_line: number,
+                tag: None,
+                blame: None,
+                swatch: None,
+            }));
+        }
+        results
+    }
+}
+
+/// Extracts the link targets of an HTML or Markdown document, and gives them to another extractor
+/// (the URI one) once the whole document has been read.
+struct LinksExtractor<'a> {
+    extractor: Box<dyn Extractor + 'a>,
+    input: Input,
+    base_url: Option<String>,
+    doc: String,
+}
+
+impl Extractor for LinksExtractor<'_> {
+    fn id(&self) -> &'static str {
+        self.extractor.id()
+    }
+
+    fn begin(&mut self, file: Option<&std::path::Path>) {
+        self.doc.clear();
+        self.extractor.begin(file);
+    }
+
+    fn extract(&mut self, _: usize, line: &str) -> Vec<Found> {
+        self.doc.push_str(line);
+        self.doc.push('\n');
+        Vec::new()
+    }
+
+    fn end(&mut self) -> Vec<Found> {
+        let base = self.base_url.as_deref();
+        let links = match self.input {
+            Input::Html => markup::html_links(&self.doc, base),
+            _ => markup::markdown_links(&self.doc, base),
+        };
+        let mut found: Vec<_> = (1..)
+            .zip(links)
+            .flat_map(|(number, link)| self.extractor.extract(number, &link))
+            .collect();
+        found.extend(self.extractor.end());
+        found
+    }
+
+    fn finish(&mut self) -> Vec<Found> {
+        self.extractor.finish()
+    }
+}
+
+/// Gives the lines of an HTML or Markdown document to another extractor, their entities being
+/// decoded (e.g. `&amp;`).
+struct DecodeExtractor<'a> {
+    extractor: Box<dyn Extractor + 'a>,
+}
+
+impl Extractor for DecodeExtractor<'_> {
+    fn id(&self) -> &'static str {
+        self.extractor.id()
+    }
+
+    fn begin(&mut self, file: Option<&std::path::Path>) {
+        self.extractor.begin(file);
+    }
+
+    fn extract(&mut self, number: usize, line: &str) -> Vec<Found> {
+        self.extractor
+            .extract(number, &markup::decode_entities(line))
+    }
+
+    fn end(&mut self) -> Vec<Found> {
+        self.extractor.end()
+    }
+
+    fn finish(&mut self) -> Vec<Found> {
+        self.extractor.finish()
+    }
+}
+
+/// Extracts the codetags, filtering and sorting them on their fields.
+struct CodetagExtractor<'a> {
+    finder: &'a Codetag,
+    lang: Option<&'static Language>,
+    continuation: bool,
+    scanner: Option<Scanner<'a>>,
+    file: Option<Rc<std::path::Path>>,
+    blame: bool,
+    blames: Vec<Rc<Blame>>,
+    author: Option<String>,
+    priority: Option<PriorityFilter>,
+    // the time before which the codetags must have been last changed
+    changed_before: Option<i64>,
+    sort: Option<CodetagSort>,
+    held: Vec<Found>,
+}
+
+impl<'a> CodetagExtractor<'a> {
+    fn new(finder: &'a Codetag, opts: &Opts) -> Self {
+        CodetagExtractor {
+            finder,
+            lang: opts.lang,
+            continuation: opts.codetag_continuation,
+            scanner: None,
+            file: None,
+            blame: opts.codetag_blame || opts.older_than.is_some(),
+            blames: Vec::new(),
+            author: opts.codetag_author.clone(),
+            priority: opts.codetag_priority.clone(),
+            changed_before: opts.older_than.map(|duration| {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |now| now.as_secs() as i64);
+                now - duration
+            }),
+            sort: opts.codetag_sort,
+            held: Vec::new(),
+        }
+    }
+
+    fn is_selected(&self, tag: &Tag) -> bool {
+        if let Some(ref author) = self.author {
+            if !tag
+                .fields
+                .authors
+                .iter()
+                .any(|a| a.eq_ignore_ascii_case(author))
+            {
+                return false;
+            }
+        }
+        if let Some(ref filter) = self.priority {
+            match tag.fields.priority {
+                Some(p) if filter.orderings.contains(&p.cmp(&filter.priority)) => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    fn select(&mut self, occurrences: impl IntoIterator<Item = Occurrence>) -> Vec<Found> {
+        let mut results = Vec::new();
+        for occurrence in occurrences {
+            if !self.is_selected(&occurrence.tag) {
+                continue;
+            }
+            let blame = self.blames.get(occurrence.line - 1).cloned();
+            if let Some(changed_before) = self.changed_before {
+                match blame {
+                    Some(ref blame) if blame.time < changed_before => {}
+                    _ => continue,
+                }
+            }
+            let found = Found {
+                finder: self.finder.id(),
+                text: occurrence.text,
+                file: self.file.clone(),
+                line: occurrence.line,
+                end_line: occurrence.end_line,
+                tag: Some(occurrence.tag),
+                blame,
+                swatch: None,
+            };
+            if self.sort.is_some() {
+                self.held.push(found);
+            } else {
+                results.push(found);
+            }
+        }
+        results
+    }
+}
+
+impl<'a> Extractor for CodetagExtractor<'a> {
+    fn id(&self) -> &'static str {
+        self.finder.id()
+    }
+
+    fn begin(&mut self, file: Option<&std::path::Path>) {
+        // without a known language, the whole lines are searched
+        let language = self.lang.or_else(|| file.and_then(Language::from_path));
+        let mut scanner = Scanner::new(self.finder, language);
+        scanner.continuation = self.continuation;
+        self.scanner = Some(scanner);
+        self.file = file.map(Rc::from);
+        self.blames = match file {
+            Some(file) if self.blame => blame::blame(file).unwrap_or_else(|e| {
+                log::debug!("failed to blame '{}': {}", file.display(), e);
+                Vec::new()
+            }),
+            _ => Vec::new(),
+        };
+    }
+
+    fn extract(&mut self, _number: usize, line: &str) -> Vec<Found> {
+        let occurrences = match self.scanner {
+            Some(ref mut scanner) => scanner.scan_line(line),
+            None => Vec::new(),
+        };
+        self.select(occurrences)
+    }
+
+    fn end(&mut self) -> Vec<Found> {
+        let occurrence = self.scanner.take().and_then(|mut scanner| scanner.finish());
+        self.select(occurrence)
+    }
+
+    fn finish(&mut self) -> Vec<Found> {
+        let mut held = std::mem::take(&mut self.held);
+        if let Some(sort) = self.sort {
+            // the codetags missing the sort field come last
+            fn missing_last<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+                match (a, b) {
+                    (Some(a), Some(b)) => a.cmp(&b),
+                    (a, b) => b.is_some().cmp(&a.is_some()),
+                }
+            }
+            held.sort_by(|a, b| {
+                // the codetag results always carry their tag
+                let (a, b) = (a.tag.as_ref().unwrap(), b.tag.as_ref().unwrap());
+                match sort {
+                    CodetagSort::Priority => missing_last(a.fields.priority, b.fields.priority),
+                    CodetagSort::Date => {
+                        missing_last(a.fields.date.as_ref(), b.fields.date.as_ref())
+                    }
+                    CodetagSort::Due => missing_last(a.fields.due.as_ref(), b.fields.due.as_ref()),
+                    CodetagSort::Author => {
+                        missing_last(a.fields.authors.first(), b.fields.authors.first())
+                    }
+                    CodetagSort::Mnemonic => {
+                        a.mnemonic.to_uppercase().cmp(&b.mnemonic.to_uppercase())
+                    }
+                }
+            });
+        }
+        held
+    }
+}
+
+/// Merges the networks found by other extractors, and returns them once the whole input has
+/// been read.
+struct AggregateExtractor<'a> {
+    extractors: Vec<Box<dyn Extractor + 'a>>,
+    networks: Vec<Network>,
+    excluded: Vec<Network>,
+}
+
+impl AggregateExtractor<'_> {
+    fn collect(&mut self, found: Vec<Found>) -> Vec<Found> {
+        self.networks.extend(
+            found
+                .iter()
+                .flat_map(|found| cidr::parse_networks(&found.text)),
+        );
+        Vec::new()
+    }
+}
+
+impl Extractor for AggregateExtractor<'_> {
+    fn id(&self) -> &'static str {
+        "cidr"
+    }
+
+    fn begin(&mut self, file: Option<&std::path::Path>) {
+        for extractor in self.extractors.iter_mut() {
+            extractor.begin(file);
+        }
+    }
+
+    fn extract(&mut self, number: usize, line: &str) -> Vec<Found> {
+        let found: Vec<_> = self
+            .extractors
+            .iter_mut()
+            .flat_map(|extractor| extractor.extract(number, line))
+            .collect();
+        self.collect(found)
+    }
+
+    fn end(&mut self) -> Vec<Found> {
+        let found: Vec<_> = self.extractors.iter_mut().flat_map(|e| e.end()).collect();
+        self.collect(found)
+    }
+
+    fn finish(&mut self) -> Vec<Found> {
+        let found: Vec<_> = self
+            .extractors
+            .iter_mut()
+            .flat_map(|e| e.finish())
+            .collect();
+        self.collect(found);
+        let networks = std::mem::take(&mut self.networks);
+        cidr::subtract(networks, self.excluded.iter().copied())
+            .into_iter()
+            .map(|network| Found {
+                finder: self.id(),
+                text: network.to_string(),
+                file: None,
+                line: 0,
+                end_line: 0,
+                tag: None,
+                blame: None,
+                swatch: None,
+            })
+            .collect()
+    }
+}
+
+/// Merges the near-duplicate colors found by other extractors, and returns their palette once
+/// the whole input has been read.
+struct PaletteExtractor<'a> {
+    extractors: Vec<Box<dyn Extractor + 'a>>,
+    max_delta_e: f64,
+    // the distinct colors, in the order they have been found, with their number of occurrences
+    colors: Vec<(String, usize)>,
+    indexes: HashMap<String, usize>,
+}
+
+impl PaletteExtractor<'_> {
+    fn collect(&mut self, found: Vec<Found>) -> Vec<Found> {
+        for found in found {
+            match self.indexes.get(&found.text) {
+                Some(&i) => self.colors[i].1 += 1,
+                None => {
+                    self.indexes.insert(found.text.clone(), self.colors.len());
+                    self.colors.push((found.text, 1));
+                }
+            }
+        }
+        Vec::new()
+    }
+}
+
+impl Extractor for PaletteExtractor<'_> {
+    fn id(&self) -> &'static str {
+        "color"
+    }
+
+    fn begin(&mut self, file: Option<&std::path::Path>) {
+        for extractor in self.extractors.iter_mut() {
+            extractor.begin(file);
+        }
+    }
+
+    fn extract(&mut self, number: usize, line: &str) -> Vec<Found> {
+        let found: Vec<_> = self
+            .extractors
+            .iter_mut()
+            .flat_map(|extractor| extractor.extract(number, line))
+            .collect();
+        self.collect(found)
+    }
+
+    fn end(&mut self) -> Vec<Found> {
+        let found: Vec<_> = self.extractors.iter_mut().flat_map(|e| e.end()).collect();
+        self.collect(found)
+    }
+
+    fn finish(&mut self) -> Vec<Found> {
+        let found: Vec<_> = self
+            .extractors
+            .iter_mut()
+            .flat_map(|e| e.finish())
+            .collect();
+        self.collect(found);
+
+        // the most frequent colors stand for their near-duplicates, e.g. currentColor is left out
+        let mut colors: Vec<_> = std::mem::take(&mut self.colors)
+            .into_iter()
+            .filter_map(|(text, count)| Some((CssColor::parse(&text)?, text, count)))
+            .collect();
+        colors.sort_by_key(|(_, _, count)| Reverse(*count));
+        let values: Vec<_> = colors.iter().map(|(color, _, _)| *color).collect();
+        let mut swatches: Vec<_> = color::cluster(&values, self.max_delta_e)
+            .into_iter()
+            .map(|group| Swatch {
+                color: values[group[0]],
+                count: group.iter().map(|&i| colors[i].2).sum(),
+                colors: group.iter().map(|&i| colors[i].1.clone()).collect(),
+            })
+            .collect();
+        swatches.sort_by_key(|swatch| Reverse(swatch.count));
+
+        swatches
+            .into_iter()
+            .map(|swatch| Found {
+                finder: self.id(),
+                text: swatch.colors[0].clone(),
+                file: None,
+                line: 0,
+                end_line: 0,
+                tag: None,
+                blame: None,
+                swatch: Some(swatch),
+            })
+            .collect()
+    }
+}
+
+/// Sorts the semantic versions found by other extractors by precedence, and returns them (or
+/// only the greatest one) once the whole input has been read.
+struct VersionExtractor<'a> {
+    extractors: Vec<Box<dyn Extractor + 'a>>,
+    max: bool,
+    found: Vec<Found>,
+}
+
+impl VersionExtractor<'_> {
+    fn collect(&mut self, found: Vec<Found>) -> Vec<Found> {
+        self.found.extend(found);
+        Vec::new()
+    }
+}
+
+impl Extractor for VersionExtractor<'_> {
+    fn id(&self) -> &'static str {
+        "semver"
+    }
+
+    fn begin(&mut self, file: Option<&std::path::Path>) {
+        for extractor in self.extractors.iter_mut() {
+            extractor.begin(file);
+        }
+    }
+
+    fn extract(&mut self, number: usize, line: &str) -> Vec<Found> {
+        let found: Vec<_> = self
+            .extractors
+            .iter_mut()
+            .flat_map(|extractor| extractor.extract(number, line))
+            .collect();
+        self.collect(found)
+    }
+
+    fn end(&mut self) -> Vec<Found> {
+        let found: Vec<_> = self.extractors.iter_mut().flat_map(|e| e.end()).collect();
+        self.collect(found)
+    }
+
+    fn finish(&mut self) -> Vec<Found> {
+        let found: Vec<_> = self
+            .extractors
+            .iter_mut()
+            .flat_map(|e| e.finish())
+            .collect();
+        self.collect(found);
+
+        // the sort is stable, the versions of equal precedence stay in the order they were found
+        let mut versions: Vec<_> = std::mem::take(&mut self.found)
+            .into_iter()
+            .map(|found| (Version::parse(&found.text), found))
+            .collect();
+        versions.sort_by(|(a, _), (b, _)| match (a, b) {
+            (Some(a), Some(b)) => a.precedence(b),
+            _ => a.is_some().cmp(&b.is_some()),
+        });
+        if self.max {
+            let max = versions.pop().filter(|(version, _)| version.is_some());
+            return max.into_iter().map(|(_, found)| found).collect();
+        }
+        versions.into_iter().map(|(_, found)| found).collect()
+    }
+}
+
+/// A post-processing step applied to the results of a finder: it rewrites a result into any
+/// number of results, none to discard it.
+type Stage = Box<dyn Fn(&str) -> Vec<String>>;
+
+// Whether all the addresses of the found network are part of the given ones.
+fn is_within(found: &str, networks: &[Network]) -> Option<bool> {
+    let found = cidr::parse_networks(found);
+    if found.is_empty() {
+        return None;
+    }
+    Some(found.iter().all(|f| networks.iter().any(|n| n.contains(f))))
+}
+
+fn ip_stages(opts: &Opts) -> Vec<Stage> {
+    let mut stages: Vec<Stage> = Vec::new();
+    if opts.ip_canonical || opts.ip_unmap {
+        let unmap = opts.ip_unmap;
+        stages.push(Box::new(move |found| {
+            vec![ip::canonical(found, unmap).unwrap_or_else(|| found.to_string())]
+        }));
+    }
+    stages
+}
+
+fn color_stages(opts: &Opts) -> Vec<Stage> {
+    let mut stages: Vec<Stage> = Vec::new();
+    if let Some(format) = opts.color_format {
+        stages.push(Box::new(move |found| {
+            let Some(color) = CssColor::parse(found) else {
+                return vec![found.to_string()];
+            };
+            vec![match format {
+                ColorFormat::Hex => color.to_hex(),
+                ColorFormat::Rgb => color.to_gamut().to_string(),
+                ColorFormat::Hsl => color.to_gamut().convert(Space::Hsl).to_string(),
+                ColorFormat::Oklch => color.convert(Space::Oklch).to_string(),
+            }]
+        }));
+    }
+    stages
+}
+
+// Adds the rules of the given files to the embedded ones, exiting on the first invalid rule.
+fn add_rules_files(
+    paths: &[std::path::PathBuf],
+    mut add_rules: impl FnMut(&str) -> Result<(), RuleError>,
+) {
+    for path in paths {
+        let rules = std::fs::read_to_string(path).unwrap_or_else(|e| {
+            Opts::command()
+                .error(ErrorKind::Io, format!("{}: {}", path.display(), e))
+                .exit()
+        });
+        if let Err(e) = add_rules(&rules) {
+            Opts::command()
+                .error(
+                    ErrorKind::InvalidValue,
+                    format!("{}: {}", path.display(), e),
+                )
+                .exit()
+        }
+    }
+}
+
+fn email_stages(opts: &Opts) -> Vec<Stage> {
+    let mut stages: Vec<Stage> = Vec::new();
+    if opts.email_obfuscated {
+        stages.push(Box::new(|found| {
+            vec![email::deobfuscate(found).unwrap_or_else(|| found.to_string())]
+        }));
+    }
+    if opts.normalize || !opts.normalize_rules.is_empty() {
+        let mut normalizer = Normalizer::default();
+        add_rules_files(&opts.normalize_rules, |rules| normalizer.add_rules(rules));
+        stages.push(Box::new(move |found| {
+            vec![normalizer
+                .normalize(found)
+                .unwrap_or_else(|| found.to_string())]
+        }));
+    }
+    stages
+}
+
+fn datetime_stages(opts: &Opts) -> Vec<Stage> {
+    let mut stages: Vec<Stage> = Vec::new();
+    let parse = |found: &str, offset| {
+        let timestamp = Timestamp::parse(found)?;
+        Some(match offset {
+            Some(offset) => timestamp.assume_offset(offset),
+            None => timestamp,
+        })
+    };
+    let offset = opts.assume_tz;
+    if opts.since.is_some() || opts.until.is_some() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs() as i64);
+        let since = opts
+            .since
+            .as_ref()
+            .map(|bound| bound.unix(now, offset.unwrap_or(0)));
+        let until = opts
+            .until
+            .as_ref()
+            .map(|bound| bound.unix(now, offset.unwrap_or(0)));
+        stages.push(Box::new(move |found| {
+            match parse(found, offset).and_then(|t| t.unix()) {
+                Some(unix)
+                    if since.is_none_or(|since| since <= unix)
+                        && until.is_none_or(|until| unix < until) =>
+                {
+                    vec![found.to_string()]
+                }
+                _ => vec![],
+            }
+        }));
+    }
+    if let Some(ref output) = opts.datetime_format {
+        let output = output.clone();
+        stages.push(Box::new(move |found| {
+            let formatted = parse(found, offset)
+                .and_then(|t| t.to_utc())
+                .and_then(|utc| match output {
+                    DatetimeOutput::Rfc3339 => Some(utc.to_rfc3339()),
+                    DatetimeOutput::Epoch => {
+                        let seconds = utc.strftime("%s")?;
+                        // the fraction keeps the precision of the datetime
+                        Some(match utc.time {
+                            Some(time) if time.precision > 0 => {
+                                let fraction = format!("{:09}", time.nanosecond);
+                                let digits = (time.precision as usize).min(fraction.len());
+                                format!("{}.{}", seconds, &fraction[..digits])
+                            }
+                            _ => seconds,
+                        })
+                    }
+                    DatetimeOutput::Strftime(ref pattern) => utc.strftime(pattern),
+                });
+            vec![formatted.unwrap_or_else(|| found.to_string())]
+        }));
+    }
+    stages
+}
+
+fn cidr_stages(opts: &Opts) -> Vec<Stage> {
+    let mut stages: Vec<Stage> = Vec::new();
+    if opts.to_cidr {
+        stages.push(Box::new(|found| {
+            match cidr::parse_networks(found).as_slice() {
+                [] => vec![found.to_string()],
+                networks => networks.iter().map(Network::to_string).collect(),
+            }
+        }));
+    }
+    stages
+}
+
+fn network_stages(opts: &Opts) -> Vec<Stage> {
+    let mut stages: Vec<Stage> = Vec::new();
+    if !opts.within.is_empty() {
+        let within = opts.within.clone();
+        stages.push(Box::new(move |found| match is_within(found, &within) {
+            Some(true) => vec![found.to_string()],
+            _ => Vec::new(),
+        }));
+    }
+    if !opts.ip_scope.is_empty() {
+        let scopes = opts.ip_scope.clone();
+        stages.push(Box::new(move |found| {
+            let networks = cidr::parse_networks(found);
+            let in_scope = networks
+                .iter()
+                .all(|n| n.scope().is_some_and(|s| scopes.contains(&s)));
+            if !networks.is_empty() && in_scope {
+                vec![found.to_string()]
+            } else {
+                Vec::new()
+            }
+        }));
+    }
+    // the excluded networks are subtracted once aggregated instead
+    if !opts.not_within.is_empty() && !opts.aggregate {
+        let not_within = opts.not_within.clone();
+        stages.push(Box::new(move |found| match is_within(found, &not_within) {
+            Some(false) => vec![found.to_string()],
+            _ => Vec::new(),
+        }));
+    }
+    stages
+}
+
+fn semver_stages(opts: &Opts) -> Vec<Stage> {
+    let mut stages: Vec<Stage> = Vec::new();
+    if let Some(requirement) = opts.semver_matches.clone() {
+        stages.push(Box::new(move |found| match Version::parse(found) {
+            Some(version) if requirement.matches(&version) => vec![found.to_string()],
+            _ => Vec::new(),
+        }));
+    }
+    stages
+}
+
+fn uri_stages(opts: &Opts) -> Vec<Stage> {
+    let mut stages: Vec<Stage> = Vec::new();
+    if let Some(base_url) = opts.base_url.clone() {
+        // absolute URIs start with a scheme, relative references never do
+        stages.push(Box::new(move |found| {
+            if found.starts_with(['/', '.', '?']) {
+                uri::resolve(&base_url, found).into_iter().collect()
+            } else {
+                vec![found.to_string()]
+            }
+        }));
+    }
+    if opts.clean || !opts.clean_rules.is_empty() {
+        let mut cleaner = Cleaner::default();
+        add_rules_files(&opts.clean_rules, |rules| cleaner.add_rules(rules));
+        stages.push(Box::new(move |found| vec![cleaner.clean(found)]));
+    }
+    if !opts.ip_scope.is_empty() {
+        let scopes = opts.ip_scope.clone();
+        let finder = URI::default();
+        stages.push(Box::new(move |found| {
+            let Some(host) = finder.components(found).and_then(|c| c.host) else {
+                return Vec::new();
+            };
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            // the domain names are public, except localhost
+            let scope = match host.parse() {
+                Ok(addr) => ip::scope(addr),
+                Err(_) if host.eq_ignore_ascii_case("localhost") => Scope::Loopback,
+                Err(_) if host.to_ascii_lowercase().ends_with(".localhost") => Scope::Loopback,
+                Err(_) => Scope::Public,
+            };
+            if scopes.contains(&scope) {
+                vec![found.to_string()]
+            } else {
+                Vec::new()
+            }
+        }));
+    }
+    if let Some(select) = opts.select.clone() {
+        let finder = URI::default();
+        stages.push(Box::new(move |found| {
+            let Some(c) = finder.components(found) else {
+                return Vec::new();
+            };
+            let component = match &select {
+                Select::Scheme => c.scheme.map(str::to_string),
+                Select::Host => c.host.map(str::to_string),
+                Select::Port => c.port.map(str::to_string),
+                Select::Path => Some(c.path.to_string()),
+                Select::Query => c.query.map(str::to_string),
+                Select::Fragment => c.fragment.map(str::to_string),
+                Select::Param(name) => c.param(name),
+            };
+            component
+                .filter(|component| !component.is_empty())
+                .into_iter()
+                .collect()
+        }));
+    }
+    stages
+}
+
 fn main() -> ExitCode {
     env_logger::init();
 
     let opts = Opts::parse();
+    if let Some(Command::Report(Report::Codetags(ref report))) = opts.command {
+        return report::codetags(report);
+    }
+
     let cidr = TryInto::<Cidr>::try_into(&opts);
     let codetag = TryInto::<Codetag>::try_into(&opts);
     let color = TryInto::<Color>::try_into(&opts);
@@ -445,44 +1778,432 @@ fn main() -> ExitCode {
         return ExitCode::SUCCESS;
     }
 
-    for line in io::stdin().lock().lines() {
-        let line = match line {
-            Ok(line) => line,
+    let mut stages: HashMap<&str, Vec<Stage>> = [
+        (
+            "cidr",
+            cidr_stages(&opts)
+                .into_iter()
+                .chain(network_stages(&opts))
+                .collect(),
+        ),
+        ("color", color_stages(&opts)),
+        ("datetime", datetime_stages(&opts)),
+        ("email", email_stages(&opts)),
+        (
+            "ip",
+            ip_stages(&opts)
+                .into_iter()
+                .chain(network_stages(&opts))
+                .collect(),
+        ),
+        ("semver", semver_stages(&opts)),
+        ("uri", uri_stages(&opts)),
+    ]
+    .into_iter()
+    .collect();
+    let mut extractors: Vec<Box<dyn Extractor>> = finders
+        .into_iter()
+        .map(|finder| -> Box<dyn Extractor> {
+            match (finder.id(), &codetag) {
+                ("codetag", Ok(codetag)) => Box::new(CodetagExtractor::new(codetag, &opts)),
+                (id, _) => Box::new(FinderExtractor {
+                    finder,
+                    stages: stages.remove(id).unwrap_or_default(),
+                    file: None,
+                }),
+            }
+        })
+        .map(|extractor| -> Box<dyn Extractor> {
+            // only the URI finder searches the link targets of the markup
+            match (opts.input, extractor.id()) {
+                (Input::Text, _) => extractor,
+                (_, "uri") => Box::new(LinksExtractor {
+                    extractor,
+                    input: opts.input,
+                    base_url: opts.base_url.clone(),
+                    doc: String::new(),
+                }),
+                _ => Box::new(DecodeExtractor { extractor }),
+            }
+        })
+        .collect();
+    if opts.aggregate {
+        let (networks, mut others): (Vec<_>, Vec<_>) = extractors
+            .into_iter()
+            .partition(|extractor| matches!(extractor.id(), "cidr" | "ip"));
+        if !networks.is_empty() {
+            others.push(Box::new(AggregateExtractor {
+                extractors: networks,
+                networks: Vec::new(),
+                excluded: opts.not_within.clone(),
+            }));
+        }
+        extractors = others;
+    }
+    if let Some(max_delta_e) = opts.palette {
+        let (colors, mut others): (Vec<_>, Vec<_>) = extractors
+            .into_iter()
+            .partition(|extractor| extractor.id() == "color");
+        others.push(Box::new(PaletteExtractor {
+            extractors: colors,
+            max_delta_e,
+            colors: Vec::new(),
+            indexes: HashMap::new(),
+        }));
+        extractors = others;
+    }
+    if opts.semver_sort || opts.semver_max {
+        let (versions, mut others): (Vec<_>, Vec<_>) = extractors
+            .into_iter()
+            .partition(|extractor| extractor.id() == "semver");
+        others.push(Box::new(VersionExtractor {
+            extractors: versions,
+            max: opts.semver_max,
+            found: Vec::new(),
+        }));
+        extractors = others;
+    }
+
+    let mut baseline = opts.baseline.as_ref().map(|path| {
+        Baseline::load(path).unwrap_or_else(|e| {
+            Opts::command()
+                .error(ErrorKind::Io, format!("{}: {}", path.display(), e))
+                .exit()
+        })
+    });
+    let mut recorded = Baseline::default();
+    let mut new_codetags = 0;
+
+    // the swatches are only printed to the terminals, unless NO_COLOR is set
+    let swatches = io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    let mut json = Vec::new();
+    let mut status = search(&opts, &mut extractors, |found| {
+        if let Some(ref tag) = found.tag {
+            let fingerprint = Fingerprint::new(found.file.as_deref(), tag);
+            if let Some(ref mut baseline) = baseline {
+                if baseline.remove(&fingerprint) {
+                    recorded.insert(fingerprint);
+                    return false;
+                }
+                new_codetags += 1;
+            }
+            recorded.insert(fingerprint);
+        }
+        match opts.format {
+            Format::Text => {
+                if opts.line_number && found.line > 0 {
+                    if let Some(ref file) = found.file {
+                        print!("{}:", file.display());
+                    }
+                    if found.end_line > found.line {
+                        print!("{}-{}:", found.line, found.end_line);
+                    } else {
+                        print!("{}:", found.line);
+                    }
+                }
+                match found.swatch {
+                    Some(ref swatch) => {
+                        if swatches {
+                            let [r, g, b] = swatch
+                                .color
+                                .to_gamut()
+                                .components
+                                .map(|v| (v * 255.0).round() as u8);
+                            print!("\x1b[48;2;{};{};{}m    \x1b[0m ", r, g, b);
+                        }
+                        println!(
+                            "{} {} {}",
+                            found.text,
+                            swatch.count,
+                            swatch.colors.join(", ")
+                        );
+                    }
+                    None => println!("{}", found.text),
+                }
+            }
+            Format::Json => json.push(found.to_json(opts.assume_tz)),
+            Format::Jsonl => println!("{}", found.to_json(opts.assume_tz)),
+        }
+        if opts.open {
+            if let Err(e) = open_url(&found.text) {
+                eprintln!("failed to open '{}': {}", found.text, e);
+            }
+        }
+        // stop at the first result if asked to
+        opts.first
+    });
+    if let Format::Json = opts.format {
+        let json = serde_json::Value::Array(json);
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json).unwrap_or_default()
+        );
+    }
+
+    if let Some(ref path) = opts.write_baseline {
+        if let Err(e) = recorded.save(path) {
+            eprintln!("failed to write '{}': {}", path.display(), e);
+            status = ExitCode::FAILURE;
+        }
+    }
+    if new_codetags > 0 {
+        eprintln!(
+            "{} codetag{} missing from the baseline",
+            new_codetags,
+            if new_codetags == 1 { "" } else { "s" }
+        );
+        status = ExitCode::FAILURE;
+    }
+
+    status
+}
+
+/// Feeds the lines of the input to the extractors, and outputs their results until asked to
+/// stop.
+fn search(
+    opts: &Opts,
+    extractors: &mut [Box<dyn Extractor + '_>],
+    mut output: impl FnMut(&Found) -> bool,
+) -> ExitCode {
+    let files: Vec<_> = if opts.files.is_empty() {
+        vec![None]
+    } else {
+        opts.files.iter().map(|file| Some(file.as_path())).collect()
+    };
+    let mut status = ExitCode::SUCCESS;
+
+    for file in files {
+        let lines = match read_lines(file) {
+            Ok(lines) => lines,
             Err(e) => {
-                log::error!("failed to read line: {}", e);
+                let name = file.map_or("-".into(), |file| file.display().to_string());
+                eprintln!("failed to read '{}': {}", name, e);
+                status = ExitCode::FAILURE;
                 continue;
             }
         };
+        for extractor in extractors.iter_mut() {
+            extractor.begin(file);
+        }
 
-        for finder in &finders {
-            log::debug!("[{}] line \"{}\"", finder.id(), line);
-            let mut idx = 0;
-            while idx < line.len() {
-                let segment = &line[idx..];
-                log::debug!("[{}] searching in \"{}\"", finder.id(), segment);
-                if let Some(range) = finder.find(segment) {
-                    log::debug!("[{}] found at [{};{}[", finder.id(), range.start, range.end);
-                    idx += range.end;
-                    let found = &segment[range].trim();
-                    if !found.is_empty() {
-                        println!("{}", found);
-                        if opts.open {
-                            if let Err(e) = open_url(found) {
-                                eprintln!("failed to open '{}': {}", found, e);
-                            }
-                        }
-                        if opts.first {
-                            return ExitCode::SUCCESS;
-                        }
+        for (number, line) in (1..).zip(lines) {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => {
+                    log::error!("failed to read line: {}", e);
+                    continue;
+                }
+            };
+
+            for extractor in extractors.iter_mut() {
+                log::debug!("[{}] line \"{}\"", extractor.id(), line);
+                for found in extractor.extract(number, &line) {
+                    if output(&found) {
+                        return status;
                     }
-                } else {
-                    break;
                 }
             }
         }
+
+        for extractor in extractors.iter_mut() {
+            for found in extractor.end() {
+                if output(&found) {
+                    return status;
+                }
+            }
+        }
+    }
+
+    for extractor in extractors.iter_mut() {
+        for found in extractor.finish() {
+            if output(&found) {
+                return status;
+            }
+        }
     }
 
-    ExitCode::SUCCESS
+    status
+}
+
+/// Returns the lines to search in the given file (or the standard input).
+fn read_lines(file: Option<&std::path::Path>) -> io::Result<io::Lines<Box<dyn BufRead>>> {
+    let reader: Box<dyn BufRead> = match file {
+        Some(file) => Box::new(BufReader::new(File::open(file)?)),
+        None => Box::new(io::stdin().lock()),
+    };
+    Ok(reader.lines())
+}
+
+fn parse_base_url(s: &str) -> Result<String, String> {
+    match URI::default().find(s) {
+        Some(range) if range == (0..s.len()) => Ok(s.to_string()),
+        _ => Err(format!("'{}' is not an absolute URL", s)),
+    }
+}
+
+fn parse_select(s: &str) -> Result<Select, String> {
+    Ok(match s {
+        "scheme" => Select::Scheme,
+        "host" => Select::Host,
+        "port" => Select::Port,
+        "path" => Select::Path,
+        "query" => Select::Query,
+        "fragment" => Select::Fragment,
+        _ => match s.strip_prefix("param:") {
+            Some(name) if !name.is_empty() => Select::Param(name.to_string()),
+            _ => return Err(format!("unknown uri component '{}'", s)),
+        },
+    })
+}
+
+fn parse_lang(s: &str) -> Result<&'static Language, String> {
+    Language::from_name(s).ok_or_else(|| format!("unknown language '{}'", s))
+}
+
+fn parse_duration(s: &str) -> Result<i64, String> {
+    let unit = match s.char_indices().last() {
+        Some((i, 's')) => Some((i, 1)),
+        Some((i, 'm')) => Some((i, 60)),
+        Some((i, 'h')) => Some((i, 3600)),
+        Some((i, 'd')) => Some((i, 86400)),
+        Some((i, 'w')) => Some((i, 7 * 86400)),
+        Some((i, 'y')) => Some((i, 365 * 86400)),
+        _ => None,
+    };
+    match unit.and_then(|(i, unit)| s[..i].parse::<i64>().ok()?.checked_mul(unit)) {
+        Some(duration) if duration >= 0 => Ok(duration),
+        _ => Err(format!(
+            "'{}' is not a duration (e.g. 30m, 24h, 180d, 12w, 1y)",
+            s
+        )),
+    }
+}
+
+// The optional values of --codetag, --hash and --uri are told apart from the files that may
+// follow them, e.g. `--uri page.html` or `--codetag Makefile`.
+fn parse_optional_list(
+    s: &str,
+    flag: &str,
+    what: &str,
+    is_valid: impl Fn(&str) -> bool,
+) -> Result<String, String> {
+    let is_file = std::path::Path::new(s).exists();
+    if !is_file && s.split(',').all(|item| !item.is_empty() && is_valid(item)) {
+        return Ok(s.to_string());
+    }
+    Err(format!(
+        "'{}' is not a list of {}, give the files before {} or after --",
+        s, what, flag
+    ))
+}
+
+fn parse_mnemonics(s: &str) -> Result<String, String> {
+    parse_optional_list(
+        s,
+        "--codetag",
+        "mnemonics or categories (e.g. fixme,note)",
+        |m| !m.contains(|c: char| c.is_whitespace() || "():=./".contains(c)),
+    )
+}
+
+fn parse_algorithms(s: &str) -> Result<String, String> {
+    parse_optional_list(s, "--hash", "hash algorithms (e.g. md5,sha256)", |a| {
+        a.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+    })
+}
+
+fn parse_schemes(s: &str) -> Result<String, String> {
+    parse_optional_list(s, "--uri", "uri schemes (e.g. https,ftp)", |scheme| {
+        scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'-')
+    })
+}
+
+fn parse_mnemonic(s: &str) -> Result<Mnemonic, String> {
+    let (category, name) = s.split_once('=').unwrap_or(("", s));
+    let (name, colon) = match name.strip_suffix(":?") {
+        Some(name) => (name, false),
+        None => (name, true),
+    };
+    if name.is_empty() || name.contains(|c: char| c.is_whitespace() || "():=./".contains(c)) {
+        return Err(format!("'{}' is not a mnemonic (e.g. note=SAFETY)", s));
+    }
+    Ok(Mnemonic {
+        colon,
+        ..Mnemonic::new(name, if category.is_empty() { name } else { category })
+    })
+}
+
+fn parse_datetime_format(s: &str) -> Result<datetime::Format, String> {
+    datetime::Format::from_name(s).ok_or_else(|| format!("unknown datetime format '{}'", s))
+}
+
+fn parse_datetime_output(s: &str) -> Result<DatetimeOutput, String> {
+    Ok(match s {
+        "rfc3339" => DatetimeOutput::Rfc3339,
+        "epoch" => DatetimeOutput::Epoch,
+        _ if s.contains('%') && Timestamp::from_unix(0).strftime(s).is_some() => {
+            DatetimeOutput::Strftime(s.to_string())
+        }
+        _ => return Err(format!("unknown datetime format '{}'", s)),
+    })
+}
+
+fn parse_time_bound(s: &str) -> Result<TimeBound, String> {
+    if let Some(duration) = s.strip_prefix('-') {
+        return parse_duration(duration).map(TimeBound::Ago);
+    }
+    Timestamp::parse(s).map(TimeBound::At).ok_or_else(|| {
+        format!(
+            "'{}' is not a datetime (e.g. 2024-01-15T10:00:00Z or -2h)",
+            s
+        )
+    })
+}
+
+fn parse_offset(s: &str) -> Result<i32, String> {
+    datetime::parse_offset(s)
+        .ok_or_else(|| format!("'{}' is not a UTC offset (e.g. Z, +02:00, -0500 or EST)", s))
+}
+
+fn parse_version_req(s: &str) -> Result<VersionReq, String> {
+    VersionReq::parse(s).ok_or_else(|| {
+        format!(
+            "'{}' is not a version requirement (e.g. ^1.2, ~1.2.3, '>=1, <2' or 1.x)",
+            s
+        )
+    })
+}
+
+fn parse_scope(s: &str) -> Result<Scope, String> {
+    Scope::from_name(s).ok_or_else(|| format!("unknown scope '{}'", s))
+}
+
+fn parse_network(s: &str) -> Result<Network, String> {
+    Network::parse(s).ok_or_else(|| format!("'{}' is not a network (e.g. 10.0.0.0/8)", s))
+}
+
+fn parse_priority_filter(s: &str) -> Result<PriorityFilter, String> {
+    let (orderings, priority): (&'static [Ordering], _) = if let Some(p) = s.strip_prefix("<=") {
+        (&[Ordering::Less, Ordering::Equal], p)
+    } else if let Some(p) = s.strip_prefix(">=") {
+        (&[Ordering::Greater, Ordering::Equal], p)
+    } else if let Some(p) = s.strip_prefix('<') {
+        (&[Ordering::Less], p)
+    } else if let Some(p) = s.strip_prefix('>') {
+        (&[Ordering::Greater], p)
+    } else {
+        (&[Ordering::Equal], s.strip_prefix('=').unwrap_or(s))
+    };
+    match priority.trim().parse() {
+        Ok(priority) => Ok(PriorityFilter {
+            orderings,
+            priority,
+        }),
+        Err(_) => Err(format!("'{}' is not a priority comparison", s)),
+    }
 }
 
 fn open_url(url: &str) -> io::Result<()> {
@@ -0,0 +1,324 @@
+use crate::{blame, fields_json, parse_lang, parse_mnemonic};
+use clap::{Args, ValueEnum};
+use squeeze::codetag::{Codetag, Mnemonic, Occurrence, Scanner};
+use squeeze::comment::Language;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const UNASSIGNED: &str = "(unassigned)";
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ReportFormat {
+    /// a Markdown document, e.g. to commit as TODO.md
+    Markdown,
+    /// a standalone HTML page
+    Html,
+    /// a JSON object
+    Json,
+}
+
+#[derive(Args)]
+pub struct CodetagsReport {
+    #[arg(value_name = "FILE", required = true, help = "the files to search")]
+    files: Vec<PathBuf>,
+    #[arg(
+        long = "format",
+        value_enum,
+        default_value = "markdown",
+        help = "how to output the report"
+    )]
+    format: ReportFormat,
+    #[arg(
+        long = "lang",
+        value_parser = parse_lang,
+        help = "only search the comments of this language (detected from the file extensions by default)"
+    )]
+    lang: Option<&'static Language>,
+    #[arg(
+        long = "codetag-define",
+        value_name = "[CATEGORY=]MNEMONIC[:?]",
+        value_parser = parse_mnemonic,
+        help = "search for this mnemonic in addition to the default ones, as part of a category (e.g. 'note=SAFETY'), the colon following it being optional with ':?'"
+    )]
+    define: Vec<Mnemonic>,
+    #[arg(
+        long = "codetag-continuation",
+        help = "join the comment lines continuing a codetag"
+    )]
+    continuation: bool,
+    #[arg(
+        long = "codetag-blame",
+        help = "attribute the codetags without authors to the author of their last change"
+    )]
+    blame: bool,
+}
+
+/// A codetag, along with where it has been found.
+struct Entry {
+    file: String,
+    category: String,
+    authors: Vec<String>,
+    occurrence: Occurrence,
+}
+
+impl Entry {
+    fn location(&self) -> String {
+        let o = &self.occurrence;
+        if o.end_line > o.line {
+            format!("{}:{}-{}", self.file, o.line, o.end_line)
+        } else {
+            format!("{}:{}", self.file, o.line)
+        }
+    }
+
+    fn anchor(&self) -> String {
+        format!("{}#{}", self.file, self.fragment())
+    }
+
+    fn fragment(&self) -> String {
+        let o = &self.occurrence;
+        if o.end_line > o.line {
+            format!("L{}-L{}", o.line, o.end_line)
+        } else {
+            format!("L{}", o.line)
+        }
+    }
+}
+
+/// The number of codetags per category, per file and per author, in decreasing order.
+struct Summary<'a> {
+    categories: Vec<(&'a str, usize)>,
+    files: Vec<(&'a str, usize)>,
+    authors: Vec<(&'a str, usize)>,
+}
+
+impl<'a> Summary<'a> {
+    fn new(entries: &'a [Entry]) -> Self {
+        fn count<'a>(keys: impl Iterator<Item = &'a str>) -> Vec<(&'a str, usize)> {
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            for key in keys {
+                *counts.entry(key).or_default() += 1;
+            }
+            let mut counts: Vec<_> = counts.into_iter().collect();
+            counts.sort_by(|(a, m), (b, n)| n.cmp(m).then(a.cmp(b)));
+            counts
+        }
+
+        Summary {
+            categories: count(entries.iter().map(|e| e.category.as_str())),
+            files: count(entries.iter().map(|e| e.file.as_str())),
+            authors: count(entries.iter().flat_map(|e| {
+                let authors = e.authors.iter().map(String::as_str);
+                authors.chain(e.authors.is_empty().then_some(UNASSIGNED))
+            })),
+        }
+    }
+
+    fn headline(&self, entries: &[Entry]) -> String {
+        format!(
+            "{} codetag{} in {} file{}.",
+            entries.len(),
+            if entries.len() == 1 { "" } else { "s" },
+            self.files.len(),
+            if self.files.len() == 1 { "" } else { "s" },
+        )
+    }
+}
+
+/// Searches the files for codetags, and prints a summary per category, per file and per author.
+pub fn codetags(report: &CodetagsReport) -> ExitCode {
+    let mut finder = Codetag::default();
+    for mnemonic in &report.define {
+        finder.define(mnemonic.clone());
+    }
+    finder
+        .build_mnemonics_regex()
+        .expect("failed to build codetag regex");
+
+    let mut entries = Vec::new();
+    let mut status = ExitCode::SUCCESS;
+    for file in &report.files {
+        let content = match std::fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("failed to read '{}': {}", file.display(), e);
+                status = ExitCode::FAILURE;
+                continue;
+            }
+        };
+
+        let language = report.lang.or_else(|| Language::from_path(file));
+        let mut scanner = Scanner::new(&finder, language);
+        scanner.continuation = report.continuation;
+        let mut occurrences: Vec<_> = content.lines().flat_map(|l| scanner.scan_line(l)).collect();
+        occurrences.extend(scanner.finish());
+
+        let blames = if report.blame {
+            blame::blame(file).unwrap_or_else(|e| {
+                log::debug!("failed to blame '{}': {}", file.display(), e);
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+
+        for occurrence in occurrences {
+            let mut authors = occurrence.tag.fields.authors.clone();
+            if authors.is_empty() {
+                authors.extend(blames.get(occurrence.line - 1).map(|b| b.author.clone()));
+            }
+            let category = match finder.category(&occurrence.tag.mnemonic) {
+                Some(category) => category.to_string(),
+                None => occurrence.tag.mnemonic.to_lowercase(),
+            };
+            entries.push(Entry {
+                file: file.display().to_string(),
+                category,
+                authors,
+                occurrence,
+            });
+        }
+    }
+
+    let summary = Summary::new(&entries);
+    match report.format {
+        ReportFormat::Markdown => print!("{}", markdown(&entries, &summary)),
+        ReportFormat::Html => print!("{}", html(&entries, &summary)),
+        ReportFormat::Json => println!("{:#}", json(&entries, &summary)),
+    }
+    status
+}
+
+fn markdown(entries: &[Entry], summary: &Summary) -> String {
+    // Escapes the characters of the inline formatting, the links, the tables and the headings,
+    // and replaces the line breaks (e.g. of the joined continuation lines) that would end the
+    // list items.
+    fn escape(s: &str) -> String {
+        let mut escaped = String::with_capacity(s.len());
+        for c in s.chars() {
+            match c {
+                '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '#' | '!' | '~' | '&' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                '\r' | '\n' => escaped.push(' '),
+                c => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    // Percent-encodes the file of the link destination, which would otherwise end at its spaces
+    // or parentheses.
+    fn destination(entry: &Entry) -> String {
+        let mut encoded = String::with_capacity(entry.file.len());
+        for b in entry.file.bytes() {
+            if b.is_ascii_alphanumeric() || b"-._~/".contains(&b) {
+                encoded.push(b as char);
+            } else {
+                let _ = write!(encoded, "%{:02X}", b);
+            }
+        }
+        format!("{}#{}", encoded, entry.fragment())
+    }
+
+    fn table(out: &mut String, title: &str, column: &str, counts: &[(&str, usize)]) {
+        let _ = writeln!(out, "\n## {}\n", title);
+        let _ = writeln!(out, "| {} | Count |\n| --- | ---: |", column);
+        for (key, count) in counts {
+            let _ = writeln!(out, "| {} | {} |", escape(key), count);
+        }
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(out, "# Codetags\n\n{}", summary.headline(entries));
+    table(&mut out, "Categories", "Category", &summary.categories);
+    table(&mut out, "Files", "File", &summary.files);
+    table(&mut out, "Authors", "Author", &summary.authors);
+    for (category, _) in &summary.categories {
+        let _ = writeln!(out, "\n## {}\n", escape(category));
+        for entry in entries.iter().filter(|e| e.category == *category) {
+            let _ = writeln!(
+                out,
+                "- [{}]({}): {}",
+                escape(&entry.location()),
+                destination(entry),
+                escape(&entry.occurrence.text)
+            );
+        }
+    }
+    out
+}
+
+fn html(entries: &[Entry], summary: &Summary) -> String {
+    fn escape(s: &str) -> String {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    fn table(out: &mut String, title: &str, column: &str, counts: &[(&str, usize)]) {
+        let _ = writeln!(out, "<h2>{}</h2>\n<table>", title);
+        let _ = writeln!(out, "<tr><th>{}</th><th>Count</th></tr>", column);
+        for (key, count) in counts {
+            let _ = writeln!(out, "<tr><td>{}</td><td>{}</td></tr>", escape(key), count);
+        }
+        out.push_str("</table>\n");
+    }
+
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str("<title>Codetags</title>\n</head>\n<body>\n<h1>Codetags</h1>\n");
+    let _ = writeln!(out, "<p>{}</p>", summary.headline(entries));
+    table(&mut out, "Categories", "Category", &summary.categories);
+    table(&mut out, "Files", "File", &summary.files);
+    table(&mut out, "Authors", "Author", &summary.authors);
+    for (category, _) in &summary.categories {
+        let _ = writeln!(out, "<h2>{}</h2>\n<ul>", escape(category));
+        for entry in entries.iter().filter(|e| e.category == *category) {
+            let _ = writeln!(
+                out,
+                "<li><a href=\"{}\">{}</a>: {}</li>",
+                escape(&entry.anchor()),
+                escape(&entry.location()),
+                escape(&entry.occurrence.text)
+            );
+        }
+        out.push_str("</ul>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn json(entries: &[Entry], summary: &Summary) -> serde_json::Value {
+    fn counts(key: &str, counts: &[(&str, usize)]) -> serde_json::Value {
+        counts
+            .iter()
+            .map(|(k, count)| serde_json::json!({ key: k, "count": count }))
+            .collect()
+    }
+
+    serde_json::json!({
+        "total": entries.len(),
+        "categories": counts("category", &summary.categories),
+        "files": counts("file", &summary.files),
+        "authors": counts("author", &summary.authors),
+        "codetags": entries
+            .iter()
+            .map(|e| serde_json::json!({
+                "file": e.file,
+                "line": e.occurrence.line,
+                "end_line": e.occurrence.end_line,
+                "category": e.category,
+                "mnemonic": e.occurrence.tag.mnemonic,
+                "description": e.occurrence.tag.description,
+                "text": e.occurrence.text,
+                "authors": e.authors,
+                "fields": fields_json(&e.occurrence.tag.fields),
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
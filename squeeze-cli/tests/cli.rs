@@ -1,11 +1,44 @@
 use assert_cmd::Command;
 use predicates::prelude::*;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
 
 fn squeeze() -> Command {
     #[allow(deprecated)]
     Command::cargo_bin("squeeze").unwrap()
 }
 
+// A temporary directory, removed when dropped, even when the test fails.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> TempDir {
+        let path = std::env::temp_dir().join(format!("squeeze-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
 // ============================================================================
 // Help and version tests
 // ============================================================================
@@ -66,6 +99,36 @@ fn uri_with_scheme_filter_should_only_match_specified_scheme() {
         .stdout(predicate::str::contains("http://insecure.com").not());
 }
 
+#[test]
+fn uri_with_separate_scheme_filter_should_only_match_specified_scheme() {
+    squeeze()
+        .args(["--uri", "https"])
+        .write_stdin("https://secure.com and http://insecure.com\n")
+        .assert()
+        .success()
+        .stdout("https://secure.com\n");
+}
+
+#[test]
+fn uri_codetag_and_hash_should_reject_files_as_values() {
+    for (flag, file) in [
+        ("--uri", "page.html"),
+        ("--codetag", "src/main.py"),
+        ("--hash", "sums.txt"),
+        ("--codetag", "Cargo.toml"),
+    ] {
+        squeeze()
+            .args([flag, file])
+            .write_stdin("")
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(format!(
+                "give the files before {} or after --",
+                flag
+            )));
+    }
+}
+
 #[test]
 fn url_alias_should_extract_common_url_schemes() {
     squeeze()
@@ -110,6 +173,218 @@ fn strict_mode_should_include_trailing_parentheses() {
         .stdout(predicate::str::contains("http://example.com/path)"));
 }
 
+#[test]
+fn select_should_output_uri_hosts() {
+    squeeze()
+        .arg("--url")
+        .arg("--select=host")
+        .write_stdin("https://a.com/x http://user@b.com:8080/y?z mailto:c@d.com\n")
+        .assert()
+        .success()
+        .stdout("a.com\nb.com\n");
+}
+
+#[test]
+fn select_should_output_uri_components() {
+    for (component, expected) in [
+        ("scheme", "https\n"),
+        ("port", "8080\n"),
+        ("path", "/a/b\n"),
+        ("query", "q=1&r=2\n"),
+        ("fragment", "top\n"),
+    ] {
+        squeeze()
+            .arg("--uri")
+            .arg(format!("--select={}", component))
+            .write_stdin("https://example.com:8080/a/b?q=1&r=2#top\n")
+            .assert()
+            .success()
+            .stdout(expected);
+    }
+}
+
+#[test]
+fn select_should_output_decoded_query_params() {
+    squeeze()
+        .arg("--url")
+        .arg("--select=param:redirect_uri")
+        .write_stdin(
+            "https://a.com/?redirect_uri=https%3A%2F%2Fb.com%2Fcb https://c.com/?other=1\n",
+        )
+        .assert()
+        .success()
+        .stdout("https://b.com/cb\n");
+}
+
+#[test]
+fn select_should_reject_unknown_components() {
+    squeeze()
+        .arg("--url")
+        .arg("--select=nope")
+        .write_stdin("")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown uri component"));
+}
+
+#[test]
+fn clean_should_strip_tracking_params_and_redirectors() {
+    squeeze()
+        .arg("--url")
+        .arg("--clean")
+        .write_stdin(
+            "https://example.com/?id=1&utm_source=x&fbclid=y \
+             https://www.google.com/url?q=https%3A%2F%2Fother.com%2F&sa=D\n",
+        )
+        .assert()
+        .success()
+        .stdout("https://example.com/?id=1\nhttps://other.com/\n");
+}
+
+#[test]
+fn clean_rules_should_extend_the_embedded_rules() {
+    let dir = TempDir::new("clean-rules");
+    let rules = dir.join("rules.txt");
+    std::fs::write(&rules, "param ref\nredirect out.example.com /go to\n").unwrap();
+
+    squeeze()
+        .arg("--url")
+        .arg(format!("--clean-rules={}", rules.display()))
+        .write_stdin(
+            "https://out.example.com/go?to=https%3A%2F%2Fa.com%2F%3Fref%3Dx%26utm_medium%3Dy\n",
+        )
+        .assert()
+        .success()
+        .stdout("https://a.com/\n");
+}
+
+#[test]
+fn clean_rules_should_report_invalid_rules() {
+    let dir = TempDir::new("invalid-rules");
+    let rules = dir.join("rules.txt");
+    std::fs::write(&rules, "param ok\nnope\n").unwrap();
+
+    squeeze()
+        .arg("--url")
+        .arg(format!("--clean-rules={}", rules.display()))
+        .write_stdin("")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("line 2: unknown rule 'nope'"));
+}
+
+#[test]
+fn clean_should_apply_before_select() {
+    squeeze()
+        .arg("--url")
+        .arg("--clean")
+        .arg("--select=host")
+        .write_stdin("https://www.google.com/url?q=https%3A%2F%2Fother.com%2F\n")
+        .assert()
+        .success()
+        .stdout("other.com\n");
+}
+
+// ============================================================================
+// Markup input tests
+// ============================================================================
+
+#[test]
+fn html_input_should_extract_decoded_link_targets() {
+    squeeze()
+        .arg("--url")
+        .arg("--input=html")
+        .write_stdin("<a href=\"https://example.com/?a=1&amp;b=2\">link</a>\n")
+        .assert()
+        .success()
+        .stdout("https://example.com/?a=1&b=2\n");
+}
+
+#[test]
+fn html_input_should_resolve_relative_links_against_base_url() {
+    squeeze()
+        .arg("--url")
+        .arg("--input=html")
+        .arg("--base-url=https://example.com/docs/")
+        .write_stdin("<a href=\"../login\">login</a>\n<img\n src=\"logo.png\">\n")
+        .assert()
+        .success()
+        .stdout("https://example.com/login\nhttps://example.com/docs/logo.png\n");
+}
+
+#[test]
+fn html_input_should_skip_relative_links_without_base_url() {
+    squeeze()
+        .arg("--url")
+        .arg("--input=html")
+        .write_stdin("<a href=\"/login\">login</a>\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn html_input_should_only_apply_to_the_uri_finder() {
+    squeeze()
+        .arg("--email")
+        .arg("--uri")
+        .arg("--input=html")
+        .write_stdin("<p>mail john&#64;example.com <a href=\"https://example.com\">x</a></p>\n")
+        .assert()
+        .success()
+        .stdout("john@example.com\nhttps://example.com\n");
+}
+
+#[test]
+fn markdown_input_should_extract_link_targets() {
+    squeeze()
+        .arg("--url")
+        .arg("--input=markdown")
+        .write_stdin(
+            "[a](https://a.com/x_(y)) <https://b.com>\n[c]: https://c.com\n`https://code.com`\n",
+        )
+        .assert()
+        .success()
+        .stdout("https://a.com/x_(y)\nhttps://b.com\nhttps://c.com\n");
+}
+
+#[test]
+fn base_url_should_extract_relative_references_from_text() {
+    squeeze()
+        .arg("--url")
+        .arg("--base-url=https://example.com/docs/guide.html")
+        .write_stdin("see ../api/index.html, /login and ?page=2 or https://other.com/x\n")
+        .assert()
+        .success()
+        .stdout(
+            "https://example.com/api/index.html\n\
+             https://example.com/login\n\
+             https://example.com/docs/guide.html?page=2\n\
+             https://other.com/x\n",
+        );
+}
+
+#[test]
+fn relative_references_should_be_ignored_without_base_url() {
+    squeeze()
+        .arg("--url")
+        .write_stdin("see ../api/index.html and /login\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn base_url_should_reject_relative_urls() {
+    squeeze()
+        .arg("--url")
+        .arg("--base-url=/docs/")
+        .write_stdin("")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is not an absolute URL"));
+}
+
 // ============================================================================
 // Codetag extraction tests
 // ============================================================================
@@ -157,6 +432,53 @@ fn fixme_alias_should_only_extract_fixmes() {
         .stdout(predicate::str::contains("TODO").not());
 }
 
+#[test]
+fn codetag_with_category_should_extract_the_whole_category() {
+    squeeze()
+        .arg("--fixme")
+        .write_stdin("// XXX: a\n// TODO: b\n// BROKEN: c\n")
+        .assert()
+        .success()
+        .stdout("XXX: a\nBROKEN: c\n");
+}
+
+#[test]
+fn codetag_define_should_extend_the_default_mnemonics() {
+    squeeze()
+        .arg("--codetag")
+        .arg("--codetag-define=note=SAFETY:?")
+        .arg("--codetag-define=todo=AFAIRE")
+        .write_stdin("// SAFETY the pointer is valid\n// AFAIRE: b\n// TODO: c\n")
+        .assert()
+        .success()
+        .stdout("SAFETY the pointer is valid\nAFAIRE: b\nTODO: c\n");
+
+    squeeze()
+        .arg("--todo")
+        .arg("--codetag-define=todo=AFAIRE")
+        .arg("--codetag-define=PERF")
+        .write_stdin("// PERF: a\n// AFAIRE: b\n// NOTE: c\n")
+        .assert()
+        .success()
+        .stdout("AFAIRE: b\n");
+}
+
+#[test]
+fn codetag_define_should_reject_invalid_mnemonics() {
+    squeeze()
+        .arg("--codetag")
+        .arg("--codetag-define=note=")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'note=' is not a mnemonic"));
+    squeeze()
+        .arg("--codetag")
+        .arg("--codetag-define=src/main.rs")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'src/main.rs' is not a mnemonic"));
+}
+
 #[test]
 fn hide_mnemonic_should_exclude_mnemonic_from_output() {
     squeeze()
@@ -169,6 +491,336 @@ fn hide_mnemonic_should_exclude_mnemonic_from_output() {
         .stdout(predicate::str::contains("TODO:").not());
 }
 
+#[test]
+fn codetag_author_should_filter_by_author() {
+    squeeze()
+        .arg("--codetag")
+        .arg("--codetag-author=mlb")
+        .write_stdin("# TODO: one <MLB>\n# TODO: two <CLE>\n# TODO(mlb): three\n")
+        .assert()
+        .success()
+        .stdout("TODO: one <MLB>\nTODO(mlb): three\n");
+}
+
+#[test]
+fn codetag_priority_should_filter_by_priority() {
+    squeeze()
+        .arg("--codetag")
+        .arg("--codetag-priority=<=2")
+        .write_stdin("# TODO: one <p:1>\n# TODO: two <p:3>\n# TODO: three\n# TODO: four <p:2>\n")
+        .assert()
+        .success()
+        .stdout("TODO: one <p:1>\nTODO: four <p:2>\n");
+}
+
+#[test]
+fn codetag_priority_should_reject_invalid_comparisons() {
+    squeeze()
+        .arg("--codetag")
+        .arg("--codetag-priority=<=high")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not a priority comparison"));
+}
+
+#[test]
+fn codetag_sort_should_sort_by_priority() {
+    squeeze()
+        .arg("--codetag")
+        .arg("--codetag-sort=priority")
+        .write_stdin("# TODO: a\n# TODO: b <p:3>\n# TODO: c <p:1>\n# TODO: d <p:3>\n")
+        .assert()
+        .success()
+        .stdout("TODO: c <p:1>\nTODO: b <p:3>\nTODO: d <p:3>\nTODO: a\n");
+}
+
+#[test]
+fn codetag_sort_should_sort_by_date() {
+    squeeze()
+        .arg("--codetag")
+        .arg("--codetag-sort=date")
+        .arg("--first")
+        .write_stdin("# TODO: a <2024-03-01>\n# TODO: b <2023-12-24>\n")
+        .assert()
+        .success()
+        .stdout("TODO: b <2023-12-24>\n");
+}
+
+#[test]
+fn codetag_should_only_search_comments_of_known_languages() {
+    let dir = TempDir::new("codetag-lang");
+    let file = dir.join("main.js");
+    std::fs::write(
+        &file,
+        "const s = \"the TODO: list\"; // FIXME: escape s\n/* TODO: a\n * TODO: b */\n",
+    )
+    .unwrap();
+
+    squeeze()
+        .arg(&file)
+        .arg("--codetag")
+        .assert()
+        .success()
+        .stdout("FIXME: escape s\nTODO: a\nTODO: b\n");
+    squeeze()
+        .args(["--codetag", "--"])
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout("FIXME: escape s\nTODO: a\nTODO: b\n");
+}
+
+#[test]
+fn codetag_lang_should_apply_to_stdin() {
+    squeeze()
+        .arg("--codetag")
+        .arg("--lang=html")
+        .write_stdin("<p>TODO: not a comment</p><!-- TODO: fix the layout -->\n")
+        .assert()
+        .success()
+        .stdout("TODO: fix the layout\n");
+}
+
+#[test]
+fn codetag_lang_should_skip_rust_char_literals() {
+    squeeze()
+        .arg("--codetag")
+        .arg("--lang=rust")
+        .write_stdin("fn a() -> u8 { b'\"' }\n// TODO: first\nfn b<'a>() -> char { '\"' }\n// FIXME: second\n")
+        .assert()
+        .success()
+        .stdout("TODO: first\nFIXME: second\n");
+}
+
+#[test]
+fn codetag_lang_should_reject_unknown_languages() {
+    squeeze()
+        .arg("--codetag")
+        .arg("--lang=klingon")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown language 'klingon'"));
+}
+
+#[test]
+fn codetag_continuation_should_join_the_following_comment_lines() {
+    squeeze()
+        .arg("--codetag")
+        .arg("--codetag-continuation")
+        .arg("--lang=python")
+        .arg("-n")
+        .write_stdin(
+            "# TODO: this is a long\n#       codetag <MLB>\nx = 1  # FIXME: a\n       # b\n",
+        )
+        .assert()
+        .success()
+        .stdout("1-2:TODO: this is a long codetag <MLB>\n3-4:FIXME: a b\n");
+}
+
+fn git(dir: &std::path::Path, args: &[&str], date: Option<&str>) {
+    let mut git = std::process::Command::new("git");
+    git.current_dir(dir)
+        .args([
+            "-c",
+            "user.name=Jane Doe",
+            "-c",
+            "user.email=jane@example.com",
+        ])
+        .args(args);
+    if let Some(date) = date {
+        git.env("GIT_AUTHOR_DATE", date);
+        git.env("GIT_COMMITTER_DATE", date);
+    }
+    assert!(git.status().unwrap().success());
+}
+
+#[test]
+fn codetag_blame_should_attach_the_last_change() {
+    let dir = TempDir::new("codetag-blame");
+    git(&dir, &["init", "-q"], None);
+    std::fs::write(dir.join("a.py"), "# TODO: old\n").unwrap();
+    git(&dir, &["add", "a.py"], None);
+    git(
+        &dir,
+        &["commit", "-qm", "old"],
+        Some("2020-01-02T03:04:05+01:00"),
+    );
+    std::fs::write(dir.join("a.py"), "# TODO: old\n# FIXME: new\n").unwrap();
+    git(&dir, &["commit", "-qam", "new"], None);
+    std::fs::write(
+        dir.join("a.py"),
+        "# TODO: old\n# FIXME: new\n# XXX: local\n",
+    )
+    .unwrap();
+
+    let output = squeeze()
+        .arg("--codetag")
+        .arg("--codetag-blame")
+        .arg("--format=jsonl")
+        .arg(dir.join("a.py"))
+        .output()
+        .unwrap();
+    let lines: Vec<&str> = std::str::from_utf8(&output.stdout)
+        .unwrap()
+        .lines()
+        .collect();
+    assert_eq!(3, lines.len());
+    assert!(lines[0].contains(r#""author":"Jane Doe""#), "{}", lines[0]);
+    assert!(lines[0].contains(r#""author_mail":"jane@example.com""#));
+    assert!(lines[0].contains(r#""date":"2020-01-02T03:04:05+01:00""#));
+    assert!(lines[2].contains(r#""author":"Not Committed Yet""#));
+
+    squeeze()
+        .arg("--codetag")
+        .arg("--older-than=180d")
+        .arg(dir.join("a.py"))
+        .assert()
+        .success()
+        .stdout("TODO: old\n");
+}
+
+#[test]
+fn older_than_should_reject_invalid_durations() {
+    squeeze()
+        .arg("--codetag")
+        .arg("--older-than=soon")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'soon' is not a duration"));
+}
+
+#[test]
+fn baseline_should_only_report_the_new_codetags() {
+    let dir = TempDir::new("baseline");
+    std::fs::write(dir.join("a.py"), "# TODO: a\n# FIXME: b\n# FIXME: b\n").unwrap();
+
+    squeeze()
+        .current_dir(&dir)
+        .args(["--codetag", "--write-baseline=baseline.json", "./a.py"])
+        .assert()
+        .success()
+        .stdout("TODO: a\nFIXME: b\nFIXME: b\n");
+
+    // the known codetags moved around, one has been duplicated and one added
+    std::fs::write(
+        dir.join("a.py"),
+        "import os\n# FIXME:  b\n# TODO: a\n# FIXME: b\n# FIXME: b\n# XXX: c\n",
+    )
+    .unwrap();
+    squeeze()
+        .current_dir(&dir)
+        .args(["--codetag", "--baseline=baseline.json", "a.py"])
+        .assert()
+        .failure()
+        .stdout("FIXME: b\nXXX: c\n")
+        .stderr("2 codetags missing from the baseline\n");
+
+    std::fs::write(dir.join("a.py"), "# FIXME: b\n").unwrap();
+    squeeze()
+        .current_dir(&dir)
+        .args(["--codetag", "--baseline=baseline.json", "a.py"])
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[test]
+fn baseline_should_report_invalid_baselines() {
+    let dir = TempDir::new("invalid-baseline");
+    let file = dir.join("baseline.json");
+    std::fs::write(&file, r#"{"version": 1}"#).unwrap();
+
+    squeeze()
+        .arg("--codetag")
+        .arg(format!("--baseline={}", file.display()))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("missing the codetags array"));
+}
+
+#[test]
+fn report_codetags_should_summarize_per_category_file_and_author() {
+    let dir = TempDir::new("report");
+    std::fs::write(
+        dir.join("a.py"),
+        "# TODO: a <MLB>\n# XXX(jo): b\nx = \"TODO: not a comment\"\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("b.rs"), "// FIXME: c\n").unwrap();
+
+    squeeze()
+        .current_dir(&dir)
+        .args(["report", "codetags", "a.py", "b.rs"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "# Codetags\n\n3 codetags in 2 files.\n\n## Categories\n\n| Category | Count |\n| --- | ---: |\n| fixme | 2 |\n| todo | 1 |\n",
+        ))
+        .stdout(predicate::str::contains("| (unassigned) | 1 |\n| MLB | 1 |\n| jo | 1 |\n"))
+        .stdout(predicate::str::ends_with(
+            "## fixme\n\n- [a.py:2](a.py#L2): XXX(jo): b\n- [b.rs:1](b.rs#L1): FIXME: c\n\n## todo\n\n- [a.py:1](a.py#L1): TODO: a \\<MLB\\>\n",
+        ));
+
+    squeeze()
+        .current_dir(&dir)
+        .args(["report", "codetags", "--format=html", "a.py"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "<li><a href=\"a.py#L1\">a.py:1</a>: TODO: a &lt;MLB&gt;</li>",
+        ));
+
+    squeeze()
+        .current_dir(&dir)
+        .args(["report", "codetags", "--format=json", "b.rs"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""total": 1"#))
+        .stdout(predicate::str::contains(r#""category": "fixme""#));
+}
+
+#[test]
+fn report_codetags_should_escape_the_markdown() {
+    let dir = TempDir::new("report-md");
+    std::fs::write(
+        dir.join("a.py"),
+        "# TODO: handle [links](x) and *emphasis* in `code` | <b>\n#       & more\n",
+    )
+    .unwrap();
+
+    squeeze()
+        .current_dir(&dir)
+        .args(["report", "codetags", "--codetag-continuation", "a.py"])
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with(
+            "- [a.py:1-2](a.py#L1-L2): TODO: handle \\[links\\](x) and \\*emphasis\\* in \\`code\\` \\| \\<b\\> \\& more\n",
+        ));
+
+    std::fs::write(dir.join("my (draft).py"), "# HACK: b\n").unwrap();
+
+    squeeze()
+        .current_dir(&dir)
+        .args(["report", "codetags", "--codetag-define=#_hacks_=HACK"])
+        .arg("my (draft).py")
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with(
+            "## \\#\\_hacks\\_\n\n- [my (draft).py:1](my%20%28draft%29.py#L1): HACK: b\n",
+        ));
+}
+
+#[test]
+fn report_codetags_should_fail_on_unreadable_files() {
+    squeeze()
+        .args(["report", "codetags", "does-not-exist.py"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "failed to read 'does-not-exist.py'",
+        ));
+}
+
 // ============================================================================
 // Email extraction tests
 // ============================================================================
@@ -224,6 +876,87 @@ fn email_flag_should_not_match_bare_at() {
         .stdout(predicate::str::is_empty());
 }
 
+#[test]
+fn email_rfc5322_flag_should_extract_mailboxes() {
+    squeeze()
+        .arg("--email-rfc5322")
+        .write_stdin("To: \"Smith, John\" <john@example.com>, Undisclosed-recipients:;, \"odd local\"@example.com\n")
+        .assert()
+        .success()
+        .stdout("\"Smith, John\" <john@example.com>\n\"odd local\"@example.com\n");
+}
+
+#[test]
+fn email_rfc5322_flag_should_report_names_and_addresses() {
+    squeeze()
+        .args(["--email-rfc5322", "--format=jsonl"])
+        .write_stdin("From: =?UTF-8?Q?J=C3=B6rg?= <jorg@example.com>\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""name":"Jörg""#))
+        .stdout(predicate::str::contains(r#""address":"jorg@example.com""#))
+        .stdout(predicate::str::contains(r#""local":"jorg""#))
+        .stdout(predicate::str::contains(r#""domain":"example.com""#));
+}
+
+#[test]
+fn email_obfuscated_flag_should_output_the_addresses() {
+    squeeze()
+        .arg("--email-obfuscated")
+        .write_stdin("john at example dot com, jane[at]example[dot]org\nI am at home dot com\nJuan (arroba) Ejemplo.com\n")
+        .assert()
+        .success()
+        .stdout("john@example.com\njane@example.org\nJuan@Ejemplo.com\n");
+}
+
+#[test]
+fn email_obfuscated_flag_should_deobfuscate_before_normalizing() {
+    squeeze()
+        .args(["--email-obfuscated", "--normalize"])
+        .write_stdin("Juan (arroba) Ejemplo.com\n")
+        .assert()
+        .success()
+        .stdout("Juan@ejemplo.com\n");
+}
+
+#[test]
+fn normalize_should_dedupe_the_addresses_of_a_mailbox() {
+    squeeze()
+        .args(["--email", "--normalize"])
+        .write_stdin("First.Last+tag@GMail.com, firstlast@gmail.com and Jane+tag@Bücher.de\n")
+        .assert()
+        .success()
+        .stdout("firstlast@gmail.com\nfirstlast@gmail.com\nJane+tag@xn--bcher-kva.de\n");
+}
+
+#[test]
+fn normalize_rules_should_extend_the_embedded_rules() {
+    let dir = TempDir::new("email-rules");
+    let rules = dir.join("rules.txt");
+    std::fs::write(
+        &rules,
+        "alias example.org example.com\nlowercase example.com\nplus example.com\ndots example.com\n",
+    )
+    .unwrap();
+
+    squeeze()
+        .arg("--email")
+        .arg(format!("--normalize-rules={}", rules.display()))
+        .write_stdin("First.Last+tag@example.org\n")
+        .assert()
+        .success()
+        .stdout("firstlast@example.com\n");
+
+    std::fs::write(&rules, "dots\n").unwrap();
+    squeeze()
+        .arg("--email")
+        .arg(format!("--normalize-rules={}", rules.display()))
+        .write_stdin("")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("line 1: expected: dots DOMAIN"));
+}
+
 // ============================================================================
 // Path extraction tests
 // ============================================================================
@@ -344,6 +1077,84 @@ fn color_flag_should_extract_multiple_colors() {
         .stdout(predicate::str::contains("rgb(0, 255, 0)"));
 }
 
+#[test]
+fn color_flag_should_validate_css_color_4_functions() {
+    squeeze()
+        .arg("--color")
+        .write_stdin("a: oklch(70% 0.1 240 / 50%); b: rgb(300, 0, 0); c: color(display-p3 1 0 0)\n")
+        .assert()
+        .success()
+        .stdout("oklch(70% 0.1 240 / 50%)\ncolor(display-p3 1 0 0)\n");
+}
+
+#[test]
+fn color_names_flag_should_extract_named_colors() {
+    squeeze()
+        .arg("--color-names")
+        .write_stdin("fill: currentColor; stroke: RebeccaPurple; .red-team { color: #fff }\n")
+        .assert()
+        .success()
+        .stdout("currentColor\nRebeccaPurple\n#fff\n");
+}
+
+#[test]
+fn color_format_flag_should_convert_the_colors() {
+    let input =
+        "a: #f00; b: hsl(120 100% 25% / 0.5); c: color(display-p3 1 0 0); d: currentColor\n";
+    for (format, expected) in [
+        ("hex", "#ff0000\n#00800080\n#ff0b0c\n"),
+        ("rgb", "rgb(255 0 0)\nrgb(0 128 0 / 0.5)\nrgb(255 11 12)\n"),
+        ("hsl", "hsl(0 100% 50%)\nhsl(120 100% 25% / 0.5)\nhsl(359.91 100% 52.23%)\n"),
+        ("oklch", "oklch(62.8% 0.2577 29.23)\noklch(51.83% 0.1764 142.5 / 0.5)\noklch(64.86% 0.2995 28.96)\n"),
+    ] {
+        squeeze()
+            .args(["--color-format", format])
+            .write_stdin(input)
+            .assert()
+            .success()
+            .stdout(expected);
+    }
+}
+
+#[test]
+fn color_flag_should_report_the_contrast_ratios() {
+    squeeze()
+        .args(["--color", "--format=jsonl"])
+        .write_stdin("color: #767676\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""contrast":{"black":4.62,"white":4.54}"#,
+        ))
+        .stdout(predicate::str::contains(r##""hex":"#767676""##));
+}
+
+#[test]
+fn palette_flag_should_merge_the_near_duplicate_colors() {
+    // the swatches are not printed, the output not being a terminal
+    squeeze()
+        .arg("--palette")
+        .write_stdin("a: #336699; b: #ff0000; c: #fe0101\nd: #FF0000; e: rgb(51 102 153)\n")
+        .assert()
+        .success()
+        .stdout(concat!(
+            "#ff0000 3 #ff0000, #fe0101, #FF0000\n",
+            "#336699 2 #336699, rgb(51 102 153)\n",
+        ));
+}
+
+#[test]
+fn palette_flag_should_take_the_distance_of_the_near_duplicates() {
+    squeeze()
+        .args(["--palette=0", "--color-format=hex", "--format=jsonl"])
+        .write_stdin("#ff0000 #fe0101 #FF0000\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r##""colors":["#ff0000"]"##))
+        .stdout(predicate::str::contains(r#""count":2"#))
+        .stdout(predicate::str::contains(r##""colors":["#fe0101"]"##));
+}
+
 // ============================================================================
 // Env extraction tests
 // ============================================================================
@@ -428,6 +1239,18 @@ fn hash_flag_with_algo_filter_should_only_match_specified() {
         .stdout(predicate::str::contains("2aae6c35c94fcfb415dbe95f408b9ce91ee846ed").not());
 }
 
+#[test]
+fn hash_with_separate_algo_filter_should_only_match_specified() {
+    squeeze()
+        .args(["--hash", "md5"])
+        .write_stdin(
+            "5d41402abc4b2a76b9719d911017c592 and 2aae6c35c94fcfb415dbe95f408b9ce91ee846ed\n",
+        )
+        .assert()
+        .success()
+        .stdout("5d41402abc4b2a76b9719d911017c592\n");
+}
+
 #[test]
 fn md5_alias_should_only_extract_md5() {
     squeeze()
@@ -442,64 +1265,139 @@ fn md5_alias_should_only_extract_md5() {
 }
 
 #[test]
-fn sha1_alias_should_only_extract_sha1() {
+fn sha1_alias_should_only_extract_sha1() {
+    squeeze()
+        .arg("--sha1")
+        .write_stdin(
+            "5d41402abc4b2a76b9719d911017c592 and 2aae6c35c94fcfb415dbe95f408b9ce91ee846ed\n",
+        )
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed",
+        ))
+        .stdout(predicate::str::contains("5d41402abc4b2a76b9719d911017c592").not());
+}
+
+// ============================================================================
+// IP extraction tests
+// ============================================================================
+
+#[test]
+fn ip_flag_should_extract_ipv4() {
+    squeeze()
+        .arg("--ip")
+        .write_stdin("server at 192.168.1.1\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("192.168.1.1"));
+}
+
+#[test]
+fn ip_flag_should_extract_ipv6_bracketed() {
+    squeeze()
+        .arg("--ip")
+        .write_stdin("connect to [::1]\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[::1]"));
+}
+
+#[test]
+fn ipv4_flag_should_only_extract_ipv4() {
+    squeeze()
+        .arg("--ipv4")
+        .write_stdin("192.168.1.1 and [::1]\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("192.168.1.1"))
+        .stdout(predicate::str::contains("[::1]").not());
+}
+
+#[test]
+fn ipv6_flag_should_only_extract_ipv6() {
+    squeeze()
+        .arg("--ipv6")
+        .write_stdin("192.168.1.1 and [::1]\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[::1]"))
+        .stdout(predicate::str::contains("192.168.1.1").not());
+}
+
+#[test]
+fn ip_flag_should_extract_zones() {
+    squeeze()
+        .arg("--ip")
+        .write_stdin("ping fe80::1%eth0.\n")
+        .assert()
+        .success()
+        .stdout("fe80::1%eth0\n");
+}
+
+#[test]
+fn ip_canonical_should_output_rfc_5952_addresses() {
+    squeeze()
+        .arg("--ip")
+        .arg("--ip-canonical")
+        .write_stdin("[2001:DB8:0:0::1] ::ffff:192.0.2.1 FE80::1%eth0\n")
+        .assert()
+        .success()
+        .stdout("2001:db8::1\n::ffff:192.0.2.1\nfe80::1%eth0\n");
+}
+
+#[test]
+fn ip_unmap_should_output_ipv4_mapped_addresses_as_ipv4() {
     squeeze()
-        .arg("--sha1")
-        .write_stdin(
-            "5d41402abc4b2a76b9719d911017c592 and 2aae6c35c94fcfb415dbe95f408b9ce91ee846ed\n",
-        )
+        .arg("--ip")
+        .arg("--ip-unmap")
+        .write_stdin("::ffff:192.0.2.1 ::FFFF:C000:202 2001:db8::1\n")
         .assert()
         .success()
-        .stdout(predicate::str::contains(
-            "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed",
-        ))
-        .stdout(predicate::str::contains("5d41402abc4b2a76b9719d911017c592").not());
+        .stdout("192.0.2.1\n192.0.2.2\n2001:db8::1\n");
 }
 
-// ============================================================================
-// IP extraction tests
-// ============================================================================
-
 #[test]
-fn ip_flag_should_extract_ipv4() {
+fn ip_scope_should_filter_ips_and_cidrs_by_scope() {
     squeeze()
         .arg("--ip")
-        .write_stdin("server at 192.168.1.1\n")
+        .arg("--cidr")
+        .arg("--ip-scope=public")
+        .write_stdin("127.0.0.1 8.8.8.8 10.0.0.1 fe80::1 2606:4700::1111 192.0.2.1 10.0.0.0/7\n")
         .assert()
         .success()
-        .stdout(predicate::str::contains("192.168.1.1"));
-}
+        .stdout("8.8.8.8\n2606:4700::1111\n");
 
-#[test]
-fn ip_flag_should_extract_ipv6_bracketed() {
     squeeze()
         .arg("--ip")
-        .write_stdin("connect to [::1]\n")
+        .arg("--ip-scope=loopback,link-local")
+        .write_stdin("127.0.0.1 8.8.8.8 fe80::1\n")
         .assert()
         .success()
-        .stdout(predicate::str::contains("[::1]"));
+        .stdout("127.0.0.1\nfe80::1\n");
 }
 
 #[test]
-fn ipv4_flag_should_only_extract_ipv4() {
+fn ip_scope_should_filter_uris_by_host() {
     squeeze()
-        .arg("--ipv4")
-        .write_stdin("192.168.1.1 and [::1]\n")
+        .arg("--url")
+        .arg("--ip-scope=public")
+        .write_stdin(
+            "http://localhost:8080/ http://10.0.0.1/ https://example.com http://[::1]/ http://8.8.4.4/\n",
+        )
         .assert()
         .success()
-        .stdout(predicate::str::contains("192.168.1.1"))
-        .stdout(predicate::str::contains("[::1]").not());
+        .stdout("https://example.com\nhttp://8.8.4.4/\n");
 }
 
 #[test]
-fn ipv6_flag_should_only_extract_ipv6() {
+fn ip_scope_should_reject_unknown_scopes() {
     squeeze()
-        .arg("--ipv6")
-        .write_stdin("192.168.1.1 and [::1]\n")
+        .arg("--ip")
+        .arg("--ip-scope=global")
         .assert()
-        .success()
-        .stdout(predicate::str::contains("[::1]"))
-        .stdout(predicate::str::contains("192.168.1.1").not());
+        .failure()
+        .stderr(predicate::str::contains("unknown scope 'global'"));
 }
 
 // ============================================================================
@@ -644,6 +1542,56 @@ fn semver_flag_should_not_match_two_components() {
         .stdout(predicate::str::is_empty());
 }
 
+#[test]
+fn semver_sort_should_sort_by_precedence() {
+    squeeze()
+        .arg("--semver-sort")
+        .write_stdin("v1.10.0 1.0.0 1.0.0-beta.11\n1.2.0 1.0.0-beta.2 01.0.0\n")
+        .assert()
+        .success()
+        .stdout("01.0.0\n1.0.0-beta.2\n1.0.0-beta.11\n1.0.0\n1.2.0\nv1.10.0\n");
+}
+
+#[test]
+fn semver_max_should_output_greatest_version() {
+    squeeze()
+        .arg("--semver-max")
+        .write_stdin("1.9.0 v1.10.0\n2.0.0-rc.1 1.2.0\n")
+        .assert()
+        .success()
+        .stdout("2.0.0-rc.1\n");
+}
+
+#[test]
+fn semver_matches_should_filter_versions() {
+    squeeze()
+        .args(["--semver-matches", ">=1, <2"])
+        .write_stdin("0.9.0 1.0.0 1.9.9 2.0.0 1.5.0-rc.1\n")
+        .assert()
+        .success()
+        .stdout("1.0.0\n1.9.9\n");
+}
+
+#[test]
+fn semver_matches_should_combine_with_max() {
+    squeeze()
+        .args(["--semver-matches", "~1.2", "--semver-max"])
+        .write_stdin("v1.2.0 v1.2.7 v1.3.0 v1.2.10\n")
+        .assert()
+        .success()
+        .stdout("v1.2.10\n");
+}
+
+#[test]
+fn semver_matches_should_reject_invalid_requirement() {
+    squeeze()
+        .args(["--semver-matches", "1.x.2"])
+        .write_stdin("1.0.0\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is not a version requirement"));
+}
+
 // ============================================================================
 // UUID extraction tests
 // ============================================================================
@@ -732,6 +1680,100 @@ fn cidr_flag_should_not_match_plain_ip() {
         .stdout(predicate::str::is_empty());
 }
 
+#[test]
+fn cidr_masks_and_ranges_should_extract_other_notations() {
+    squeeze()
+        .arg("--cidr-masks")
+        .arg("--cidr-ranges")
+        .write_stdin("ip route 10.0.0.0 255.255.255.0\npermit 10.1.0.0 0.0.255.255 any\npool 10.0.0.1-10.0.0.6\n")
+        .assert()
+        .success()
+        .stdout("10.0.0.0 255.255.255.0\n10.1.0.0 0.0.255.255\n10.0.0.1-10.0.0.6\n");
+}
+
+#[test]
+fn to_cidr_should_convert_to_cidr_blocks() {
+    squeeze()
+        .arg("--cidr-masks")
+        .arg("--cidr-ranges")
+        .arg("--to-cidr")
+        .write_stdin("10.0.0.0/255.255.0.0 10.1.2.3/8\npool 10.0.0.1 - 10.0.0.6\n")
+        .assert()
+        .success()
+        .stdout("10.0.0.0/16\n10.0.0.0/8\n10.0.0.1/32\n10.0.0.2/31\n10.0.0.4/31\n10.0.0.6/32\n");
+}
+
+#[test]
+fn to_cidr_should_leave_the_ips_as_is() {
+    squeeze()
+        .arg("--ip")
+        .arg("--cidr")
+        .arg("--to-cidr")
+        .write_stdin("192.0.2.1 fe80::1%eth0 10.1.2.3/8\n")
+        .assert()
+        .success()
+        .stdout("10.0.0.0/8\n192.0.2.1\nfe80::1%eth0\n10.1.2.3\n");
+}
+
+#[test]
+fn within_should_filter_ips_and_cidrs_by_network() {
+    squeeze()
+        .arg("--ipv4")
+        .arg("--cidr")
+        .arg("--within=10.0.0.0/8")
+        .arg("--within=2001:db8::/32")
+        .write_stdin("10.1.2.3 172.16.0.1 10.0.0.0/16 192.168.0.0/16 2001:db8::/48\n")
+        .assert()
+        .success()
+        .stdout("10.0.0.0/16\n2001:db8::/48\n10.1.2.3\n10.0.0.0\n");
+}
+
+#[test]
+fn not_within_should_exclude_ips_and_cidrs_by_network() {
+    squeeze()
+        .arg("--ip")
+        .arg("--not-within=10.0.0.0/8")
+        .write_stdin("10.1.2.3 172.16.0.1 ::1\n")
+        .assert()
+        .success()
+        .stdout("172.16.0.1\n::1\n");
+}
+
+#[test]
+fn aggregate_should_merge_the_networks_found() {
+    squeeze()
+        .arg("--ipv4")
+        .arg("--cidr")
+        .arg("--aggregate")
+        .arg("-n")
+        .write_stdin("10.0.0.1 10.0.0.0/31\n10.0.0.2/31 192.168.0.0/24\n192.168.1.0/24\n")
+        .assert()
+        .success()
+        .stdout("10.0.0.0/30\n192.168.0.0/23\n");
+}
+
+#[test]
+fn aggregate_should_subtract_the_excluded_networks() {
+    squeeze()
+        .arg("--cidr")
+        .arg("--aggregate")
+        .arg("--not-within=10.0.0.0/9")
+        .write_stdin("10.0.0.0/8\n")
+        .assert()
+        .success()
+        .stdout("10.128.0.0/9\n");
+}
+
+#[test]
+fn within_should_reject_invalid_networks() {
+    squeeze()
+        .arg("--ip")
+        .arg("--within=10.0.0.0/33")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'10.0.0.0/33' is not a network"));
+}
+
 // ============================================================================
 // Datetime extraction tests
 // ============================================================================
@@ -776,6 +1818,189 @@ fn datetime_flag_should_not_match_invalid_date() {
         .stdout(predicate::str::is_empty());
 }
 
+#[test]
+fn datetime_flag_should_not_match_days_missing_from_the_calendar() {
+    squeeze()
+        .arg("--datetime")
+        .write_stdin("2023-02-29 2023-04-31 2024-02-29\n")
+        .assert()
+        .success()
+        .stdout("2024-02-29\n");
+}
+
+#[test]
+fn datetime_flag_should_extract_the_formats_of_logs_and_emails() {
+    squeeze()
+        .arg("--datetime")
+        .write_stdin("Date: Tue, 15 Nov 1994 08:12:31 GMT\n[10/Oct/2000:13:55:36 -0700] Oct 11 22:14:15 1700000000\n")
+        .assert()
+        .success()
+        .stdout("Tue, 15 Nov 1994 08:12:31 GMT\n10/Oct/2000:13:55:36 -0700\nOct 11 22:14:15\n");
+}
+
+#[test]
+fn datetime_flag_should_only_search_the_given_formats() {
+    squeeze()
+        .arg("--datetime=clf,epoch")
+        .write_stdin("2024-01-15 [10/Oct/2000:13:55:36 -0700] {\"ts\":1700000000123}\n")
+        .assert()
+        .success()
+        .stdout("10/Oct/2000:13:55:36 -0700\n1700000000123\n");
+}
+
+#[test]
+fn datetime_flag_should_reject_unknown_formats() {
+    squeeze()
+        .arg("--datetime=iso,nope")
+        .write_stdin("")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown datetime format 'nope'"));
+}
+
+#[test]
+fn datetime_flag_should_report_unix_timestamps() {
+    squeeze()
+        .args(["--datetime", "--format=jsonl"])
+        .write_stdin("2024-01-15T10:30:00+01:00 2024-01-15\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""unix":1705311000"#))
+        .stdout(predicate::str::contains(r#""unix":null"#));
+
+    squeeze()
+        .args(["--datetime", "--format=jsonl", "--assume-tz=+01:00"])
+        .write_stdin("2024-01-15T10:30:00\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""unix":1705311000"#));
+}
+
+#[test]
+fn datetime_flag_should_extract_iso_durations_intervals_and_week_dates() {
+    squeeze()
+        .arg("--datetime=iso,duration")
+        .write_stdin("sprint=2024-W03-2/P2W day=2024-015T10:00Z timeout=PT30M\n")
+        .assert()
+        .success()
+        .stdout("2024-W03-2/P2W\n2024-015T10:00Z\nPT30M\n");
+}
+
+#[test]
+fn datetime_flag_should_not_extract_durations_or_bare_ordinal_dates_by_default() {
+    squeeze()
+        .arg("--datetime")
+        .write_stdin("wait P2D or P1M, see 2024-123 and 1999-001 on 2024-01-15\n")
+        .assert()
+        .success()
+        .stdout("2024-01-15\n");
+}
+
+#[test]
+fn datetime_flag_should_report_the_seconds_of_durations() {
+    squeeze()
+        .args(["--datetime=duration", "--format=jsonl"])
+        .write_stdin("PT1H30M P1M\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""seconds":5400"#))
+        .stdout(predicate::str::contains(r#""seconds":null"#));
+}
+
+#[test]
+fn datetime_format_flag_should_output_the_datetimes_in_utc() {
+    let input = "2024-01-15T10:30:00.250+01:00 [10/Oct/2000:13:55:36 -0700] 2024-01-15\n";
+    for (format, expected) in [
+        (
+            "rfc3339",
+            "2024-01-15T09:30:00.250Z\n2000-10-10T20:55:36Z\n2024-01-15\n",
+        ),
+        ("epoch", "1705311000.250\n971211336\n2024-01-15\n"),
+        (
+            "%d %b %Y %H:%M",
+            "15 Jan 2024 09:30\n10 Oct 2000 20:55\n2024-01-15\n",
+        ),
+    ] {
+        squeeze()
+            .args(["--datetime", "--datetime-format", format])
+            .write_stdin(input)
+            .assert()
+            .success()
+            .stdout(expected);
+    }
+}
+
+#[test]
+fn datetime_format_flag_should_reject_unknown_formats() {
+    squeeze()
+        .args(["--datetime", "--datetime-format", "%Q"])
+        .write_stdin("")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown datetime format '%Q'"));
+}
+
+#[test]
+fn assume_tz_flag_should_convert_the_local_datetimes() {
+    squeeze()
+        .args([
+            "--datetime",
+            "--datetime-format=rfc3339",
+            "--assume-tz",
+            "-05:00",
+        ])
+        .write_stdin("2024-01-15 10:30:00 2024-01-15T10:30:00Z\n")
+        .assert()
+        .success()
+        .stdout("2024-01-15T15:30:00Z\n2024-01-15T10:30:00Z\n");
+}
+
+#[test]
+fn since_and_until_flags_should_filter_the_datetimes() {
+    squeeze()
+        .args(["--datetime", "--since", "2024-01-15", "--until", "2024-02-01T00:00:00+01:00"])
+        .write_stdin("2024-01-14T23:59:59Z 2024-01-15T00:00:00Z 2024-01-31T23:00:00Z 2024-01-31T22:59:59Z 2024-01-20\n")
+        .assert()
+        .success()
+        .stdout("2024-01-15T00:00:00Z\n2024-01-31T22:59:59Z\n");
+}
+
+#[test]
+fn since_flag_should_accept_relative_datetimes() {
+    squeeze()
+        .args(["--datetime=iso,epoch", "--since", "-2h"])
+        .write_stdin("2000-01-01T00:00:00Z 4102444800\n")
+        .assert()
+        .success()
+        .stdout("4102444800\n");
+}
+
+#[test]
+fn since_flag_should_only_keep_the_local_datetimes_given_assume_tz() {
+    squeeze()
+        .args([
+            "--datetime",
+            "--since",
+            "2024-01-15T10:00:00",
+            "--assume-tz",
+            "+01:00",
+        ])
+        .write_stdin("2024-01-15 09:00 2024-01-15 10:00 2024-01-15T09:30:00Z\n")
+        .assert()
+        .success()
+        .stdout("2024-01-15 10:00\n2024-01-15T09:30:00Z\n");
+}
+
+#[test]
+fn since_flag_should_reject_invalid_datetimes() {
+    squeeze()
+        .args(["--datetime", "--since", "yesterday"])
+        .write_stdin("")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'yesterday' is not a datetime"));
+}
+
 // ============================================================================
 // JWT extraction tests
 // ============================================================================
@@ -907,6 +2132,85 @@ fn no_matches_should_produce_no_output() {
         .stdout(predicate::str::is_empty());
 }
 
+#[test]
+fn files_should_be_searched_instead_of_stdin() {
+    let dir = TempDir::new("files");
+    std::fs::write(dir.join("a.txt"), "https://a.com\n").unwrap();
+    std::fs::write(dir.join("b.txt"), "https://b.com\n").unwrap();
+
+    squeeze()
+        .arg("--url")
+        .arg(dir.join("a.txt"))
+        .arg(dir.join("missing.txt"))
+        .arg(dir.join("b.txt"))
+        .write_stdin("https://stdin.com\n")
+        .assert()
+        .failure()
+        .stdout("https://a.com\nhttps://b.com\n")
+        .stderr(predicate::str::contains("missing.txt"));
+}
+
+#[test]
+fn line_number_should_prefix_the_results() {
+    let dir = TempDir::new("line-number");
+    let file = dir.join("a.txt");
+    std::fs::write(&file, "\nhttps://a.com https://b.com\n").unwrap();
+
+    squeeze()
+        .arg("--url")
+        .arg("--line-number")
+        .write_stdin("https://stdin.com\n")
+        .assert()
+        .success()
+        .stdout("1:https://stdin.com\n");
+    squeeze()
+        .arg("--url")
+        .arg("-n")
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout(format!(
+            "{0}:2:https://a.com\n{0}:2:https://b.com\n",
+            file.display()
+        ));
+}
+
+#[test]
+fn format_json_should_describe_the_results() {
+    squeeze()
+        .arg("--url")
+        .arg("--todo")
+        .arg("--format=json")
+        .write_stdin("\n# TODO(john): see https://a.com <p:1>\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("["))
+        .stdout(predicate::str::contains(r#""finder": "codetag""#))
+        .stdout(predicate::str::contains(
+            r#""description": "see https://a.com""#,
+        ))
+        .stdout(predicate::str::contains(r#""priority": 1"#))
+        .stdout(predicate::str::contains(r#""finder": "uri""#))
+        .stdout(predicate::str::contains(r#""text": "https://a.com""#))
+        .stdout(predicate::str::contains(r#""line": 2"#));
+}
+
+#[test]
+fn format_jsonl_should_output_one_object_per_line() {
+    squeeze()
+        .arg("--url")
+        .arg("--format=jsonl")
+        .write_stdin("https://a.com https://b.com\n")
+        .assert()
+        .success()
+        .stdout(concat!(
+            r#"{"end_line":1,"file":null,"finder":"uri","line":1,"text":"https://a.com"}"#,
+            "\n",
+            r#"{"end_line":1,"file":null,"finder":"uri","line":1,"text":"https://b.com"}"#,
+            "\n",
+        ));
+}
+
 // ============================================================================
 // Multiple finders tests
 // ============================================================================
@@ -1,24 +1,27 @@
+use super::ip::{self, Scope};
 use super::Finder;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::ops::Range;
 
+/// Finds the networks written in the CIDR notation (e.g. `10.0.0.0/8` or `2001:db8::/32`), and
+/// optionally in the notations found in firewall configurations, see [`parse_networks`] to
+/// convert them to CIDR blocks.
 #[derive(Default)]
-pub struct Cidr {}
+pub struct Cidr {
+    /// Whether to also find the IPv4 networks written with a netmask (`10.0.0.0 255.255.255.0`
+    /// or `10.0.0.0/255.255.255.0`) or a wildcard mask (`10.0.0.0 0.0.0.255`).
+    pub masks: bool,
+    /// Whether to also find the ranges of addresses (`10.0.0.1-10.0.0.50`).
+    pub ranges: bool,
+}
 
 impl Cidr {
-    fn try_ipv4_cidr(input: &[u8], idx: usize) -> Option<Range<usize>> {
-        if !input[idx].is_ascii_digit() {
-            return None;
-        }
-
-        // Boundary before: not preceded by digit or dot
-        if idx > 0 && (input[idx - 1].is_ascii_digit() || input[idx - 1] == b'.') {
-            return None;
-        }
-
-        let start = idx;
+    // Parses the 4 octets of an IPv4 address, returns it along with the position following it.
+    fn parse_ipv4(input: &[u8], idx: usize) -> Option<(u32, usize)> {
         let mut pos = idx;
+        let mut addr = 0;
 
-        // Parse 4 octets
         for octet_idx in 0..4 {
             if octet_idx > 0 {
                 if pos >= input.len() || input[pos] != b'.' {
@@ -45,8 +48,94 @@ impl Cidr {
             if value > 255 {
                 return None;
             }
+            addr = addr << 8 | u32::from(value);
+        }
+
+        Some((addr, pos))
+    }
+
+    // Finds an IPv4 network written with a mask, or an IPv4 range.
+    fn try_ipv4_notation(&self, input: &[u8], idx: usize) -> Option<Range<usize>> {
+        // Boundary before: not preceded by digit or dot
+        if idx > 0 && (input[idx - 1].is_ascii_digit() || input[idx - 1] == b'.') {
+            return None;
+        }
+        let (first, pos) = Self::parse_ipv4(input, idx)?;
+        let is_boundary =
+            |end: usize| end >= input.len() || !(input[end].is_ascii_digit() || input[end] == b'.');
+
+        if self.masks && pos < input.len() {
+            let mask_start = match input[pos] {
+                b'/' => pos + 1,
+                b' ' | b'\t' => skip_blanks(input, pos),
+                _ => input.len(),
+            };
+            if let Some((mask, end)) = Self::parse_ipv4(input, mask_start) {
+                if mask_prefix(first, mask).is_some() && is_boundary(end) {
+                    return Some(idx..end);
+                }
+            }
+        }
+
+        if self.ranges {
+            let dash = skip_blanks(input, pos);
+            if dash < input.len() && input[dash] == b'-' {
+                let (last, end) = Self::parse_ipv4(input, skip_blanks(input, dash + 1))?;
+                if last >= first && is_boundary(end) {
+                    return Some(idx..end);
+                }
+            }
+        }
+
+        None
+    }
+
+    // Finds an IPv6 range.
+    fn try_ipv6_range(input: &[u8], idx: usize) -> Option<Range<usize>> {
+        // Boundary before
+        if idx > 0 && (input[idx - 1].is_ascii_alphanumeric() || input[idx - 1] == b':') {
+            return None;
+        }
+        let parse = |start: usize| -> Option<(Ipv6Addr, usize)> {
+            let mut end = start;
+            while end < input.len() && (input[end].is_ascii_hexdigit() || input[end] == b':') {
+                end += 1;
+            }
+            let addr = std::str::from_utf8(&input[start..end]).ok()?;
+            if !Self::is_valid_ipv6(addr.as_bytes()) {
+                return None;
+            }
+            Some((addr.parse().ok()?, end))
+        };
+
+        let (first, pos) = parse(idx)?;
+        let dash = skip_blanks(input, pos);
+        if dash >= input.len() || input[dash] != b'-' {
+            return None;
+        }
+        let (last, end) = parse(skip_blanks(input, dash + 1))?;
+        // Boundary after
+        if last < first
+            || (end < input.len() && (input[end].is_ascii_alphanumeric() || input[end] == b'.'))
+        {
+            return None;
+        }
+        Some(idx..end)
+    }
+
+    fn try_ipv4_cidr(input: &[u8], idx: usize) -> Option<Range<usize>> {
+        if !input[idx].is_ascii_digit() {
+            return None;
         }
 
+        // Boundary before: not preceded by digit or dot
+        if idx > 0 && (input[idx - 1].is_ascii_digit() || input[idx - 1] == b'.') {
+            return None;
+        }
+
+        let start = idx;
+        let (_, mut pos) = Self::parse_ipv4(input, idx)?;
+
         // Must have /prefix
         if pos >= input.len() || input[pos] != b'/' {
             return None;
@@ -218,12 +307,22 @@ impl Finder for Cidr {
                 if let Some(range) = Self::try_ipv4_cidr(input, idx) {
                     return Some(range);
                 }
+                if self.masks || self.ranges {
+                    if let Some(range) = self.try_ipv4_notation(input, idx) {
+                        return Some(range);
+                    }
+                }
             }
 
             if input[idx] == b'[' || input[idx].is_ascii_hexdigit() || input[idx] == b':' {
                 if let Some(range) = Self::try_ipv6_cidr(input, idx) {
                     return Some(range);
                 }
+                if self.ranges {
+                    if let Some(range) = Self::try_ipv6_range(input, idx) {
+                        return Some(range);
+                    }
+                }
             }
 
             idx += 1;
@@ -233,7 +332,324 @@ impl Finder for Cidr {
     }
 }
 
+fn skip_blanks(input: &[u8], mut pos: usize) -> usize {
+    while pos < input.len() && (input[pos] == b' ' || input[pos] == b'\t') {
+        pos += 1;
+    }
+    pos
+}
+
+// Returns the prefix length of a netmask (`255.255.255.0`) or a wildcard mask (`0.0.0.255`). The
+// `0.0.0.0` mask is a wildcard for a single address (`10.0.0.1 0.0.0.0`), unless it comes with
+// the `0.0.0.0` address (the default route).
+fn mask_prefix(addr: u32, mask: u32) -> Option<u8> {
+    if mask == 0 && addr != 0 {
+        Some(32)
+    } else if mask.leading_ones() + mask.trailing_zeros() == 32 {
+        Some(mask.leading_ones() as u8)
+    } else if mask.leading_zeros() + mask.trailing_ones() == 32 {
+        Some(mask.leading_zeros() as u8)
+    } else {
+        None
+    }
+}
+
+/// Parses a network in any of the notations found by the [`Cidr`] finder, or a single address,
+/// and returns the minimal list of CIDR blocks covering it. Returns an empty list if `s` is not a
+/// network.
+///
+/// # Example
+///
+/// ```
+/// use squeeze::cidr::parse_networks;
+///
+/// let networks = |s| -> Vec<String> { parse_networks(s).iter().map(|n| n.to_string()).collect() };
+/// assert_eq!(vec!["10.0.0.0/24"], networks("10.0.0.0 255.255.255.0"));
+/// assert_eq!(vec!["10.0.0.0/16"], networks("10.0.0.0/255.255.0.0"));
+/// assert_eq!(vec!["10.0.0.0/24"], networks("10.0.0.0 0.0.0.255"));
+/// assert_eq!(vec!["10.0.0.0/31", "10.0.0.2/32"], networks("10.0.0.0-10.0.0.2"));
+/// ```
+pub fn parse_networks(s: &str) -> Vec<Network> {
+    let s = s.trim();
+    if let Some(network) = Network::parse(s) {
+        return vec![network];
+    }
+    if let Some((first, last)) = s.split_once('-') {
+        return match (first.trim().parse(), last.trim().parse()) {
+            (Ok(first), Ok(last)) => Network::from_range(first, last),
+            _ => Vec::new(),
+        };
+    }
+    let masked = s
+        .split_once('/')
+        .or_else(|| s.split_once([' ', '\t']))
+        .and_then(|(addr, mask)| {
+            let addr: Ipv4Addr = addr.parse().ok()?;
+            let mask: Ipv4Addr = mask.trim().parse().ok()?;
+            let prefix = mask_prefix(addr.into(), mask.into())?;
+            Network::new(IpAddr::V4(addr), prefix)
+        });
+    masked.into_iter().collect()
+}
+
+/// An IPv4 or IPv6 network, e.g. `10.0.0.0/8`, a single address being a `/32` (or `/128`)
+/// network.
+///
+/// # Example
+///
+/// ```
+/// use squeeze::cidr::{self, Network};
+///
+/// let network = Network::parse("10.0.0.0/8").unwrap();
+/// assert!(network.contains(&Network::parse("10.1.2.3").unwrap()));
+///
+/// let networks = ["10.0.0.0/25", "10.0.0.128/25", "10.0.1.0/24"].map(|s| Network::parse(s).unwrap());
+/// let aggregated: Vec<_> = cidr::aggregate(networks).iter().map(|n| n.to_string()).collect();
+/// assert_eq!(vec!["10.0.0.0/23"], aggregated);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Network {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl Network {
+    /// Returns the network of the given address and prefix length, without its host bits (e.g.
+    /// `10.0.0.0/8` for `10.1.2.3/8`). Returns `None` if the prefix is too long.
+    pub fn new(addr: IpAddr, prefix: u8) -> Option<Self> {
+        let bits = if addr.is_ipv4() { 32 } else { 128 };
+        if u32::from(prefix) > bits {
+            return None;
+        }
+        let start = to_bits(addr) & !host_mask(bits - u32::from(prefix));
+        Some(Network {
+            addr: from_bits(start, addr.is_ipv4()),
+            prefix,
+        })
+    }
+
+    /// Parses a network in the CIDR notation (e.g. `192.168.0.0/16` or `[2001:db8::]/32`), or a
+    /// single address.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        // the zone of an address (e.g. fe80::1%eth0) does not change its network
+        let (addr, _) = ip::parse(addr)?;
+        let prefix = match prefix {
+            Some(prefix) if prefix.bytes().all(|b| b.is_ascii_digit()) => prefix.parse().ok()?,
+            Some(_) => return None,
+            None if addr.is_ipv4() => 32,
+            None => 128,
+        };
+        Network::new(addr, prefix)
+    }
+
+    /// Returns the first address of the network.
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// Returns the prefix length of the network.
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    /// Returns the last address of the network.
+    pub fn last(&self) -> IpAddr {
+        let (_, end) = self.bounds();
+        from_bits(end, self.addr.is_ipv4())
+    }
+
+    /// Returns whether the given network (or address) is part of this network. IPv4 and IPv6
+    /// networks never contain each other.
+    pub fn contains(&self, other: &Network) -> bool {
+        self.addr.is_ipv4() == other.addr.is_ipv4()
+            && other.prefix >= self.prefix
+            && Network::new(other.addr, self.prefix) == Some(*self)
+    }
+
+    /// Returns the scope of all the addresses of the network, or `None` if they have different
+    /// scopes (e.g. `0.0.0.0/0`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use squeeze::{cidr::Network, ip::Scope};
+    ///
+    /// assert_eq!(Some(Scope::Private), Network::parse("10.1.0.0/16").unwrap().scope());
+    /// assert_eq!(None, Network::parse("10.0.0.0/7").unwrap().scope());
+    /// ```
+    pub fn scope(&self) -> Option<Scope> {
+        let scope = ip::scope(self.addr);
+        let mixed = ip::scope(self.last()) != scope
+            || ip::special_purpose()
+                .iter()
+                .any(|(n, s)| *s != scope && n.prefix > self.prefix && self.contains(n));
+        (!mixed).then_some(scope)
+    }
+
+    /// Returns the minimal list of networks covering the addresses from `first` to `last`
+    /// (inclusive), e.g. `10.0.0.0/24` and `10.0.1.0/25` for `10.0.0.0` to `10.0.1.127`. Returns
+    /// an empty list if the addresses are not of the same family, or are not in order.
+    pub fn from_range(first: IpAddr, last: IpAddr) -> Vec<Network> {
+        if first.is_ipv4() != last.is_ipv4() || first > last {
+            return Vec::new();
+        }
+        networks_of(first.is_ipv4(), to_bits(first), to_bits(last))
+    }
+
+    /// Returns the minimal list of networks covering the addresses of this network which are
+    /// not part of the given one.
+    pub fn exclude(&self, other: &Network) -> Vec<Network> {
+        subtract([*self], [*other])
+    }
+
+    fn bits(&self) -> u32 {
+        if self.addr.is_ipv4() {
+            32
+        } else {
+            128
+        }
+    }
+
+    // the first and last addresses, as integers
+    fn bounds(&self) -> (u128, u128) {
+        let start = to_bits(self.addr);
+        (
+            start,
+            start | host_mask(self.bits() - u32::from(self.prefix)),
+        )
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+/// Merges the overlapping and adjacent networks into the minimal list of networks covering the
+/// same addresses, IPv4 networks first.
+pub fn aggregate(networks: impl IntoIterator<Item = Network>) -> Vec<Network> {
+    to_networks(merge(networks))
+}
+
+/// Returns the minimal list of networks covering the addresses of `networks` which are not part
+/// of any of the `excluded` ones.
+pub fn subtract(
+    networks: impl IntoIterator<Item = Network>,
+    excluded: impl IntoIterator<Item = Network>,
+) -> Vec<Network> {
+    let excluded = merge(excluded);
+    let mut remaining = Vec::new();
+    for (ipv4, mut start, end) in merge(networks) {
+        let mut done = false;
+        for &(ex_ipv4, ex_start, ex_end) in &excluded {
+            if ex_ipv4 != ipv4 || ex_end < start || ex_start > end {
+                continue;
+            }
+            if ex_start > start {
+                remaining.push((ipv4, start, ex_start - 1));
+            }
+            if ex_end >= end {
+                done = true;
+                break;
+            }
+            start = ex_end + 1;
+        }
+        if !done {
+            remaining.push((ipv4, start, end));
+        }
+    }
+    to_networks(remaining)
+}
+
+// Sorts the networks as (is IPv4, first, last) ranges of addresses, merging the overlapping and
+// adjacent ones. IPv4 ranges come first.
+fn merge(networks: impl IntoIterator<Item = Network>) -> Vec<(bool, u128, u128)> {
+    let mut ranges: Vec<(bool, u128, u128)> = networks
+        .into_iter()
+        .map(|n| {
+            let (start, end) = n.bounds();
+            (!n.addr.is_ipv4(), start, end)
+        })
+        .collect();
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(bool, u128, u128)> = Vec::with_capacity(ranges.len());
+    for (ipv6, start, end) in ranges {
+        match merged.last_mut() {
+            Some((last_ipv6, _, last_end))
+                if *last_ipv6 == ipv6 && (*last_end == u128::MAX || start <= *last_end + 1) =>
+            {
+                *last_end = end.max(*last_end);
+            }
+            _ => merged.push((ipv6, start, end)),
+        }
+    }
+    merged
+        .into_iter()
+        .map(|(ipv6, start, end)| (!ipv6, start, end))
+        .collect()
+}
+
+fn to_networks(ranges: Vec<(bool, u128, u128)>) -> Vec<Network> {
+    ranges
+        .into_iter()
+        .flat_map(|(ipv4, start, end)| networks_of(ipv4, start, end))
+        .collect()
+}
+
+// Splits a range of addresses into the largest aligned blocks.
+fn networks_of(ipv4: bool, mut start: u128, end: u128) -> Vec<Network> {
+    let bits = if ipv4 { 32 } else { 128 };
+    let mut networks = Vec::new();
+    loop {
+        // the largest block starting at `start`, then shrunk until it ends before `end`
+        let mut host_bits = start.trailing_zeros().min(bits);
+        while start | host_mask(host_bits) > end {
+            host_bits -= 1;
+        }
+        let last = start | host_mask(host_bits);
+        networks.push(Network {
+            addr: from_bits(start, ipv4),
+            prefix: (bits - host_bits) as u8,
+        });
+        if last >= end {
+            break;
+        }
+        start = last + 1;
+    }
+    networks
+}
+
+fn host_mask(host_bits: u32) -> u128 {
+    if host_bits >= 128 {
+        u128::MAX
+    } else {
+        (1 << host_bits) - 1
+    }
+}
+
+fn to_bits(addr: IpAddr) -> u128 {
+    match addr {
+        IpAddr::V4(addr) => u32::from(addr).into(),
+        IpAddr::V6(addr) => addr.into(),
+    }
+}
+
+fn from_bits(bits: u128, ipv4: bool) -> IpAddr {
+    if ipv4 {
+        IpAddr::V4(Ipv4Addr::from(bits as u32))
+    } else {
+        IpAddr::V6(Ipv6Addr::from(bits))
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::useless_vec)]
 mod tests {
     use super::*;
 
@@ -398,4 +814,248 @@ mod tests {
         let range = finder.find(input).unwrap();
         assert_eq!("172.16.0.0/12", &input[range]);
     }
+
+    // Networks
+    fn networks(v: &[&str]) -> Vec<Network> {
+        v.iter().map(|s| Network::parse(s).unwrap()).collect()
+    }
+
+    fn strings(v: Vec<Network>) -> Vec<String> {
+        v.iter().map(Network::to_string).collect()
+    }
+
+    #[test]
+    fn network_should_parse_cidrs_and_addresses() {
+        for (input, expected) in vec![
+            ("10.0.0.0/8", Some("10.0.0.0/8")),
+            ("10.1.2.3/8", Some("10.0.0.0/8")),
+            ("10.1.2.3", Some("10.1.2.3/32")),
+            ("0.0.0.0/0", Some("0.0.0.0/0")),
+            ("2001:db8::/32", Some("2001:db8::/32")),
+            ("[2001:db8::1]/64", Some("2001:db8::/64")),
+            ("2001:0DB8::1", Some("2001:db8::1/128")),
+            ("fe80::1%eth0", Some("fe80::1/128")),
+            ("10.0.0.0/33", None),
+            ("10.0.0.0/+8", None),
+            ("10.0.0.0/", None),
+            ("::/129", None),
+            ("example.com", None),
+        ] {
+            assert_eq!(
+                expected.map(str::to_string),
+                Network::parse(input).map(|n| n.to_string()),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn network_should_contain_its_subnets_and_addresses() {
+        let network = Network::parse("10.0.0.0/8").unwrap();
+        for (input, expected) in vec![
+            ("10.0.0.0/8", true),
+            ("10.255.0.0/16", true),
+            ("10.1.2.3", true),
+            ("11.0.0.0", false),
+            ("0.0.0.0/0", false),
+            ("::ffff:10.0.0.1", false),
+        ] {
+            let other = Network::parse(input).unwrap();
+            assert_eq!(expected, network.contains(&other), "{}", input);
+        }
+        let network = Network::parse("2001:db8::/32").unwrap();
+        assert!(network.contains(&Network::parse("2001:db8:ffff::1").unwrap()));
+        assert!(!network.contains(&Network::parse("2001:db9::").unwrap()));
+        assert_eq!(
+            "2001:db8:ffff:ffff:ffff:ffff:ffff:ffff",
+            network.last().to_string()
+        );
+    }
+
+    #[test]
+    fn aggregate_should_merge_overlapping_and_adjacent_networks() {
+        for (input, expected) in vec![
+            (vec!["10.0.0.0/24", "10.0.1.0/24"], vec!["10.0.0.0/23"]),
+            (
+                vec!["10.0.1.0/24", "10.0.2.0/24"],
+                vec!["10.0.1.0/24", "10.0.2.0/24"],
+            ),
+            (
+                vec!["10.0.0.0/8", "10.1.0.0/16", "10.1.2.3"],
+                vec!["10.0.0.0/8"],
+            ),
+            (
+                vec!["10.0.0.1", "10.0.0.2", "10.0.0.3", "10.0.0.0"],
+                vec!["10.0.0.0/30"],
+            ),
+            (
+                vec!["2001:db8::/33", "2001:db8:8000::/33", "192.168.0.0/16"],
+                vec!["192.168.0.0/16", "2001:db8::/32"],
+            ),
+            (vec!["0.0.0.0/1", "128.0.0.0/1"], vec!["0.0.0.0/0"]),
+            (vec!["::/0", "::1"], vec!["::/0"]),
+            (vec![], vec![]),
+        ] {
+            assert_eq!(
+                expected,
+                strings(aggregate(networks(&input))),
+                "{:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn subtract_should_exclude_networks() {
+        for (input, excluded, expected) in vec![
+            (vec!["10.0.0.0/8"], vec!["10.0.0.0/9"], vec!["10.128.0.0/9"]),
+            (
+                vec!["10.0.0.0/24"],
+                vec!["10.0.0.128/26"],
+                vec!["10.0.0.0/25", "10.0.0.192/26"],
+            ),
+            (
+                vec!["10.0.0.0/30"],
+                vec!["10.0.0.1", "10.0.0.2"],
+                vec!["10.0.0.0/32", "10.0.0.3/32"],
+            ),
+            (vec!["10.0.0.0/24"], vec!["10.0.0.0/8"], vec![]),
+            (vec!["10.0.0.0/24"], vec!["::/0"], vec!["10.0.0.0/24"]),
+            (vec!["::/0"], vec!["::/1"], vec!["8000::/1"]),
+        ] {
+            assert_eq!(
+                expected,
+                strings(subtract(networks(&input), networks(&excluded))),
+                "{:?} - {:?}",
+                input,
+                excluded
+            );
+        }
+        let network = Network::parse("192.168.0.0/23").unwrap();
+        assert_eq!(
+            vec!["192.168.1.0/24"],
+            strings(network.exclude(&Network::parse("192.168.0.0/24").unwrap()))
+        );
+    }
+
+    #[test]
+    fn from_range_should_return_the_minimal_networks() {
+        for (first, last, expected) in vec![
+            ("10.0.0.0", "10.0.1.127", vec!["10.0.0.0/24", "10.0.1.0/25"]),
+            (
+                "10.0.0.1",
+                "10.0.0.6",
+                vec!["10.0.0.1/32", "10.0.0.2/31", "10.0.0.4/31", "10.0.0.6/32"],
+            ),
+            ("0.0.0.0", "255.255.255.255", vec!["0.0.0.0/0"]),
+            (
+                "::",
+                "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff",
+                vec!["::/0"],
+            ),
+            ("10.0.0.2", "10.0.0.1", vec![]),
+            ("10.0.0.1", "::1", vec![]),
+        ] {
+            assert_eq!(
+                expected,
+                strings(Network::from_range(
+                    first.parse().unwrap(),
+                    last.parse().unwrap()
+                )),
+                "{} - {}",
+                first,
+                last
+            );
+        }
+    }
+
+    // Masks and ranges
+    #[test]
+    fn find_should_extract_masks_and_ranges_if_asked_to() {
+        let finder = Cidr {
+            masks: true,
+            ranges: true,
+        };
+        for (input, expected) in vec![
+            (
+                "ip route 10.0.0.0 255.255.255.0 eth0",
+                Some("10.0.0.0 255.255.255.0"),
+            ),
+            ("net 10.0.0.0/255.255.0.0;", Some("10.0.0.0/255.255.0.0")),
+            (
+                "permit ip 10.0.0.0 0.0.0.255 any",
+                Some("10.0.0.0 0.0.0.255"),
+            ),
+            ("permit ip 10.0.0.1 0.0.0.0 any", Some("10.0.0.1 0.0.0.0")),
+            ("pool 10.0.0.1-10.0.0.50", Some("10.0.0.1-10.0.0.50")),
+            ("pool 10.0.0.1 - 10.0.0.50.", None),
+            ("pool 10.0.0.1 - 10.0.0.50 ok", Some("10.0.0.1 - 10.0.0.50")),
+            (
+                "pool 2001:db8::1-2001:db8::ff",
+                Some("2001:db8::1-2001:db8::ff"),
+            ),
+            ("10.0.0.0/24", Some("10.0.0.0/24")),
+            ("hosts 10.0.0.1 10.0.0.2", None),
+            ("10.0.0.0 255.0.255.0", None),
+            ("10.0.0.50-10.0.0.1", None),
+            ("2001:db8::ff-2001:db8::1", None),
+            ("dead-beef", None),
+        ] {
+            assert_eq!(expected, finder.find(input).map(|r| &input[r]), "{}", input);
+        }
+    }
+
+    #[test]
+    fn find_should_not_extract_masks_and_ranges_by_default() {
+        let finder = Cidr::default();
+        for input in vec!["10.0.0.0 255.255.255.0", "10.0.0.1-10.0.0.50"] {
+            assert_eq!(None, finder.find(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_networks_should_convert_to_cidr_blocks() {
+        for (input, expected) in vec![
+            ("10.0.0.0 255.255.255.0", vec!["10.0.0.0/24"]),
+            ("10.0.0.0/255.255.0.0", vec!["10.0.0.0/16"]),
+            ("10.0.0.0 0.0.0.255", vec!["10.0.0.0/24"]),
+            ("10.0.0.1 0.0.0.0", vec!["10.0.0.1/32"]),
+            ("0.0.0.0 0.0.0.0", vec!["0.0.0.0/0"]),
+            ("10.0.0.1 255.255.255.255", vec!["10.0.0.1/32"]),
+            (
+                "10.0.0.1 - 10.0.0.6",
+                vec!["10.0.0.1/32", "10.0.0.2/31", "10.0.0.4/31", "10.0.0.6/32"],
+            ),
+            ("2001:db8::-2001:db8::ffff", vec!["2001:db8::/112"]),
+            ("10.1.2.3/8", vec!["10.0.0.0/8"]),
+            ("10.0.0.0 255.0.255.0", vec![]),
+            ("10.0.0.0 - ::1", vec![]),
+            ("nope", vec![]),
+        ] {
+            assert_eq!(expected, strings(parse_networks(input)), "{}", input);
+        }
+    }
+
+    #[test]
+    fn network_scope_should_be_the_scope_of_all_its_addresses() {
+        for (input, expected) in vec![
+            ("10.0.0.0/8", Some(Scope::Private)),
+            ("192.168.1.0/24", Some(Scope::Private)),
+            ("8.8.8.0/24", Some(Scope::Public)),
+            ("10.0.0.0/7", None),
+            ("0.0.0.0/0", None),
+            ("192.0.0.0/16", None),
+            ("2001:db8::/48", Some(Scope::Documentation)),
+            ("2000::/3", None),
+            ("fe80::/64", Some(Scope::LinkLocal)),
+        ] {
+            assert_eq!(
+                expected,
+                Network::parse(input).unwrap().scope(),
+                "{}",
+                input
+            );
+        }
+    }
 }
@@ -0,0 +1,399 @@
+//! URL cleaning: tracking parameters removal and redirectors unwrapping.
+//!
+//! URLs copied from emails and social media are often wrapped in redirectors
+//! (`https://www.google.com/url?q=...`, Outlook safelinks, ...) and decorated with
+//! tracking parameters (`utm_*`, `fbclid`, `gclid`, ...). The [`Cleaner`] strips them
+//! according to an embedded rule list, which can be extended with [`Cleaner::add_rules`].
+//!
+//! # Example
+//!
+//! ```
+//! use squeeze::clean::Cleaner;
+//!
+//! let cleaner = Cleaner::default();
+//! assert_eq!(
+//!     cleaner.clean("https://www.google.com/url?q=https%3A%2F%2Fexample.com%2F%3Fid%3D1%26utm_source%3Dx&sa=D"),
+//!     "https://example.com/?id=1",
+//! );
+//! ```
+//!
+//! # Rules
+//!
+//! Rules are written one per line, blank lines and lines starting with `#` are ignored:
+//!
+//! ```text
+//! # remove the `ref` query parameter from all URLs
+//! param ref
+//! # remove the `si` query parameter from youtube.com and its subdomains
+//! param si *.youtube.com
+//! # unwrap https://out.example.com/go?to=<url>
+//! redirect out.example.com /go to
+//! ```
+//!
+//! Parameter names may end with `*` to match a prefix (`utm_*`), and hosts may start with `*.`
+//! to match a domain and all its subdomains. Matching is case-insensitive.
+
+use super::rules::RuleError;
+use super::uri::{percent_decode, URI};
+
+const DEFAULT_RULES: &str = include_str!("clean-rules.txt");
+
+// Redirectors can be nested (e.g. a safelink wrapping a google redirect), but not infinitely.
+const MAX_REDIRECTS: usize = 8;
+
+struct ParamRule {
+    name: String,
+    host: Option<String>,
+}
+
+struct RedirectRule {
+    host: String,
+    path: String,
+    param: String,
+}
+
+/// Cleans URLs from their tracking parameters and redirectors.
+///
+/// The default instance uses the embedded rule list. Use [`Cleaner::empty`] to start from
+/// scratch.
+pub struct Cleaner {
+    params: Vec<ParamRule>,
+    redirects: Vec<RedirectRule>,
+    finder: URI,
+}
+
+impl Default for Cleaner {
+    fn default() -> Self {
+        let mut cleaner = Self::empty();
+        cleaner
+            .add_rules(DEFAULT_RULES)
+            .expect("implementation error: the default cleaning rules are invalid");
+        cleaner
+    }
+}
+
+impl Cleaner {
+    /// Creates a cleaner without any rule.
+    pub fn empty() -> Self {
+        // the URLs to clean have already been delimited
+        let mut finder = URI::default();
+        finder.strict = true;
+
+        Cleaner {
+            params: Vec::new(),
+            redirects: Vec::new(),
+            finder,
+        }
+    }
+
+    /// Parses `rules` (see the [module documentation](self)) and adds them to the cleaner.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on the first invalid rule, in which case no rule is added.
+    pub fn add_rules(&mut self, rules: &str) -> Result<(), RuleError> {
+        let mut params = Vec::new();
+        let mut redirects = Vec::new();
+
+        for (i, line) in rules.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: &str| RuleError::new(i + 1, message);
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["param", name] => params.push(ParamRule {
+                    name: name.to_ascii_lowercase(),
+                    host: None,
+                }),
+                ["param", name, host] => params.push(ParamRule {
+                    name: name.to_ascii_lowercase(),
+                    host: Some(host.to_ascii_lowercase()),
+                }),
+                ["param", ..] => return Err(error("expected: param NAME [HOST]")),
+                ["redirect", host, path, param] => {
+                    if !path.starts_with('/') {
+                        return Err(error("the redirect path must start with '/'"));
+                    }
+                    redirects.push(RedirectRule {
+                        host: host.to_ascii_lowercase(),
+                        path: path.to_ascii_lowercase(),
+                        param: param.to_ascii_lowercase(),
+                    })
+                }
+                ["redirect", ..] => return Err(error("expected: redirect HOST PATH PARAM")),
+                [kind, ..] => return Err(error(&format!("unknown rule '{}'", kind))),
+                [] => unreachable!(),
+            }
+        }
+
+        self.params.extend(params);
+        self.redirects.extend(redirects);
+        Ok(())
+    }
+
+    /// Unwraps the redirectors and removes the tracking parameters of `url`.
+    ///
+    /// URLs that cannot be parsed are returned untouched.
+    pub fn clean(&self, url: &str) -> String {
+        let mut url = url.to_string();
+        for _ in 0..MAX_REDIRECTS {
+            match self.unwrap_redirect(&url) {
+                Some(target) => url = target,
+                None => break,
+            }
+        }
+        self.strip_params(&url)
+    }
+
+    fn unwrap_redirect(&self, url: &str) -> Option<String> {
+        let c = self.finder.components(url)?;
+        let host = c.host?.to_ascii_lowercase();
+        let rule = self
+            .redirects
+            .iter()
+            .find(|r| r.path.eq_ignore_ascii_case(c.path) && match_host(&r.host, &host))?;
+        let (_, target) = c
+            .query_pairs()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&rule.param))?;
+
+        // only unwrap absolute URIs, relative ones would need the redirector as a base
+        match self.finder.components(&target) {
+            Some(t) if t.scheme.is_some() && t.host.is_some_and(|h| !h.is_empty()) => Some(target),
+            _ => None,
+        }
+    }
+
+    fn strip_params(&self, url: &str) -> String {
+        let host = match self.finder.components(url) {
+            Some(c) => c.host.unwrap_or_default().to_ascii_lowercase(),
+            None => return url.to_string(),
+        };
+
+        let (before_fragment, fragment) = match url.split_once('#') {
+            Some((before, fragment)) => (before, Some(fragment)),
+            None => (url, None),
+        };
+        let (base, query) = match before_fragment.split_once('?') {
+            Some((base, query)) => (base, query),
+            None => return url.to_string(),
+        };
+
+        // the separators are kept, the parameters being separated by `&` or `;`
+        let mut kept = String::new();
+        for piece in query.split_inclusive(['&', ';']) {
+            let pair = piece.strip_suffix(['&', ';']).unwrap_or(piece);
+            let name = pair.split_once('=').map_or(pair, |(name, _)| name);
+            let name = percent_decode(name).to_ascii_lowercase();
+            let tracking = self.params.iter().any(|rule| {
+                match_name(&rule.name, &name)
+                    && rule.host.as_ref().is_none_or(|h| match_host(h, &host))
+            });
+            if !tracking {
+                kept.push_str(piece);
+            } else if piece == pair {
+                // the last parameter, the one before has to lose its separator
+                kept.pop();
+            }
+        }
+
+        let mut out = base.to_string();
+        if !kept.is_empty() {
+            out.push('?');
+            out.push_str(&kept);
+        }
+        if let Some(fragment) = fragment {
+            out.push('#');
+            out.push_str(fragment);
+        }
+        out
+    }
+}
+
+// `pattern` and `name` are expected to be lowercase
+fn match_name(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+// `pattern` and `host` are expected to be lowercase
+fn match_host(pattern: &str, host: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(domain) => {
+            host == domain
+                || host
+                    .strip_suffix(domain)
+                    .is_some_and(|sub| sub.ends_with('.'))
+        }
+        None => pattern == host,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::useless_vec)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clean_should_remove_tracking_params() {
+        let cleaner = Cleaner::default();
+        for (input, expected) in vec![
+            (
+                "https://example.com/?utm_source=x&utm_medium=y",
+                "https://example.com/",
+            ),
+            (
+                "https://example.com/a?id=1&fbclid=abc&page=2#top",
+                "https://example.com/a?id=1&page=2#top",
+            ),
+            (
+                "https://example.com/?UTM_Campaign=x&gclid=1#frag",
+                "https://example.com/#frag",
+            ),
+            ("https://example.com/?id=1", "https://example.com/?id=1"),
+            ("https://example.com/", "https://example.com/"),
+            (
+                "https://example.com/?id=1;utm_source=x;page=2",
+                "https://example.com/?id=1;page=2",
+            ),
+            (
+                "https://example.com/?id=1&utm_source=x;gclid=1",
+                "https://example.com/?id=1",
+            ),
+            ("https://example.com/?id=1&", "https://example.com/?id=1&"),
+        ] {
+            assert_eq!(expected, cleaner.clean(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn clean_should_remove_host_specific_params_on_their_host_only() {
+        let cleaner = Cleaner::default();
+        assert_eq!(
+            "https://youtu.be/dQw4w9WgXcQ?t=42",
+            cleaner.clean("https://youtu.be/dQw4w9WgXcQ?si=abc&t=42")
+        );
+        assert_eq!(
+            "https://music.youtube.com/watch?v=1",
+            cleaner.clean("https://music.youtube.com/watch?v=1&si=abc")
+        );
+        assert_eq!(
+            "https://example.com/?si=abc",
+            cleaner.clean("https://example.com/?si=abc")
+        );
+    }
+
+    #[test]
+    fn clean_should_unwrap_redirectors() {
+        let cleaner = Cleaner::default();
+        for (input, expected) in vec![
+            (
+                "https://www.google.com/url?q=https://example.com/page&sa=D&ust=1",
+                "https://example.com/page",
+            ),
+            (
+                "https://nam12.safelinks.protection.outlook.com/?url=https%3A%2F%2Fexample.com%2F%3Fa%3D1&data=05%7C01",
+                "https://example.com/?a=1",
+            ),
+            (
+                "https://l.facebook.com/l.php?u=https%3A%2F%2Fexample.com%2F%3Ffbclid%3Dx&h=AT0",
+                "https://example.com/",
+            ),
+            (
+                "https://WWW.Google.com/URL?Q=https://example.com/page",
+                "https://example.com/page",
+            ),
+        ] {
+            assert_eq!(expected, cleaner.clean(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn clean_should_unwrap_nested_redirectors() {
+        let cleaner = Cleaner::default();
+        let inner = "https://www.google.com/url?q=https%3A%2F%2Fexample.com%2F%3Futm_source%3Dx";
+        let outer = format!(
+            "https://eur01.safelinks.protection.outlook.com/?url={}",
+            inner
+                .replace('%', "%25")
+                .replace(':', "%3A")
+                .replace('/', "%2F")
+                .replace('?', "%3F")
+                .replace('=', "%3D")
+        );
+        assert_eq!("https://example.com/", cleaner.clean(&outer));
+    }
+
+    #[test]
+    fn clean_should_not_unwrap_relative_or_missing_targets() {
+        let cleaner = Cleaner::default();
+        for input in vec![
+            "https://www.google.com/url?q=/relative",
+            "https://www.google.com/url?other=1",
+            "https://www.google.com/search?q=https://example.com",
+        ] {
+            assert_eq!(input, cleaner.clean(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn clean_should_leave_invalid_urls_untouched() {
+        let cleaner = Cleaner::default();
+        for input in vec!["", "not a url", "https://example.com/ space?utm_source=x"] {
+            assert_eq!(input, cleaner.clean(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn add_rules_should_extend_the_rule_list() {
+        let mut cleaner = Cleaner::default();
+        cleaner
+            .add_rules("# comment\n\nparam ref\nredirect out.example.com /go to\n")
+            .unwrap();
+        assert_eq!(
+            "https://example.com/?id=1",
+            cleaner.clean(
+                "https://out.example.com/go?to=https%3A%2F%2Fexample.com%2F%3Fid%3D1%26ref%3Dx"
+            )
+        );
+    }
+
+    #[test]
+    fn empty_should_not_clean_anything() {
+        let cleaner = Cleaner::empty();
+        let input = "https://www.google.com/url?q=https://example.com&utm_source=x";
+        assert_eq!(input, cleaner.clean(input));
+    }
+
+    #[test]
+    fn add_rules_should_reject_invalid_rules() {
+        for (rules, line) in vec![
+            ("unknown rule", 1),
+            ("param", 1),
+            ("param a b c", 1),
+            ("\n\nredirect example.com", 3),
+            ("redirect example.com go to", 1),
+        ] {
+            let mut cleaner = Cleaner::empty();
+            assert_eq!(
+                Some(line),
+                cleaner.add_rules(rules).err().map(|e| e.line),
+                "{}",
+                rules
+            );
+        }
+    }
+
+    #[test]
+    fn match_host_should_support_domain_wildcards() {
+        assert!(match_host("example.com", "example.com"));
+        assert!(!match_host("example.com", "www.example.com"));
+        assert!(match_host("*.example.com", "example.com"));
+        assert!(match_host("*.example.com", "www.example.com"));
+        assert!(!match_host("*.example.com", "notexample.com"));
+    }
+}
@@ -18,17 +18,17 @@
 //! }
 //! ```
 
+use super::comment::{Language, Lexer};
 use super::Finder;
 use regex::Regex;
 use std::collections::HashSet;
 use std::ops::Range;
-use std::sync::OnceLock;
 
-fn default_mnemonics() -> &'static HashSet<String> {
-    static DEFAULT_MNEMONICS: OnceLock<HashSet<String>> = OnceLock::new();
-    DEFAULT_MNEMONICS.get_or_init(|| {
-        [
-            // todo
+/// The PEP 350 mnemonics, grouped by the canonical category they belong to.
+const DEFAULT_CATEGORIES: &[(&str, &[&str])] = &[
+    (
+        "todo",
+        &[
             "TODO",
             "MILESTONE",
             "MLSTN",
@@ -36,7 +36,11 @@ fn default_mnemonics() -> &'static HashSet<String> {
             "YAGNI",
             "TBD",
             "TOBEDONE",
-            // fixme
+        ],
+    ),
+    (
+        "fixme",
+        &[
             "FIXME",
             "XXX",
             "DEBUG",
@@ -48,10 +52,12 @@ fn default_mnemonics() -> &'static HashSet<String> {
             "SMELL",
             "NEEDSWORK",
             "INSPECT",
-            // bug
-            "BUG",
-            "BUGFIX",
-            // nobug
+        ],
+    ),
+    ("bug", &["BUG", "BUGFIX"]),
+    (
+        "nobug",
+        &[
             "NOBUG",
             "NOFIX",
             "WONTFIX",
@@ -59,77 +65,374 @@ fn default_mnemonics() -> &'static HashSet<String> {
             "NEVERFIX",
             "UNFIXABLE",
             "CANTFIX",
-            // req
-            "REQ",
-            "REQUIREMENT",
-            "STORY",
-            // rfe
-            "RFE",
-            "FEETCH",
-            "NYI",
-            "FR",
-            "FTRQ",
-            "FTR",
-            // idea
-            "IDEA",
-            // ???
-            "???",
-            "QUESTION",
-            "QUEST",
-            "QSTN",
-            "WTF",
-            // !!!
-            "!!!",
-            "ALERT",
-            // hack
-            "HACK",
-            "CLEVER",
-            "MAGIC",
-            // port
-            "PORT",
-            "PORTABILITY",
-            "WKRD",
-            // caveat
-            "CAVEAT",
-            "CAV",
-            "CAVT",
-            "WARNING",
-            "CAUTION",
-            // note
-            "NOTE",
-            "HELP",
-            // faq
-            "FAQ",
-            // gloss
-            "GLOSS",
-            "GLOSSARY",
-            // see
-            "SEE",
-            "REF",
-            "REFERENCE",
-            // todoc
-            "TODOC",
-            "DOCDO",
-            "DODOC",
-            "NEEDSDOC",
-            "EXPLAIN",
-            "DOCUMENT",
-            // cred
-            "CRED",
-            "CREDIT",
-            "THANKS",
-            // stat
-            "STAT",
-            "STATUS",
-            // rvd
-            "RVD",
-            "REVIEWED",
-            "REVIEW",
-        ]
-        .iter()
-        .map(|s| s.to_string())
-        .collect()
-    })
+        ],
+    ),
+    ("req", &["REQ", "REQUIREMENT", "STORY"]),
+    ("rfe", &["RFE", "FEETCH", "NYI", "FR", "FTRQ", "FTR"]),
+    ("idea", &["IDEA"]),
+    ("???", &["???", "QUESTION", "QUEST", "QSTN", "WTF"]),
+    ("!!!", &["!!!", "ALERT"]),
+    ("hack", &["HACK", "CLEVER", "MAGIC"]),
+    ("port", &["PORT", "PORTABILITY", "WKRD"]),
+    ("caveat", &["CAVEAT", "CAV", "CAVT", "WARNING", "CAUTION"]),
+    ("note", &["NOTE", "HELP"]),
+    ("faq", &["FAQ"]),
+    ("gloss", &["GLOSS", "GLOSSARY"]),
+    ("see", &["SEE", "REF", "REFERENCE"]),
+    (
+        "todoc",
+        &["TODOC", "DOCDO", "DODOC", "NEEDSDOC", "EXPLAIN", "DOCUMENT"],
+    ),
+    ("cred", &["CRED", "CREDIT", "THANKS"]),
+    ("stat", &["STAT", "STATUS"]),
+    ("rvd", &["RVD", "REVIEWED", "REVIEW"]),
+];
+
+/// A mnemonic to search for, see [`Codetag::define`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mnemonic {
+    /// The mnemonic (e.g. `SAFETY`), matched case-insensitively.
+    pub name: String,
+    /// The canonical category it belongs to (e.g. `note`).
+    pub category: String,
+    /// Whether the mnemonic must be followed by a colon, which prevents matching it in prose.
+    pub colon: bool,
+}
+
+impl Mnemonic {
+    /// Returns a mnemonic of the given category, which must be followed by a colon.
+    pub fn new(name: &str, category: &str) -> Self {
+        Mnemonic {
+            name: name.to_uppercase(),
+            category: category.to_lowercase(),
+            colon: true,
+        }
+    }
+}
+
+/// The fields of a codetag, as defined by
+/// [PEP 350](https://peps.python.org/pep-0350/#field-syntax).
+///
+/// Fields are read from the trailing `<...>` block of a codetag (e.g. `<MLB 2024-01-15 p:2>`),
+/// and from the parenthesized group following the mnemonic (e.g. `TODO(john, #42):`).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Fields {
+    /// The originator/assignee initials or names (e.g. `MLB` or `john`).
+    pub authors: Vec<String>,
+    /// The origination date (e.g. `2024-01-15`).
+    pub date: Option<String>,
+    /// The due date (`d:`).
+    pub due: Option<String>,
+    /// The priority (`p:`), the lower the more urgent.
+    pub priority: Option<u8>,
+    /// The ticket id (`i:`, or `#42` in the parenthesized group).
+    pub ticket: Option<String>,
+    /// The tracker (`t:`).
+    pub tracker: Option<String>,
+    /// The status (`s:`).
+    pub status: Option<String>,
+    /// The category (`c:`).
+    pub category: Option<String>,
+}
+
+impl Fields {
+    // Parses a whitespace or comma separated list of fields, returns `false` if one of them is
+    // invalid.
+    fn parse_into(&mut self, s: &str) -> bool {
+        for token in s.split(|c: char| c == ',' || c.is_whitespace()) {
+            if token.is_empty() {
+                continue;
+            }
+            if let Some((key, value)) = token.split_once(':') {
+                if value.is_empty() {
+                    return false;
+                }
+                let field = match key {
+                    "d" => &mut self.due,
+                    "i" => &mut self.ticket,
+                    "t" => &mut self.tracker,
+                    "s" => &mut self.status,
+                    "c" => &mut self.category,
+                    "p" => match value.parse() {
+                        Ok(priority) => {
+                            self.priority = Some(priority);
+                            continue;
+                        }
+                        Err(_) => return false,
+                    },
+                    _ => return false,
+                };
+                *field = Some(value.to_string());
+            } else if is_date(token) {
+                self.date = Some(token.to_string());
+            } else if let Some(ticket) = token.strip_prefix('#') {
+                self.ticket = Some(ticket.to_string());
+            } else {
+                self.authors.push(token.to_string());
+            }
+        }
+        true
+    }
+}
+
+// YYYY[-MM[-DD]]
+fn is_date(s: &str) -> bool {
+    let parts: Vec<&str> = s.split('-').collect();
+    parts.len() <= 3
+        && parts[0].len() == 4
+        && parts[1..].iter().all(|p| p.len() == 2)
+        && parts.iter().all(|p| p.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// A codetag parsed by [`Codetag::find_tag`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Tag {
+    /// The mnemonic, as written in the text (e.g. `todo`).
+    pub mnemonic: String,
+    /// The description, without the mnemonic nor the fields.
+    pub description: String,
+    /// The fields of the codetag.
+    pub fields: Fields,
+}
+
+impl Tag {
+    // Sets the description from the text following the mnemonic, and merges the fields of its
+    // trailing block. Returns whether such a block (or the `<>` closing delimiter) was found.
+    fn describe(&mut self, s: &str) -> bool {
+        let mut description = s.trim();
+        let mut closed = false;
+        if let Some(open) = description
+            .strip_suffix('>')
+            .and_then(|d| d.rfind('<'))
+            .filter(|&open| open == 0 || description[..open].ends_with(char::is_whitespace))
+        {
+            let mut fields = self.fields.clone();
+            if fields.parse_into(&description[open + 1..description.len() - 1]) {
+                self.fields = fields;
+                description = description[..open].trim_end();
+                closed = true;
+            }
+        }
+        self.description = description.to_string();
+        closed
+    }
+}
+
+/// A codetag found by a [`Scanner`], possibly spanning several lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occurrence {
+    /// The text of the codetag, as returned by [`Finder::find`], with its continuation lines
+    /// joined by a space.
+    pub text: String,
+    /// The parsed codetag.
+    pub tag: Tag,
+    /// The line the codetag starts on (1-based).
+    pub line: usize,
+    /// The line the codetag ends on (1-based).
+    pub end_line: usize,
+}
+
+// A codetag which may continue on the next lines.
+struct Pending {
+    occurrence: Occurrence,
+    // the tag before its description is parsed
+    tag: Tag,
+    // the text following the mnemonic
+    rest: String,
+    // how the continuation lines must start
+    continuation: Continuation,
+}
+
+enum Continuation {
+    // the next lines of a block comment or docstring
+    Block,
+    // line comments with the same indentation and delimiter
+    Line { width: usize, delimiter: String },
+    // lines starting with the same decoration, when the language is unknown
+    Text { decoration: String },
+}
+
+impl Pending {
+    fn complete(mut self) -> Occurrence {
+        self.tag.describe(&self.rest);
+        self.occurrence.tag = self.tag;
+        self.occurrence
+    }
+}
+
+/// Scans a document for codetags, one line at a time.
+///
+/// When a language is given, only its comments are searched (see [`crate::comment`]). When
+/// [`Scanner::continuation`] is set, the comment lines following a codetag with the same
+/// indentation and delimiter are joined to it, until an empty line, another codetag, the end of
+/// the comment, or a trailing field block.
+///
+/// # Example
+///
+/// ```
+/// use squeeze::codetag::{Codetag, Scanner};
+/// use squeeze::comment::Language;
+///
+/// let mut finder = Codetag::default();
+/// finder.build_mnemonics_regex().unwrap();
+///
+/// let mut scanner = Scanner::new(&finder, Language::from_name("python"));
+/// scanner.continuation = true;
+/// assert!(scanner.scan_line("# TODO: This is a long codetag").is_empty());
+/// assert!(scanner.scan_line("#       spanning two lines").is_empty());
+/// let found = scanner.scan_line("x = 1").pop().unwrap();
+/// assert_eq!("TODO: This is a long codetag spanning two lines", found.text);
+/// assert_eq!((1, 2), (found.line, found.end_line));
+/// ```
+pub struct Scanner<'a> {
+    finder: &'a Codetag,
+    lexer: Option<Lexer>,
+    /// When `true`, the lines continuing a codetag are joined to it.
+    pub continuation: bool,
+    line: usize,
+    pending: Option<Pending>,
+}
+
+impl<'a> Scanner<'a> {
+    /// Creates a scanner searching the comments of the given language, or whole lines if
+    /// `None`.
+    pub fn new(finder: &'a Codetag, language: Option<&'static Language>) -> Self {
+        Scanner {
+            finder,
+            lexer: language.map(Lexer::new),
+            continuation: false,
+            line: 0,
+            pending: None,
+        }
+    }
+
+    /// Scans the next line, and returns the codetags completed by it.
+    pub fn scan_line(&mut self, line: &str) -> Vec<Occurrence> {
+        self.line += 1;
+        let comments = match self.lexer {
+            Some(ref mut lexer) => lexer.comments(line),
+            None => std::iter::once(0..line.len()).collect(),
+        };
+        let in_comment = self.lexer.as_ref().is_some_and(Lexer::in_comment);
+
+        let mut occurrences = Vec::new();
+        if let Some(mut pending) = self.pending.take() {
+            match self.continuation_text(&pending.continuation, line, &comments) {
+                Some(text) => {
+                    pending.occurrence.text.push(' ');
+                    pending.occurrence.text.push_str(text);
+                    pending.occurrence.end_line = self.line;
+                    pending.rest.push(' ');
+                    pending.rest.push_str(text);
+                    let closed = pending.tag.clone().describe(&pending.rest);
+                    let block_ended =
+                        matches!(pending.continuation, Continuation::Block) && !in_comment;
+                    if closed || block_ended {
+                        occurrences.push(pending.complete());
+                    } else {
+                        self.pending = Some(pending);
+                    }
+                    return occurrences;
+                }
+                None => occurrences.push(pending.complete()),
+            }
+        }
+
+        for (i, comment) in comments.iter().enumerate() {
+            let mut idx = comment.start;
+            while idx < comment.end {
+                let segment = &line[idx..comment.end];
+                let Some((range, m, tag)) = self.finder.find_parts(segment) else {
+                    break;
+                };
+                let start = idx;
+                idx += range.end;
+                let text = segment[range].trim();
+                if text.is_empty() {
+                    continue;
+                }
+                let pending = Pending {
+                    occurrence: Occurrence {
+                        text: text.to_string(),
+                        tag: Tag::default(),
+                        line: self.line,
+                        end_line: self.line,
+                    },
+                    tag,
+                    rest: segment[m.end..].trim().to_string(),
+                    continuation: Continuation::Block,
+                };
+                // only the last codetag of a line can continue on the next one
+                let last = i + 1 == comments.len() && comment.end == line.len();
+                let continuation = match self.lexer {
+                    _ if !self.continuation || !last => None,
+                    Some(_) if in_comment => Some(Continuation::Block),
+                    Some(_) => {
+                        let lead = &line[..comment.start];
+                        let delimiter = lead.rsplit(char::is_whitespace).next().unwrap_or("");
+                        Some(Continuation::Line {
+                            width: lead.chars().count(),
+                            delimiter: delimiter.to_string(),
+                        })
+                    }
+                    None => {
+                        let decoration = line[..start + m.start].trim_end();
+                        (!decoration.is_empty() && !decoration.contains(char::is_alphanumeric))
+                            .then(|| Continuation::Text {
+                                decoration: decoration.to_string(),
+                            })
+                    }
+                };
+                match continuation {
+                    Some(continuation) => {
+                        self.pending = Some(Pending {
+                            continuation,
+                            ..pending
+                        })
+                    }
+                    None => occurrences.push(pending.complete()),
+                }
+            }
+        }
+        occurrences
+    }
+
+    /// Signals the end of the document, and returns the codetag still waiting for its
+    /// continuation lines, if any.
+    pub fn finish(&mut self) -> Option<Occurrence> {
+        self.pending.take().map(Pending::complete)
+    }
+
+    // Returns the text continuing a codetag on the given line, if any.
+    fn continuation_text<'l>(
+        &self,
+        continuation: &Continuation,
+        line: &'l str,
+        comments: &[Range<usize>],
+    ) -> Option<&'l str> {
+        let text = match continuation {
+            Continuation::Block => {
+                let comment = comments.first().filter(|c| c.start == 0)?;
+                let text = line[comment.clone()].trim_start();
+                text.strip_prefix('*').unwrap_or(text)
+            }
+            Continuation::Line { width, delimiter } => {
+                let comment = comments.first()?;
+                let lead = &line[..comment.start];
+                if lead.chars().count() != *width || lead.trim_start() != delimiter {
+                    return None;
+                }
+                // e.g. the third slash of a /// doc comment
+                line[comment.clone()].trim_start_matches(|c| delimiter.contains(c) || c == '!')
+            }
+            Continuation::Text { decoration } => line.strip_prefix(decoration.as_str())?,
+        }
+        .trim();
+        if text.is_empty() || self.finder.find(text).is_some() {
+            return None;
+        }
+        Some(text)
+    }
 }
 
 /// A finder that extracts codetags (TODO, FIXME, etc.) from text.
@@ -160,7 +463,10 @@ fn default_mnemonics() -> &'static HashSet<String> {
 pub struct Codetag {
     /// When `true`, the mnemonic (e.g., "TODO:") is excluded from the result.
     pub hide_mnemonic: bool,
+    // the mnemonics and categories to search for, all of them when empty
     mnemonics: HashSet<String>,
+    // the mnemonics defined in addition to (or in place of) the default ones
+    definitions: Vec<Mnemonic>,
     mnemonics_regex: Option<Regex>,
 }
 
@@ -177,11 +483,76 @@ impl Finder for Codetag {
                 "implementation error: please call .build_mnemonics_regex() on the codetag instance",
             )
             .find(s)?;
-        let from = if self.hide_mnemonic {
-            m.end()
-        } else {
-            m.start()
+        self.range(s, m.start(), m.end())
+    }
+}
+
+impl Codetag {
+    /// Finds the first codetag in the given string, and parses it.
+    ///
+    /// The returned range is the same as the one returned by [`Finder::find`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use squeeze::codetag::Codetag;
+    ///
+    /// let mut finder = Codetag::default();
+    /// finder.build_mnemonics_regex().unwrap();
+    ///
+    /// let (_, tag) = finder
+    ///     .find_tag("# FIXME(#42): loop forever <MLB 2024-01-15 p:2>")
+    ///     .unwrap();
+    /// assert_eq!("FIXME", tag.mnemonic);
+    /// assert_eq!("loop forever", tag.description);
+    /// assert_eq!(vec!["MLB"], tag.fields.authors);
+    /// assert_eq!(Some("2024-01-15".to_string()), tag.fields.date);
+    /// assert_eq!(Some(2), tag.fields.priority);
+    /// assert_eq!(Some("42".to_string()), tag.fields.ticket);
+    /// ```
+    pub fn find_tag(&self, s: &str) -> Option<(Range<usize>, Tag)> {
+        let (range, m, mut tag) = self.find_parts(s)?;
+        tag.describe(&s[m.end..]);
+        Some((range, tag))
+    }
+
+    // Returns the range of the result, the range of the mnemonic and its fields group, and the
+    // tag without its description.
+    fn find_parts(&self, s: &str) -> Option<(Range<usize>, Range<usize>, Tag)> {
+        let captures = self
+            .mnemonics_regex
+            .as_ref()
+            .expect(
+                "implementation error: please call .build_mnemonics_regex() on the codetag instance",
+            )
+            .captures(s)?;
+        let m = captures.get(0)?;
+        let range = self.range(s, m.start(), m.end())?;
+
+        // the mnemonics not requiring a colon have their own groups
+        let (mnemonic, group) = match captures.name("mnemonic") {
+            Some(mnemonic) => (mnemonic, captures.name("group")),
+            None => (captures.name("bare")?, captures.name("bare_group")),
+        };
+        let mut tag = Tag {
+            mnemonic: mnemonic.as_str().to_string(),
+            ..Default::default()
         };
+        if let Some(group) = group {
+            if !tag.fields.parse_into(group.as_str()) {
+                // not a list of fields, keep the authors only
+                tag.fields = Fields {
+                    authors: vec![group.as_str().trim().to_string()],
+                    ..Default::default()
+                };
+            }
+        }
+
+        Some((range, m.range(), tag))
+    }
+
+    fn range(&self, s: &str, start: usize, end: usize) -> Option<Range<usize>> {
+        let from = if self.hide_mnemonic { end } else { start };
         let to = s.len();
         if from >= to {
             None
@@ -189,19 +560,84 @@ impl Finder for Codetag {
             Some(from..to)
         }
     }
-}
 
-impl Codetag {
-    /// Adds a custom mnemonic to search for.
+    /// Returns the canonical category of the given mnemonic (e.g. `fixme` for `XXX`),
+    /// case-insensitively.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use squeeze::codetag::{Codetag, Mnemonic};
+    ///
+    /// let mut finder = Codetag::default();
+    /// assert_eq!(Some("fixme"), finder.category("xxx"));
+    /// assert_eq!(Some("???"), finder.category("WTF"));
+    /// assert_eq!(None, finder.category("SAFETY"));
+    ///
+    /// finder.define(Mnemonic::new("SAFETY", "note"));
+    /// assert_eq!(Some("note"), finder.category("SAFETY"));
+    /// ```
+    pub fn category(&self, mnemonic: &str) -> Option<&str> {
+        self.definitions
+            .iter()
+            .rev()
+            .find(|m| m.name.eq_ignore_ascii_case(mnemonic))
+            .map(|m| m.category.as_str())
+            .or_else(|| {
+                DEFAULT_CATEGORIES
+                    .iter()
+                    .find(|(_, names)| names.iter().any(|n| n.eq_ignore_ascii_case(mnemonic)))
+                    .map(|(category, _)| *category)
+            })
+    }
+
+    /// Defines a mnemonic in addition to the default PEP 350 ones, or redefines one of them
+    /// (e.g. to move it to another category, or to not require a colon after it).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use squeeze::{codetag::{Codetag, Mnemonic}, Finder};
+    ///
+    /// let mut finder = Codetag::default();
+    /// finder.define(Mnemonic {
+    ///     colon: false,
+    ///     ..Mnemonic::new("SAFETY", "note")
+    /// });
+    /// finder.build_mnemonics_regex().unwrap();
+    ///
+    /// let text = "// SAFETY the pointer is never null";
+    /// assert_eq!(Some("SAFETY the pointer is never null"), finder.find(text).map(|r| &text[r]));
+    /// ```
+    pub fn define(&mut self, mnemonic: Mnemonic) {
+        self.definitions.push(mnemonic);
+    }
+
+    /// Adds a mnemonic, or a whole category of mnemonics (e.g. `fixme` for `FIXME`, `XXX`,
+    /// `BROKEN`, etc), to search for.
     ///
     /// When at least one mnemonic is added, only those mnemonics will be matched.
-    /// If no mnemonics are added, all default PEP 350 mnemonics are used.
+    /// If no mnemonics are added, all the default PEP 350 mnemonics and the defined ones are
+    /// used.
     ///
     /// Mnemonic matching is case-insensitive.
     pub fn add_mnemonic(&mut self, mnemonic: &str) {
         self.mnemonics.insert(mnemonic.to_uppercase());
     }
 
+    // Returns the default mnemonics that have not been redefined, followed by the defined ones.
+    fn known_mnemonics(&self) -> Vec<Mnemonic> {
+        let mut known: Vec<Mnemonic> = DEFAULT_CATEGORIES
+            .iter()
+            .flat_map(|(category, names)| names.iter().map(|name| Mnemonic::new(name, category)))
+            .collect();
+        for definition in &self.definitions {
+            known.retain(|m| !m.name.eq_ignore_ascii_case(&definition.name));
+            known.push(definition.clone());
+        }
+        known
+    }
+
     /// Builds the internal regex for matching mnemonics.
     ///
     /// **This must be called before using the finder.** Calling [`Finder::find`]
@@ -212,50 +648,73 @@ impl Codetag {
     /// Returns an error if the regex compilation fails (should not happen with
     /// valid mnemonics).
     pub fn build_mnemonics_regex(&mut self) -> Result<(), regex::Error> {
-        let mnemonics = if self.mnemonics.is_empty() {
-            default_mnemonics().iter()
+        let known = self.known_mnemonics();
+        let mnemonics: Vec<Mnemonic> = if self.mnemonics.is_empty() {
+            known
         } else {
-            self.mnemonics.iter()
+            // a category stands for all of its mnemonics, anything else for itself
+            let mut selected: Vec<Mnemonic> = known
+                .iter()
+                .filter(|m| {
+                    self.mnemonics.contains(&m.category.to_uppercase())
+                        || self.mnemonics.contains(&m.name)
+                })
+                .cloned()
+                .collect();
+            for name in &self.mnemonics {
+                if !selected
+                    .iter()
+                    .any(|m| &m.name == name || m.category.eq_ignore_ascii_case(name))
+                {
+                    selected.push(Mnemonic::new(name, name));
+                }
+            }
+            selected
         };
-        let mut r = String::with_capacity(mnemonics.len() * 16);
+
         // Use \b word boundary for alphanumeric mnemonics to prevent MYTODO matching TODO
         // Special mnemonics like ??? and !!! are handled separately
-        r.push_str("(?i)(?:");
-
-        let mut alpha_mnemonics = Vec::new();
-        let mut special_mnemonics = Vec::new();
-
-        for m in mnemonics {
-            if m.chars().all(|c| c.is_alphanumeric()) {
-                alpha_mnemonics.push(m.clone());
-            } else {
-                special_mnemonics.push(m.clone());
+        fn alternation(mnemonics: &[&Mnemonic]) -> String {
+            let (alpha, special): (Vec<&str>, Vec<&str>) = mnemonics
+                .iter()
+                .map(|m| m.name.as_str())
+                .partition(|m| m.chars().all(|c| c.is_alphanumeric()));
+            let mut r = String::with_capacity(mnemonics.len() * 16);
+            if !alpha.is_empty() {
+                r.push_str("\\b(?:");
+                for (i, m) in alpha.iter().enumerate() {
+                    if i > 0 {
+                        r.push('|');
+                    }
+                    regex_syntax::escape_into(m, &mut r);
+                }
+                r.push_str(")\\b");
             }
-        }
-
-        let mut first = true;
-        if !alpha_mnemonics.is_empty() {
-            r.push_str("\\b(?:");
-            for (i, m) in alpha_mnemonics.iter().enumerate() {
-                if i > 0 {
+            for m in special {
+                if !r.is_empty() {
                     r.push('|');
                 }
                 regex_syntax::escape_into(m, &mut r);
             }
-            r.push_str(")\\b");
-            first = false;
+            r
         }
 
-        for m in special_mnemonics.iter() {
-            if !first {
-                r.push('|');
-            }
-            regex_syntax::escape_into(m, &mut r);
-            first = false;
+        let (colon, bare): (Vec<&Mnemonic>, Vec<&Mnemonic>) =
+            mnemonics.iter().partition(|m| m.colon);
+        let mut branches = Vec::new();
+        if !colon.is_empty() {
+            branches.push(format!(
+                "(?P<mnemonic>{})(?:\\((?P<group>[^)]*)\\))?:",
+                alternation(&colon)
+            ));
         }
-
-        r.push_str(")(?:\\([^)]*\\))?:");
-        self.mnemonics_regex = Some(Regex::new(&r)?);
+        if !bare.is_empty() {
+            branches.push(format!(
+                "(?P<bare>{})(?:\\((?P<bare_group>[^)]*)\\))?:?",
+                alternation(&bare)
+            ));
+        }
+        self.mnemonics_regex = Some(Regex::new(&format!("(?i){}", branches.join("|")))?);
         Ok(())
     }
 }
@@ -640,4 +1099,307 @@ mod tests {
         // Should capture the entire rest of the line including colons
         assert_eq!(Some("TODO: time is 12:30:45"), result.map(|r| &input[r]));
     }
+
+    #[test]
+    fn find_tag_should_parse_trailing_fields() {
+        let mut finder = Codetag::default();
+        finder.build_mnemonics_regex().unwrap();
+        let input = "# TODO: support IPv6 <MDE, CLE 2005-09-04 d:2005-10 p:1 t:bugs s:open c:net>";
+        let (range, tag) = finder.find_tag(input).unwrap();
+        assert_eq!(&input[2..], &input[range]);
+        assert_eq!("TODO", tag.mnemonic);
+        assert_eq!("support IPv6", tag.description);
+        assert_eq!(
+            Fields {
+                authors: vec!["MDE".to_string(), "CLE".to_string()],
+                date: Some("2005-09-04".to_string()),
+                due: Some("2005-10".to_string()),
+                priority: Some(1),
+                ticket: None,
+                tracker: Some("bugs".to_string()),
+                status: Some("open".to_string()),
+                category: Some("net".to_string()),
+            },
+            tag.fields
+        );
+    }
+
+    #[test]
+    fn find_tag_should_parse_the_parenthesized_group() {
+        let mut finder = Codetag::default();
+        finder.build_mnemonics_regex().unwrap();
+        for (input, authors, ticket) in vec![
+            ("TODO(john): refactor", vec!["john"], None),
+            ("TODO(#42): refactor", vec![], Some("42")),
+            (
+                "TODO(john, i:JIRA-7): refactor",
+                vec!["john"],
+                Some("JIRA-7"),
+            ),
+            ("TODO(p:x): refactor", vec!["p:x"], None),
+        ] {
+            let (_, tag) = finder.find_tag(input).unwrap();
+            assert_eq!("refactor", tag.description, "{}", input);
+            assert_eq!(authors, tag.fields.authors, "{}", input);
+            assert_eq!(ticket.map(String::from), tag.fields.ticket, "{}", input);
+        }
+    }
+
+    #[test]
+    fn find_tag_should_handle_the_closing_delimiter() {
+        let mut finder = Codetag::default();
+        finder.build_mnemonics_regex().unwrap();
+        let (_, tag) = finder.find_tag("FIXME: leaks memory <>").unwrap();
+        assert_eq!("leaks memory", tag.description);
+        assert_eq!(Fields::default(), tag.fields);
+    }
+
+    #[test]
+    fn find_tag_should_keep_invalid_fields_in_the_description() {
+        let mut finder = Codetag::default();
+        finder.build_mnemonics_regex().unwrap();
+        for input in vec![
+            "TODO: return a Vec<String>",
+            "TODO: compare a <b> c",
+            "TODO: use <x:y>",
+            "TODO: bump <p:high>",
+        ] {
+            let (_, tag) = finder.find_tag(input).unwrap();
+            assert_eq!(&input[6..], tag.description, "{}", input);
+        }
+    }
+
+    #[test]
+    fn find_tag_should_return_the_same_range_as_find() {
+        let mut finder = Codetag::default();
+        finder.hide_mnemonic = true;
+        finder.build_mnemonics_regex().unwrap();
+        let input = "// XXX(MLB): fix <p:3>";
+        assert_eq!(finder.find(input), finder.find_tag(input).map(|(r, _)| r));
+    }
+
+    fn scan(language: Option<&str>, continuation: bool, lines: &[&str]) -> Vec<Occurrence> {
+        let mut finder = Codetag::default();
+        finder.build_mnemonics_regex().unwrap();
+        let mut scanner = Scanner::new(&finder, language.and_then(Language::from_name));
+        scanner.continuation = continuation;
+        let mut occurrences: Vec<_> = lines.iter().flat_map(|l| scanner.scan_line(l)).collect();
+        occurrences.extend(scanner.finish());
+        occurrences
+    }
+
+    fn texts(occurrences: &[Occurrence]) -> Vec<(&str, usize, usize)> {
+        occurrences
+            .iter()
+            .map(|o| (o.text.as_str(), o.line, o.end_line))
+            .collect()
+    }
+
+    #[test]
+    fn scanner_should_only_search_comments() {
+        let occurrences = scan(
+            Some("c"),
+            false,
+            &["puts(\"TODO: no\"); // TODO: a */", "/* FIXME: b */ x = 1;"],
+        );
+        assert_eq!(
+            vec![("TODO: a */", 1, 1), ("FIXME: b", 2, 2)],
+            texts(&occurrences)
+        );
+    }
+
+    #[test]
+    fn scanner_should_search_whole_lines_without_language() {
+        let occurrences = scan(None, false, &["puts(\"TODO: yes\");"]);
+        assert_eq!(vec![("TODO: yes\");", 1, 1)], texts(&occurrences));
+    }
+
+    #[test]
+    fn scanner_should_join_line_comments() {
+        let occurrences = scan(
+            Some("rust"),
+            true,
+            &[
+                "    // TODO: a",
+                "    // b",
+                "    //   c",
+                "    // FIXME: d",
+                "  // e",
+                "    /// f",
+                "    //",
+                "    // g",
+                "x(); // XXX: h",
+                "     // i",
+            ],
+        );
+        assert_eq!(
+            vec![
+                ("TODO: a b c", 1, 3),
+                ("FIXME: d", 4, 4),
+                ("XXX: h i", 9, 10)
+            ],
+            texts(&occurrences)
+        );
+    }
+
+    #[test]
+    fn scanner_should_join_block_comments() {
+        let occurrences = scan(
+            Some("java"),
+            true,
+            &[
+                "/**",
+                " * TODO: a",
+                " * b",
+                " */",
+                "/* FIXME: c",
+                "   d */ e();",
+                "// f",
+            ],
+        );
+        assert_eq!(
+            vec![("TODO: a b", 2, 3), ("FIXME: c d", 5, 6)],
+            texts(&occurrences)
+        );
+    }
+
+    #[test]
+    fn scanner_should_join_docstrings() {
+        let occurrences = scan(
+            Some("python"),
+            true,
+            &[
+                "def f():",
+                "    \"\"\"TODO: a",
+                "    b",
+                "    \"\"\"",
+                "    pass",
+            ],
+        );
+        assert_eq!(vec![("TODO: a b", 2, 3)], texts(&occurrences));
+    }
+
+    #[test]
+    fn scanner_should_stop_at_trailing_fields() {
+        let occurrences = scan(
+            Some("python"),
+            true,
+            &["# TODO: a", "#   b <MLB p:1>", "# c"],
+        );
+        assert_eq!(vec![("TODO: a b <MLB p:1>", 1, 2)], texts(&occurrences));
+        assert_eq!("a b", occurrences[0].tag.description);
+        assert_eq!(Some(1), occurrences[0].tag.fields.priority);
+    }
+
+    #[test]
+    fn scanner_should_join_decorated_lines_without_language() {
+        let occurrences = scan(None, true, &["-- TODO: a", "-- b", "TODO: c", "d"]);
+        assert_eq!(
+            vec![("TODO: a b", 1, 2), ("TODO: c", 3, 3)],
+            texts(&occurrences)
+        );
+    }
+
+    #[test]
+    fn scanner_should_not_join_without_continuation() {
+        let occurrences = scan(Some("python"), false, &["# TODO: a", "# b"]);
+        assert_eq!(vec![("TODO: a", 1, 1)], texts(&occurrences));
+    }
+
+    #[test]
+    fn category_should_cover_all_default_mnemonics() {
+        let finder = Codetag::default();
+        for mnemonic in finder.known_mnemonics() {
+            assert_eq!(
+                Some(mnemonic.category.as_str()),
+                finder.category(&mnemonic.name),
+                "{}",
+                mnemonic.name
+            );
+        }
+        for (input, expected) in vec![
+            ("todo", Some("todo")),
+            ("Refactor", Some("fixme")),
+            ("!!!", Some("!!!")),
+            ("THANKS", Some("cred")),
+            ("TODOS", None),
+        ] {
+            assert_eq!(expected, finder.category(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn it_should_search_whole_categories() {
+        let mut finder = Codetag::default();
+        finder.add_mnemonic("fixme");
+        finder.add_mnemonic("test");
+        finder.build_mnemonics_regex().unwrap();
+        for (input, expected) in vec![
+            ("FIXME: a", Some("FIXME: a")),
+            ("XXX: a", Some("XXX: a")),
+            ("broken: a", Some("broken: a")),
+            ("test: a", Some("test: a")),
+            ("TODO: a", None),
+        ] {
+            assert_eq!(expected, finder.find(input).map(|r| &input[r]), "{}", input);
+        }
+    }
+
+    #[test]
+    fn it_should_extend_the_default_mnemonics() {
+        let mut finder = Codetag::default();
+        finder.define(Mnemonic::new("perf", "perf"));
+        finder.define(Mnemonic::new("AFAIRE", "todo"));
+        finder.define(Mnemonic::new("XXX", "hack"));
+        finder.build_mnemonics_regex().unwrap();
+        for (input, expected) in vec![
+            ("PERF: a", Some("perf")),
+            ("afaire: a", Some("todo")),
+            ("XXX: a", Some("hack")),
+            ("FIXME: a", Some("fixme")),
+        ] {
+            let (_, tag) = finder.find_tag(input).unwrap();
+            assert_eq!(expected, finder.category(&tag.mnemonic), "{}", input);
+        }
+
+        finder.add_mnemonic("todo");
+        finder.build_mnemonics_regex().unwrap();
+        for (input, expected) in vec![
+            ("TODO: a", Some("TODO: a")),
+            ("AFAIRE: a", Some("AFAIRE: a")),
+            ("PERF: a", None),
+        ] {
+            assert_eq!(expected, finder.find(input).map(|r| &input[r]), "{}", input);
+        }
+    }
+
+    #[test]
+    fn it_should_find_mnemonics_not_requiring_a_colon() {
+        let mut finder = Codetag::default();
+        finder.define(Mnemonic {
+            colon: false,
+            ..Mnemonic::new("SAFETY", "note")
+        });
+        finder.build_mnemonics_regex().unwrap();
+        for (input, expected) in vec![
+            (
+                "// SAFETY the pointer is valid",
+                Some("SAFETY the pointer is valid"),
+            ),
+            (
+                "// SAFETY: the pointer is valid",
+                Some("SAFETY: the pointer is valid"),
+            ),
+            ("// SAFETY(MLB): valid", Some("SAFETY(MLB): valid")),
+            ("// UNSAFETY is bad", None),
+            ("// TODO is not a codetag", None),
+        ] {
+            assert_eq!(expected, finder.find(input).map(|r| &input[r]), "{}", input);
+        }
+
+        let (_, tag) = finder.find_tag("SAFETY(MLB) valid").unwrap();
+        assert_eq!("SAFETY", tag.mnemonic);
+        assert_eq!(vec!["MLB"], tag.fields.authors);
+        assert_eq!("valid", tag.description);
+    }
 }
@@ -1,8 +1,1118 @@
 use super::Finder;
+use std::fmt;
 use std::ops::Range;
 
+// The named colors of CSS, with their sRGB value
+const NAMES: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+// The functions of CSS Color 4, apart from the legacy aliases rgba and hsla
+const FUNCTIONS: &[&str] = &[
+    "rgb", "rgba", "hsl", "hsla", "hwb", "lab", "lch", "oklab", "oklch", "color",
+];
+
+type Matrix = [[f64; 3]; 3];
+
+// The matrices from the linear RGB components to XYZ, of CSS Color 4
+const SRGB_TO_XYZ: Matrix = [
+    [506752.0 / 1228815.0, 87881.0 / 245763.0, 12673.0 / 70218.0],
+    [87098.0 / 409605.0, 175762.0 / 245763.0, 12673.0 / 175545.0],
+    [7918.0 / 409605.0, 87881.0 / 737289.0, 1001167.0 / 1053270.0],
+];
+const DISPLAY_P3_TO_XYZ: Matrix = [
+    [
+        608311.0 / 1250200.0,
+        189793.0 / 714400.0,
+        198249.0 / 1000160.0,
+    ],
+    [
+        35783.0 / 156275.0,
+        247089.0 / 357200.0,
+        198249.0 / 2500400.0,
+    ],
+    [0.0, 32229.0 / 714400.0, 5220557.0 / 5000800.0],
+];
+const A98_RGB_TO_XYZ: Matrix = [
+    [
+        573536.0 / 994567.0,
+        263643.0 / 1420810.0,
+        187206.0 / 994567.0,
+    ],
+    [
+        591459.0 / 1989134.0,
+        6239551.0 / 9945670.0,
+        374412.0 / 4972835.0,
+    ],
+    [
+        53769.0 / 1989134.0,
+        351524.0 / 4972835.0,
+        4929758.0 / 4972835.0,
+    ],
+];
+// relative to D50
+const PROPHOTO_RGB_TO_XYZ: Matrix = [
+    [0.7977666449006423, 0.13518129740053308, 0.0313477341283922],
+    [0.2880748288194013, 0.711835234241873, 0.00008993693872564],
+    [0.0, 0.0, 0.8251046025104602],
+];
+const REC2020_TO_XYZ: Matrix = [
+    [
+        63426534.0 / 99577255.0,
+        20160776.0 / 139408157.0,
+        47086771.0 / 278816314.0,
+    ],
+    [
+        26158966.0 / 99577255.0,
+        472592308.0 / 697040785.0,
+        8267143.0 / 139408157.0,
+    ],
+    [0.0, 19567812.0 / 697040785.0, 295819943.0 / 278816314.0],
+];
+const REC2020_ALPHA: f64 = 1.09929682680944;
+const REC2020_BETA: f64 = 0.018053968510807;
+
+// The Bradford chromatic adaptation from the D50 white point to D65
+const D50_TO_D65: Matrix = [
+    [0.955473421488075, -0.02309845494876471, 0.06325924320057072],
+    [
+        -0.0283697093338637,
+        1.0099953980813041,
+        0.021041441191917323,
+    ],
+    [
+        0.012314014864481998,
+        -0.020507649298898964,
+        1.330365926242124,
+    ],
+];
+// The constants of CIE Lab
+const LAB_KAPPA: f64 = 24389.0 / 27.0;
+const LAB_EPSILON: f64 = 216.0 / 24389.0;
+const D50_WHITE: [f64; 3] = [0.3457 / 0.3585, 1.0, (1.0 - 0.3457 - 0.3585) / 0.3585];
+
+// The matrices of OKLab, from the linear sRGB components to LMS, and from LMS to OKLab
+const SRGB_TO_LMS: Matrix = [
+    [0.4122214708, 0.5363325363, 0.0514459929],
+    [0.2119034982, 0.6806995451, 0.1073969566],
+    [0.0883024619, 0.2817188376, 0.6299787005],
+];
+const LMS_TO_OKLAB: Matrix = [
+    [0.2104542553, 0.7936177850, -0.0040720468],
+    [1.9779984951, -2.4285922050, 0.4505937099],
+    [0.0259040371, 0.7827717662, -0.8086757660],
+];
+
+fn multiply(m: &Matrix, v: [f64; 3]) -> [f64; 3] {
+    m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+fn invert(m: &Matrix) -> Matrix {
+    let cofactor = |r: usize, c: usize| {
+        let (r1, r2, c1, c2) = ((r + 1) % 3, (r + 2) % 3, (c + 1) % 3, (c + 2) % 3);
+        m[r1][c1] * m[r2][c2] - m[r1][c2] * m[r2][c1]
+    };
+    let det = (0..3).map(|c| m[0][c] * cofactor(0, c)).sum::<f64>();
+    // the inverse is the transposed cofactor matrix, divided by the determinant
+    [0, 1, 2].map(|r| [0, 1, 2].map(|c| cofactor(c, r) / det))
+}
+
+// The polar form (lightness, chroma, hue in degrees) of Lab or OKLab.
+fn to_polar([l, a, b]: [f64; 3]) -> [f64; 3] {
+    let chroma = a.hypot(b);
+    // the hue of the grays is powerless
+    let hue = if chroma < 1e-6 {
+        0.0
+    } else {
+        b.atan2(a).to_degrees().rem_euclid(360.0)
+    };
+    [l, chroma, hue]
+}
+
+fn from_polar([l, chroma, hue]: [f64; 3]) -> [f64; 3] {
+    let hue = hue.to_radians();
+    [l, chroma * hue.cos(), chroma * hue.sin()]
+}
+
+/// Finds the colors of CSS Color 4: hexadecimal (e.g. `#ff00aa`), functional (e.g.
+/// `rgb(255 0 170 / 50%)`, `oklch(70% 0.1 240)` or `color(display-p3 1 0 0)`) and, optionally,
+/// named. The arguments of the functions are validated, see [`CssColor::parse`].
 #[derive(Default)]
-pub struct Color {}
+pub struct Color {
+    /// Whether to also search for the named colors (e.g. `rebeccapurple`), `transparent` and
+    /// `currentColor`.
+    pub names: bool,
+}
+
+/// The color space of a [`CssColor`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Space {
+    /// The red, green and blue components from 0 to 1, of the hexadecimal, named, `rgb()` and
+    /// `color(srgb ...)` colors.
+    Srgb,
+    /// The hue in degrees, the saturation and lightness from 0 to 1.
+    Hsl,
+    /// The hue in degrees, the whiteness and blackness from 0 to 1.
+    Hwb,
+    /// The lightness from 0 to 100, and the a and b axes.
+    Lab,
+    /// The lightness from 0 to 100, the chroma, and the hue in degrees.
+    Lch,
+    /// The lightness from 0 to 1, and the a and b axes.
+    Oklab,
+    /// The lightness from 0 to 1, the chroma, and the hue in degrees.
+    Oklch,
+    SrgbLinear,
+    DisplayP3,
+    A98Rgb,
+    ProphotoRgb,
+    Rec2020,
+    XyzD50,
+    XyzD65,
+}
+
+impl Space {
+    // The predefined color spaces of the color() function
+    const NAMES: &[(&str, Space)] = &[
+        ("srgb", Space::Srgb),
+        ("srgb-linear", Space::SrgbLinear),
+        ("display-p3", Space::DisplayP3),
+        ("a98-rgb", Space::A98Rgb),
+        ("prophoto-rgb", Space::ProphotoRgb),
+        ("rec2020", Space::Rec2020),
+        ("xyz-d50", Space::XyzD50),
+        ("xyz-d65", Space::XyzD65),
+        ("xyz", Space::XyzD65),
+    ];
+
+    /// Returns the predefined color space of the `color()` function of the given name (e.g.
+    /// `display-p3`), case-insensitively.
+    pub fn from_name(name: &str) -> Option<Space> {
+        Self::NAMES
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, space)| *space)
+    }
+
+    // The matrix from the linear components of the RGB spaces to XYZ, and whether it is relative
+    // to D50.
+    fn rgb(&self) -> Option<(&'static Matrix, bool)> {
+        match self {
+            Space::Srgb | Space::SrgbLinear => Some((&SRGB_TO_XYZ, false)),
+            Space::DisplayP3 => Some((&DISPLAY_P3_TO_XYZ, false)),
+            Space::A98Rgb => Some((&A98_RGB_TO_XYZ, false)),
+            Space::ProphotoRgb => Some((&PROPHOTO_RGB_TO_XYZ, true)),
+            Space::Rec2020 => Some((&REC2020_TO_XYZ, false)),
+            _ => None,
+        }
+    }
+
+    // Linearizes a component of an RGB space, the negative values being mirrored.
+    fn decode(&self, v: f64) -> f64 {
+        let (sign, v) = (v.signum(), v.abs());
+        sign * match self {
+            Space::Srgb | Space::DisplayP3 if v <= 0.04045 => v / 12.92,
+            Space::Srgb | Space::DisplayP3 => ((v + 0.055) / 1.055).powf(2.4),
+            Space::A98Rgb => v.powf(563.0 / 256.0),
+            Space::ProphotoRgb if v <= 16.0 / 512.0 => v / 16.0,
+            Space::ProphotoRgb => v.powf(1.8),
+            Space::Rec2020 if v < REC2020_BETA * 4.5 => v / 4.5,
+            Space::Rec2020 => ((v + REC2020_ALPHA - 1.0) / REC2020_ALPHA).powf(1.0 / 0.45),
+            _ => v,
+        }
+    }
+
+    // The inverse of decode.
+    fn encode(&self, v: f64) -> f64 {
+        let (sign, v) = (v.signum(), v.abs());
+        sign * match self {
+            Space::Srgb | Space::DisplayP3 if v <= 0.0031308 => v * 12.92,
+            Space::Srgb | Space::DisplayP3 => 1.055 * v.powf(1.0 / 2.4) - 0.055,
+            Space::A98Rgb => v.powf(256.0 / 563.0),
+            Space::ProphotoRgb if v < 1.0 / 512.0 => v * 16.0,
+            Space::ProphotoRgb => v.powf(1.0 / 1.8),
+            Space::Rec2020 if v <= REC2020_BETA => v * 4.5,
+            Space::Rec2020 => REC2020_ALPHA * v.powf(0.45) - (REC2020_ALPHA - 1.0),
+            _ => v,
+        }
+    }
+
+    /// Returns the name of the color space (e.g. `display-p3`), or of its function (e.g.
+    /// `oklch`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Space::Hsl => "hsl",
+            Space::Hwb => "hwb",
+            Space::Lab => "lab",
+            Space::Lch => "lch",
+            Space::Oklab => "oklab",
+            Space::Oklch => "oklch",
+            _ => Self::NAMES
+                .iter()
+                .find(|(_, space)| space == self)
+                .map_or("", |(name, _)| name),
+        }
+    }
+}
+
+/// A color of CSS Color 4, as found by the [`Color`] finder.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CssColor {
+    pub space: Space,
+    /// The components in the color space, `none` being 0.
+    pub components: [f64; 3],
+    /// From 0 (transparent) to 1 (opaque).
+    pub alpha: f64,
+}
+
+// A token of the arguments of a color function.
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Percentage(f64),
+    // in degrees
+    Angle(f64),
+    None,
+    Ident(String),
+    Comma,
+    Slash,
+}
+
+impl Token {
+    // Returns the value of a number, or of a percentage of the given reference.
+    fn value(&self, percent: f64) -> Option<f64> {
+        match self {
+            Token::Number(n) => Some(*n),
+            Token::Percentage(p) => Some(p / 100.0 * percent),
+            Token::None => Some(0.0),
+            _ => None,
+        }
+    }
+
+    // Returns the value within the given range, of a number or a percentage of its end.
+    fn bounded(&self, range: std::ops::RangeInclusive<f64>) -> Option<f64> {
+        let value = self.value(*range.end())?;
+        range.contains(&value).then_some(value)
+    }
+
+    // Returns the hue in degrees, from 0 to 360.
+    fn hue(&self) -> Option<f64> {
+        match self {
+            Token::Number(degrees) | Token::Angle(degrees) => Some(degrees.rem_euclid(360.0)),
+            Token::None => Some(0.0),
+            _ => None,
+        }
+    }
+}
+
+impl CssColor {
+    /// Parses a color of CSS Color 4, case-insensitively: hexadecimal, named, `transparent`, or
+    /// functional in the legacy (comma-separated) or modern (space-separated, with an optional
+    /// `/ alpha`) syntax. The arguments are validated: their number, their types (e.g. the
+    /// percentages of `hsl()`), and their ranges (e.g. from 0 to 255 for `rgb()`, or a positive
+    /// chroma), apart from the ones of `color()` which may be out of gamut. `currentColor` has
+    /// no value of its own.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use squeeze::color::{CssColor, Space};
+    ///
+    /// let color = CssColor::parse("oklch(70% 0.1 240 / 50%)").unwrap();
+    /// assert_eq!(Space::Oklch, color.space);
+    /// assert_eq!([0.7, 0.1, 240.0], color.components);
+    /// assert_eq!(0.5, color.alpha);
+    /// assert!(CssColor::parse("rgb(300 0 0)").is_none());
+    /// ```
+    pub fn parse(s: &str) -> Option<CssColor> {
+        if let Some(hex) = s.strip_prefix('#') {
+            return Self::parse_hex(hex);
+        }
+        if s.eq_ignore_ascii_case("transparent") {
+            return Some(CssColor {
+                space: Space::Srgb,
+                components: [0.0; 3],
+                alpha: 0.0,
+            });
+        }
+        if let Some((_, rgb)) = NAMES.iter().find(|(name, _)| name.eq_ignore_ascii_case(s)) {
+            return Some(Self::from_rgb(*rgb, 1.0));
+        }
+
+        let (function, args) = s.strip_suffix(')')?.split_once('(')?;
+        let function = function.to_ascii_lowercase();
+        let mut tokens = Self::tokens(args)?;
+        let space = if function == "color" {
+            match tokens.first() {
+                Some(Token::Ident(name)) => {
+                    let space = Space::from_name(name)?;
+                    tokens.remove(0);
+                    Some(space)
+                }
+                _ => return None,
+            }
+        } else {
+            None
+        };
+        let legacy = tokens.contains(&Token::Comma);
+        let (components, alpha) = if legacy {
+            if !matches!(function.as_str(), "rgb" | "rgba" | "hsl" | "hsla") {
+                return None;
+            }
+            Self::legacy_arguments(&tokens)?
+        } else {
+            Self::arguments(&tokens)?
+        };
+        let alpha = match alpha {
+            Some(alpha) => alpha.bounded(0.0..=1.0)?,
+            None => 1.0,
+        };
+        let [c1, c2, c3] = components;
+
+        let (space, components) = match function.as_str() {
+            "rgb" | "rgba" => {
+                let percentages = components
+                    .iter()
+                    .filter(|t| matches!(t, Token::Percentage(_)))
+                    .count();
+                if legacy && percentages % 3 != 0 {
+                    return None;
+                }
+                let channel = |t: &Token| Some(t.bounded(0.0..=255.0)? / 255.0);
+                (Space::Srgb, [channel(c1)?, channel(c2)?, channel(c3)?])
+            }
+            "hsl" | "hsla" | "hwb" => {
+                if legacy && [c2, c3].iter().any(|t| !matches!(t, Token::Percentage(_))) {
+                    return None;
+                }
+                let space = if function == "hwb" {
+                    Space::Hwb
+                } else {
+                    Space::Hsl
+                };
+                let fraction = |t: &Token| Some(t.bounded(0.0..=100.0)? / 100.0);
+                (space, [c1.hue()?, fraction(c2)?, fraction(c3)?])
+            }
+            "lab" => (
+                Space::Lab,
+                [c1.bounded(0.0..=100.0)?, c2.value(125.0)?, c3.value(125.0)?],
+            ),
+            "lch" => (
+                Space::Lch,
+                [
+                    c1.bounded(0.0..=100.0)?,
+                    c2.value(150.0).filter(|c| *c >= 0.0)?,
+                    c3.hue()?,
+                ],
+            ),
+            "oklab" => (
+                Space::Oklab,
+                [c1.bounded(0.0..=1.0)?, c2.value(0.4)?, c3.value(0.4)?],
+            ),
+            "oklch" => (
+                Space::Oklch,
+                [
+                    c1.bounded(0.0..=1.0)?,
+                    c2.value(0.4).filter(|c| *c >= 0.0)?,
+                    c3.hue()?,
+                ],
+            ),
+            "color" => (space?, [c1.value(1.0)?, c2.value(1.0)?, c3.value(1.0)?]),
+            _ => return None,
+        };
+        Some(CssColor {
+            space,
+            components,
+            alpha,
+        })
+    }
+
+    /// Returns the color in the given color space, which may be out of its gamut (e.g. a
+    /// `display-p3` color in sRGB), see [`CssColor::to_gamut`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use squeeze::color::{CssColor, Space};
+    ///
+    /// let color = CssColor::parse("#ff0000").unwrap().convert(Space::Hsl);
+    /// assert_eq!("hsl(0 100% 50%)", color.to_string());
+    /// ```
+    pub fn convert(&self, space: Space) -> CssColor {
+        if space == self.space {
+            return *self;
+        }
+        let components = match space {
+            Space::Hsl | Space::Hwb => {
+                let [r, g, b] = self.convert(Space::Srgb).components;
+                let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+                let delta = max - min;
+                // the hue of the grays is powerless
+                let hue = if delta < 1e-9 {
+                    0.0
+                } else if max == r {
+                    (g - b) / delta
+                } else if max == g {
+                    (b - r) / delta + 2.0
+                } else {
+                    (r - g) / delta + 4.0
+                };
+                let hue = (hue * 60.0).rem_euclid(360.0);
+                if space == Space::Hwb {
+                    [hue, min, 1.0 - max]
+                } else {
+                    let lightness = (max + min) / 2.0;
+                    let saturation = if delta < 1e-9 {
+                        0.0
+                    } else {
+                        delta / (1.0 - (2.0 * lightness - 1.0).abs())
+                    };
+                    [hue, saturation, lightness]
+                }
+            }
+            // without XYZ, for their values to be exact
+            Space::Srgb if matches!(self.space, Space::Hsl | Space::Hwb) => self.hue_to_srgb(),
+            Space::Lch => to_polar(self.convert(Space::Lab).components),
+            Space::Oklch => to_polar(self.convert(Space::Oklab).components),
+            _ => Self::from_xyz(space, self.to_xyz()),
+        };
+        CssColor {
+            space,
+            components,
+            alpha: self.alpha,
+        }
+    }
+
+    // Returns the sRGB components of an HSL or HWB color.
+    fn hue_to_srgb(&self) -> [f64; 3] {
+        match self.components {
+            [hue, white, black] if self.space == Space::Hwb => {
+                if white + black >= 1.0 {
+                    return [white / (white + black); 3];
+                }
+                let pure = CssColor {
+                    space: Space::Hsl,
+                    components: [hue, 1.0, 0.5],
+                    alpha: 1.0,
+                };
+                pure.hue_to_srgb()
+                    .map(|v| v * (1.0 - white - black) + white)
+            }
+            [hue, saturation, lightness] => {
+                let channel = |n: f64| {
+                    let k = (n + hue / 30.0) % 12.0;
+                    let a = saturation * lightness.min(1.0 - lightness);
+                    lightness - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+                };
+                [channel(0.0), channel(8.0), channel(4.0)]
+            }
+        }
+    }
+
+    // Returns the XYZ components, relative to D65.
+    fn to_xyz(self) -> [f64; 3] {
+        let c = self.components;
+        match self.space {
+            Space::Hsl | Space::Hwb => Self::srgb(self.hue_to_srgb()).to_xyz(),
+            Space::Lab => {
+                let [l, a, b] = c;
+                let f1 = (l + 16.0) / 116.0;
+                let (f0, f2) = (a / 500.0 + f1, f1 - b / 200.0);
+                let inverse = |f: f64| {
+                    if f.powi(3) > LAB_EPSILON {
+                        f.powi(3)
+                    } else {
+                        (116.0 * f - 16.0) / LAB_KAPPA
+                    }
+                };
+                let y = if l > LAB_KAPPA * LAB_EPSILON {
+                    f1.powi(3)
+                } else {
+                    l / LAB_KAPPA
+                };
+                let xyz = [inverse(f0), y, inverse(f2)];
+                multiply(&D50_TO_D65, [0, 1, 2].map(|i| xyz[i] * D50_WHITE[i]))
+            }
+            Space::Lch => CssColor {
+                space: Space::Lab,
+                components: from_polar(c),
+                alpha: self.alpha,
+            }
+            .to_xyz(),
+            Space::Oklab => {
+                let lms = multiply(&invert(&LMS_TO_OKLAB), c).map(|v| v.powi(3));
+                multiply(&SRGB_TO_XYZ, multiply(&invert(&SRGB_TO_LMS), lms))
+            }
+            Space::Oklch => CssColor {
+                space: Space::Oklab,
+                components: from_polar(c),
+                alpha: self.alpha,
+            }
+            .to_xyz(),
+            Space::XyzD50 => multiply(&D50_TO_D65, c),
+            Space::XyzD65 => c,
+            space => {
+                let (matrix, d50) = space.rgb().unwrap_or((&SRGB_TO_XYZ, false));
+                let xyz = multiply(matrix, c.map(|v| space.decode(v)));
+                if d50 {
+                    multiply(&D50_TO_D65, xyz)
+                } else {
+                    xyz
+                }
+            }
+        }
+    }
+
+    // Returns the components in the given space, apart from the polar ones, of the XYZ ones
+    // relative to D65.
+    fn from_xyz(space: Space, xyz: [f64; 3]) -> [f64; 3] {
+        match space {
+            Space::Lab => {
+                let xyz = multiply(&invert(&D50_TO_D65), xyz);
+                let [f0, f1, f2] = [0, 1, 2].map(|i| {
+                    let v = xyz[i] / D50_WHITE[i];
+                    if v > LAB_EPSILON {
+                        v.cbrt()
+                    } else {
+                        (LAB_KAPPA * v + 16.0) / 116.0
+                    }
+                });
+                [116.0 * f1 - 16.0, 500.0 * (f0 - f1), 200.0 * (f1 - f2)]
+            }
+            Space::Oklab => {
+                let lms = multiply(&SRGB_TO_LMS, multiply(&invert(&SRGB_TO_XYZ), xyz));
+                multiply(&LMS_TO_OKLAB, lms.map(f64::cbrt))
+            }
+            Space::XyzD50 => multiply(&invert(&D50_TO_D65), xyz),
+            Space::XyzD65 => xyz,
+            space => {
+                let (matrix, d50) = space.rgb().unwrap_or((&SRGB_TO_XYZ, false));
+                let xyz = if d50 {
+                    multiply(&invert(&D50_TO_D65), xyz)
+                } else {
+                    xyz
+                };
+                multiply(&invert(matrix), xyz).map(|v| space.encode(v))
+            }
+        }
+    }
+
+    fn srgb(components: [f64; 3]) -> CssColor {
+        CssColor {
+            space: Space::Srgb,
+            components,
+            alpha: 1.0,
+        }
+    }
+
+    /// Returns whether the color is within the gamut of sRGB.
+    pub fn is_in_gamut(&self) -> bool {
+        let [r, g, b] = self.convert(Space::Srgb).components;
+        [r, g, b].iter().all(|v| (-1e-6..=1.0 + 1e-6).contains(v))
+    }
+
+    /// Returns the color in sRGB, the colors out of its gamut being mapped into it by reducing
+    /// their chroma in OKLCH, as defined by CSS Color 4.
+    pub fn to_gamut(&self) -> CssColor {
+        const JND: f64 = 0.02;
+        const EPSILON: f64 = 0.0001;
+        let clip = |color: &CssColor| CssColor {
+            components: color
+                .convert(Space::Srgb)
+                .components
+                .map(|v| v.clamp(0.0, 1.0)),
+            ..color.convert(Space::Srgb)
+        };
+        let delta_eok = |a: &CssColor, b: &CssColor| {
+            let (a, b) = (a.convert(Space::Oklab), b.convert(Space::Oklab));
+            (0..3)
+                .map(|i| (a.components[i] - b.components[i]).powi(2))
+                .sum::<f64>()
+                .sqrt()
+        };
+
+        let mut current = self.convert(Space::Oklch);
+        let [lightness, chroma, _] = current.components;
+        if lightness >= 1.0 || lightness <= 0.0 {
+            let v = lightness.clamp(0.0, 1.0);
+            return CssColor {
+                alpha: self.alpha,
+                ..Self::srgb([v; 3])
+            };
+        }
+        if self.is_in_gamut() {
+            return clip(self);
+        }
+        let mut clipped = clip(&current);
+        if delta_eok(&clipped, &current) < JND {
+            return clipped;
+        }
+        let (mut min, mut max) = (0.0, chroma);
+        let mut min_in_gamut = true;
+        while max - min > EPSILON {
+            let chroma = (min + max) / 2.0;
+            current.components[1] = chroma;
+            if min_in_gamut && current.is_in_gamut() {
+                min = chroma;
+                continue;
+            }
+            clipped = clip(&current);
+            let e = delta_eok(&clipped, &current);
+            if e < JND {
+                if JND - e < EPSILON {
+                    break;
+                }
+                min_in_gamut = false;
+                min = chroma;
+            } else {
+                max = chroma;
+            }
+        }
+        clipped
+    }
+
+    /// Returns the hexadecimal notation of the color mapped into sRGB (e.g. `#ff00aa`, or
+    /// `#ff00aa80` if it is not opaque).
+    pub fn to_hex(&self) -> String {
+        let [r, g, b] = self
+            .to_gamut()
+            .components
+            .map(|v| (v * 255.0).round() as u8);
+        let hex = format!("#{:02x}{:02x}{:02x}", r, g, b);
+        if self.alpha < 1.0 {
+            format!("{}{:02x}", hex, (self.alpha * 255.0).round() as u8)
+        } else {
+            hex
+        }
+    }
+
+    /// Returns the relative luminance of the color mapped into sRGB, from 0 for black to 1 for
+    /// white, as defined by WCAG.
+    pub fn luminance(&self) -> f64 {
+        let [r, g, b] = self.to_gamut().components.map(|v| Space::Srgb.decode(v));
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    /// Returns the WCAG contrast ratio of the colors, from 1 to 21, regardless of their alpha
+    /// (e.g. at least 4.5 for the text of AA).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use squeeze::color::CssColor;
+    ///
+    /// let white = CssColor::parse("white").unwrap();
+    /// let gray = CssColor::parse("#767676").unwrap();
+    /// assert!(gray.contrast(&white) >= 4.5);
+    /// ```
+    pub fn contrast(&self, other: &CssColor) -> f64 {
+        let (a, b) = (self.luminance(), other.luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Returns the perceptual distance of the colors as defined by CIEDE2000 (ΔE00), about 1
+    /// for a just noticeable difference, regardless of their alpha.
+    pub fn delta_e(&self, other: &CssColor) -> f64 {
+        let [l1, a1, b1] = self.convert(Space::Lab).components;
+        let [l2, a2, b2] = other.convert(Space::Lab).components;
+        let pow7 = |v: f64| v.powi(7);
+        let c = (a1.hypot(b1) + a2.hypot(b2)) / 2.0;
+        let g = 0.5 * (1.0 - (pow7(c) / (pow7(c) + pow7(25.0))).sqrt());
+        let (a1, a2) = (a1 * (1.0 + g), a2 * (1.0 + g));
+        let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+        let hue = |a: f64, b: f64| {
+            if a == 0.0 && b == 0.0 {
+                0.0
+            } else {
+                b.atan2(a).to_degrees().rem_euclid(360.0)
+            }
+        };
+        let (h1, h2) = (hue(a1, b1), hue(a2, b2));
+
+        let dl = l2 - l1;
+        let dc = c2 - c1;
+        let dh = match h2 - h1 {
+            _ if c1 * c2 == 0.0 => 0.0,
+            d if d > 180.0 => d - 360.0,
+            d if d < -180.0 => d + 360.0,
+            d => d,
+        };
+        let dh = 2.0 * (c1 * c2).sqrt() * (dh / 2.0).to_radians().sin();
+
+        let l = (l1 + l2) / 2.0;
+        let c = (c1 + c2) / 2.0;
+        let h = match h1 + h2 {
+            sum if c1 * c2 == 0.0 => sum,
+            sum if (h1 - h2).abs() <= 180.0 => sum / 2.0,
+            sum if sum < 360.0 => (sum + 360.0) / 2.0,
+            sum => (sum - 360.0) / 2.0,
+        };
+        let cos = |degrees: f64| degrees.to_radians().cos();
+        let t = 1.0 - 0.17 * cos(h - 30.0) + 0.24 * cos(2.0 * h) + 0.32 * cos(3.0 * h + 6.0)
+            - 0.20 * cos(4.0 * h - 63.0);
+        let theta = 30.0 * (-((h - 275.0) / 25.0).powi(2)).exp();
+        let rc = 2.0 * (pow7(c) / (pow7(c) + pow7(25.0))).sqrt();
+        let sl = 1.0 + 0.015 * (l - 50.0).powi(2) / (20.0 + (l - 50.0).powi(2)).sqrt();
+        let sc = 1.0 + 0.045 * c;
+        let sh = 1.0 + 0.015 * c * t;
+        let rt = -(2.0 * theta).to_radians().sin() * rc;
+        ((dl / sl).powi(2) + (dc / sc).powi(2) + (dh / sh).powi(2) + rt * (dc / sc) * (dh / sh))
+            .sqrt()
+    }
+
+    fn parse_hex(hex: &str) -> Option<CssColor> {
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        // the short forms repeat each digit
+        let hex = match hex.len() {
+            3 | 4 => hex.chars().flat_map(|c| [c, c]).collect(),
+            6 | 8 => hex.to_string(),
+            _ => return None,
+        };
+        let value = u32::from_str_radix(&hex, 16).ok()?;
+        Some(if hex.len() == 8 {
+            Self::from_rgb(value >> 8, (value & 0xff) as f64 / 255.0)
+        } else {
+            Self::from_rgb(value, 1.0)
+        })
+    }
+
+    fn from_rgb(rgb: u32, alpha: f64) -> CssColor {
+        let channel = |shift: u32| ((rgb >> shift) & 0xff) as f64 / 255.0;
+        CssColor {
+            space: Space::Srgb,
+            components: [channel(16), channel(8), channel(0)],
+            alpha,
+        }
+    }
+
+    // Splits the arguments of a color function into tokens.
+    fn tokens(args: &str) -> Option<Vec<Token>> {
+        let mut tokens = Vec::new();
+        let mut rest = args.trim_start();
+        while !rest.is_empty() {
+            let len = match rest.as_bytes()[0] {
+                b',' => {
+                    tokens.push(Token::Comma);
+                    1
+                }
+                b'/' => {
+                    tokens.push(Token::Slash);
+                    1
+                }
+                b if b.is_ascii_alphabetic() => {
+                    let len = rest
+                        .bytes()
+                        .take_while(|b| b.is_ascii_alphanumeric() || *b == b'-')
+                        .count();
+                    let ident = &rest[..len];
+                    tokens.push(if ident.eq_ignore_ascii_case("none") {
+                        Token::None
+                    } else {
+                        Token::Ident(ident.to_ascii_lowercase())
+                    });
+                    len
+                }
+                _ => {
+                    let (number, len) = Self::number(rest)?;
+                    let unit_len = rest[len..]
+                        .bytes()
+                        .take_while(|b| b.is_ascii_alphabetic() || *b == b'%')
+                        .count();
+                    let unit = rest[len..len + unit_len].to_ascii_lowercase();
+                    tokens.push(match unit.as_str() {
+                        "" => Token::Number(number),
+                        "%" => Token::Percentage(number),
+                        "deg" => Token::Angle(number),
+                        "rad" => Token::Angle(number.to_degrees()),
+                        "grad" => Token::Angle(number * 0.9),
+                        "turn" => Token::Angle(number * 360.0),
+                        _ => return None,
+                    });
+                    len + unit_len
+                }
+            };
+            rest = rest[len..].trim_start();
+        }
+        Some(tokens)
+    }
+
+    // A CSS number (e.g. -1.5e2 or .5), returns it along with its length.
+    fn number(s: &str) -> Option<(f64, usize)> {
+        let bytes = s.as_bytes();
+        let digits = |pos: usize| {
+            bytes[pos.min(bytes.len())..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit())
+                .count()
+        };
+        let mut len = usize::from(matches!(bytes[0], b'+' | b'-'));
+        let integer = digits(len);
+        len += integer;
+        let mut fraction = 0;
+        if bytes.get(len) == Some(&b'.') {
+            fraction = digits(len + 1);
+            if fraction > 0 {
+                len += 1 + fraction;
+            }
+        }
+        if integer + fraction == 0 {
+            return None;
+        }
+        if matches!(bytes.get(len), Some(b'e' | b'E')) {
+            let sign = usize::from(matches!(bytes.get(len + 1), Some(b'+' | b'-')));
+            let exponent = digits(len + 1 + sign);
+            if exponent > 0 {
+                len += 1 + sign + exponent;
+            }
+        }
+        Some((s[..len].parse().ok()?, len))
+    }
+
+    // c1, c2, c3[, alpha], without none
+    fn legacy_arguments(tokens: &[Token]) -> Option<([&Token; 3], Option<&Token>)> {
+        if tokens.contains(&Token::None) {
+            return None;
+        }
+        match tokens {
+            [c1, Token::Comma, c2, Token::Comma, c3] => Some(([c1, c2, c3], None)),
+            [c1, Token::Comma, c2, Token::Comma, c3, Token::Comma, alpha] => {
+                Some(([c1, c2, c3], Some(alpha)))
+            }
+            _ => None,
+        }
+    }
+
+    // c1 c2 c3[ / alpha]
+    fn arguments(tokens: &[Token]) -> Option<([&Token; 3], Option<&Token>)> {
+        match tokens {
+            [c1, c2, c3] => Some(([c1, c2, c3], None)),
+            [c1, c2, c3, Token::Slash, alpha] => Some(([c1, c2, c3], Some(alpha))),
+            _ => None,
+        }
+    }
+}
+
+// Formats the number with up to the given number of decimals.
+fn decimal(v: f64, decimals: usize) -> String {
+    let s = format!("{:.*}", decimals, v);
+    let s = if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &s
+    };
+    if s == "-0" {
+        "0".to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+impl fmt::Display for CssColor {
+    /// Formats the color in the modern syntax of its color space (e.g. `rgb(255 0 170 / 0.5)` or
+    /// `oklch(70% 0.1 240)`), the sRGB components being rounded to integers.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [c1, c2, c3] = self.components;
+        let percent = |v: f64| decimal(v * 100.0, 2) + "%";
+        let args = match self.space {
+            Space::Srgb => [c1, c2, c3].map(|v| decimal(v * 255.0, 0)),
+            Space::Hsl | Space::Hwb => [decimal(c1, 2), percent(c2), percent(c3)],
+            Space::Lab | Space::Lch => [c1, c2, c3].map(|v| decimal(v, 2)),
+            Space::Oklab => [percent(c1), decimal(c2, 4), decimal(c3, 4)],
+            Space::Oklch => [percent(c1), decimal(c2, 4), decimal(c3, 2)],
+            _ => [c1, c2, c3].map(|v| decimal(v, 6)),
+        };
+        match self.space {
+            Space::Srgb => write!(f, "rgb(")?,
+            Space::Hsl | Space::Hwb | Space::Lab | Space::Lch | Space::Oklab | Space::Oklch => {
+                write!(f, "{}(", self.space.name())?
+            }
+            space => write!(f, "color({} ", space.name())?,
+        }
+        write!(f, "{}", args.join(" "))?;
+        if self.alpha < 1.0 {
+            write!(f, " / {}", decimal(self.alpha, 3))?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Groups the near-duplicate colors: each color joins the first group whose first color is
+/// within the given perceptual distance (see [`CssColor::delta_e`]), or starts a new one.
+/// Returns the indexes of the colors of each group.
+///
+/// # Example
+///
+/// ```
+/// use squeeze::color::{self, CssColor};
+///
+/// let colors: Vec<_> = ["#ff0000", "#0000ff", "#fe0101"]
+///     .iter()
+///     .filter_map(|s| CssColor::parse(s))
+///     .collect();
+/// assert_eq!(vec![vec![0, 2], vec![1]], color::cluster(&colors, 2.0));
+/// ```
+pub fn cluster(colors: &[CssColor], max_delta_e: f64) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for (i, color) in colors.iter().enumerate() {
+        match groups
+            .iter_mut()
+            .find(|group| colors[group[0]].delta_e(color) <= max_delta_e)
+        {
+            Some(group) => group.push(i),
+            None => groups.push(vec![i]),
+        }
+    }
+    groups
+}
 
 impl Color {
     fn is_hex(b: u8) -> bool {
@@ -43,47 +1153,39 @@ impl Color {
         None
     }
 
-    fn try_css_function(input: &[u8], pos: usize) -> Option<Range<usize>> {
-        let remaining = &input[pos..];
-
-        let prefixes: &[&[u8]] = &[b"rgba(", b"rgb(", b"hsla(", b"hsl("];
-
-        let mut matched_len = 0;
-        for prefix in prefixes {
-            if remaining.len() >= prefix.len()
-                && remaining[..prefix.len()].eq_ignore_ascii_case(prefix)
-            {
-                matched_len = prefix.len();
-                break;
+    // The function or the name starting at the given position, the word it starts with being of
+    // the given length.
+    fn try_word(&self, input: &[u8], pos: usize, len: usize) -> Option<Range<usize>> {
+        let word = std::str::from_utf8(&input[pos..pos + len]).ok()?;
+        if input.get(pos + len) == Some(&b'(') {
+            if !FUNCTIONS.iter().any(|f| f.eq_ignore_ascii_case(word)) {
+                return None;
             }
+            return Self::try_css_function(input, pos);
         }
 
-        if matched_len == 0 {
+        // the names are not part of identifiers (e.g. red-team or .red)
+        let is_part = |b: &u8| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.');
+        if !self.names
+            || pos > 0 && is_part(&input[pos - 1])
+            || input.get(pos + len).is_some_and(is_part)
+        {
             return None;
         }
+        let is_name = word.eq_ignore_ascii_case("currentcolor") || CssColor::parse(word).is_some();
+        is_name.then_some(pos..pos + len)
+    }
 
+    fn try_css_function(input: &[u8], pos: usize) -> Option<Range<usize>> {
         // Check boundary before: not preceded by alphanumeric
         if pos > 0 && input[pos - 1].is_ascii_alphanumeric() {
             return None;
         }
 
-        let mut end = pos + matched_len;
-        let mut depth = 1;
-
-        while end < input.len() && depth > 0 {
-            match input[end] {
-                b'(' => depth += 1,
-                b')' => depth -= 1,
-                _ => {}
-            }
-            end += 1;
-        }
-
-        if depth == 0 {
-            Some(pos..end)
-        } else {
-            None
-        }
+        let close = pos + input[pos..].iter().position(|&b| b == b')')?;
+        let function = std::str::from_utf8(&input[pos..=close]).ok()?;
+        CssColor::parse(function)?;
+        Some(pos..close + 1)
     }
 }
 
@@ -103,10 +1205,16 @@ impl Finder for Color {
                 }
             }
 
-            if matches!(input[idx], b'r' | b'R' | b'h' | b'H') {
-                if let Some(range) = Self::try_css_function(input, idx) {
+            if input[idx].is_ascii_alphabetic() {
+                let len = input[idx..]
+                    .iter()
+                    .take_while(|b| b.is_ascii_alphabetic())
+                    .count();
+                if let Some(range) = self.try_word(input, idx, len) {
                     return Some(range);
                 }
+                idx += len;
+                continue;
             }
 
             idx += 1;
@@ -263,4 +1371,305 @@ mod tests {
         let range = finder.find(input).unwrap();
         assert_eq!("#333", &input[range]);
     }
+
+    #[test]
+    fn find_should_extract_css_color_4_functions() {
+        let finder = Color::default();
+        for (input, expected) in [
+            ("rgb(255 0 170 / 50%)", Some("rgb(255 0 170 / 50%)")),
+            ("RGB(100%, 0%, 50%)", Some("RGB(100%, 0%, 50%)")),
+            ("hsl(120deg 100% 50%)", Some("hsl(120deg 100% 50%)")),
+            (
+                "hsl(0.25turn 100 50 / 0.5)",
+                Some("hsl(0.25turn 100 50 / 0.5)"),
+            ),
+            ("hwb(194 0% 0%)", Some("hwb(194 0% 0%)")),
+            (
+                "lab(52.2345% 40.1645 59.9971)",
+                Some("lab(52.2345% 40.1645 59.9971)"),
+            ),
+            (
+                "lch(52.2345% 72.2 56.2 / .5)",
+                Some("lch(52.2345% 72.2 56.2 / .5)"),
+            ),
+            (
+                "oklab(59.686% 0.1009 0.1192)",
+                Some("oklab(59.686% 0.1009 0.1192)"),
+            ),
+            ("oklch(70% 0.1 240)", Some("oklch(70% 0.1 240)")),
+            ("rgb(none 0 0)", Some("rgb(none 0 0)")),
+            (
+                "color(display-p3 1 0.5 0)",
+                Some("color(display-p3 1 0.5 0)"),
+            ),
+            (
+                "color(xyz-d50 0.2 0.3 0.4 / 1)",
+                Some("color(xyz-d50 0.2 0.3 0.4 / 1)"),
+            ),
+            ("a: rgb(300, 0, 0); b: #fff", Some("#fff")),
+        ] {
+            assert_eq!(
+                expected,
+                finder.find(input).map(|range| &input[range]),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn find_should_reject_invalid_arguments() {
+        let finder = Color::default();
+        for input in [
+            "rgb(255, 0)",
+            "rgb(255, 0, 170, 0.5, 1)",
+            "rgb(255 0 170 0.5)",
+            "rgb(255, 0 170)",
+            "rgb(100%, 0, 0)",
+            "rgb(none, 0, 0)",
+            "rgb(256 0 0)",
+            "rgb(0 0 0 / 2)",
+            "hsl(120, 100, 50)",
+            "hsl(120deg 101% 50%)",
+            "hwb(194, 0%, 0%)",
+            "lab(101 0 0)",
+            "lch(50 -1 0)",
+            "oklab(2 0 0)",
+            "oklch(70% -0.1 240)",
+            "color(unknown 1 0 0)",
+            "color(1 0 0)",
+            "rgb(1px 0 0)",
+            "rgb(var(--red))",
+            "lab(50deg 0 0)",
+        ] {
+            assert_eq!(None, finder.find(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn find_should_extract_names_given_the_option() {
+        let finder = Color { names: true };
+        for (input, expected) in [
+            ("color: RebeccaPurple;", Some("RebeccaPurple")),
+            ("fill: currentColor", Some("currentColor")),
+            ("background: transparent", Some("transparent")),
+            ("a red-team, .red or redish", None),
+            ("fill: darkgrey", Some("darkgrey")),
+        ] {
+            assert_eq!(
+                expected,
+                finder.find(input).map(|range| &input[range]),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(None, Color::default().find("color: red"));
+    }
+
+    #[test]
+    fn css_color_parse_should_return_the_components() {
+        for (input, space, components, alpha) in [
+            (
+                "#f0a8",
+                Space::Srgb,
+                [1.0, 0.0, 170.0 / 255.0],
+                136.0 / 255.0,
+            ),
+            (
+                "tomato",
+                Space::Srgb,
+                [1.0, 99.0 / 255.0, 71.0 / 255.0],
+                1.0,
+            ),
+            ("transparent", Space::Srgb, [0.0; 3], 0.0),
+            ("rgba(255, 0, 0, 0.5)", Space::Srgb, [1.0, 0.0, 0.0], 0.5),
+            ("hsl(-90 50% 25%)", Space::Hsl, [270.0, 0.5, 0.25], 1.0),
+            (
+                "hwb(1rad 10% 20%)",
+                Space::Hwb,
+                [1f64.to_degrees(), 0.1, 0.2],
+                1.0,
+            ),
+            (
+                "lch(50% 100% 200grad)",
+                Space::Lch,
+                [50.0, 150.0, 180.0],
+                1.0,
+            ),
+            ("oklab(0.5 -100% 50%)", Space::Oklab, [0.5, -0.4, 0.2], 1.0),
+            (
+                "color(rec2020 50% 1e-1 0)",
+                Space::Rec2020,
+                [0.5, 0.1, 0.0],
+                1.0,
+            ),
+        ] {
+            let color = CssColor::parse(input).unwrap();
+            assert_eq!(
+                (space, components, alpha),
+                (color.space, color.components, color.alpha),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(None, CssColor::parse("currentColor"));
+        assert_eq!(148, NAMES.len());
+    }
+
+    #[test]
+    fn space_from_name_should_be_case_insensitive() {
+        assert_eq!(Some(Space::DisplayP3), Space::from_name("Display-P3"));
+        assert_eq!(Some(Space::XyzD65), Space::from_name("xyz"));
+        assert_eq!("xyz-d65", Space::XyzD65.name());
+        assert_eq!("oklch", Space::Oklch.name());
+    }
+
+    #[test]
+    fn convert_should_output_the_color_in_the_given_space() {
+        for (input, space, expected) in [
+            ("#ff0000", Space::Hsl, "hsl(0 100% 50%)"),
+            ("#ff0000", Space::Oklch, "oklch(62.8% 0.2577 29.23)"),
+            ("#ff000080", Space::Srgb, "rgb(255 0 0 / 0.502)"),
+            ("hsl(120 100% 25%)", Space::Srgb, "rgb(0 128 0)"),
+            ("hwb(240 20% 40%)", Space::Srgb, "rgb(51 51 153)"),
+            ("hwb(0 60% 60%)", Space::Srgb, "rgb(128 128 128)"),
+            ("white", Space::Lab, "lab(100 0 0)"),
+            ("white", Space::Oklch, "oklch(100% 0 0)"),
+            ("lch(50 0 0)", Space::Srgb, "rgb(119 119 119)"),
+            ("oklch(62.8% 0.2577 29.23)", Space::Srgb, "rgb(255 0 0)"),
+            ("color(display-p3 1 0 0)", Space::Srgb, "rgb(279 -58 -38)"),
+            ("color(rec2020 1 1 1)", Space::Srgb, "rgb(255 255 255)"),
+            (
+                "color(prophoto-rgb 0.5 0.5 0.5)",
+                Space::Srgb,
+                "rgb(146 146 146)",
+            ),
+            (
+                "#00ff00",
+                Space::A98Rgb,
+                "color(a98-rgb 0.564972 1 0.234424)",
+            ),
+            (
+                "#00ff00",
+                Space::DisplayP3,
+                "color(display-p3 0.458402 0.985265 0.298295)",
+            ),
+            ("#ff0000", Space::Lab, "lab(54.29 80.8 69.89)"),
+            (
+                "#ff0000",
+                Space::Rec2020,
+                "color(rec2020 0.791977 0.230976 0.073761)",
+            ),
+            (
+                "#ff0000",
+                Space::ProphotoRgb,
+                "color(prophoto-rgb 0.702248 0.275721 0.103548)",
+            ),
+            (
+                "#ff0000",
+                Space::XyzD50,
+                "color(xyz-d50 0.436066 0.222493 0.013924)",
+            ),
+        ] {
+            let color = CssColor::parse(input).unwrap();
+            assert_eq!(expected, color.convert(space).to_string(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn convert_should_round_trip() {
+        let color = CssColor::parse("#6a5acd").unwrap();
+        for space in [
+            Space::Hsl,
+            Space::Hwb,
+            Space::Lab,
+            Space::Lch,
+            Space::Oklab,
+            Space::Oklch,
+            Space::SrgbLinear,
+            Space::DisplayP3,
+            Space::A98Rgb,
+            Space::ProphotoRgb,
+            Space::Rec2020,
+            Space::XyzD50,
+            Space::XyzD65,
+        ] {
+            assert_eq!("#6a5acd", color.convert(space).to_hex(), "{}", space.name());
+        }
+    }
+
+    #[test]
+    fn to_gamut_should_map_the_colors_into_srgb() {
+        let color = CssColor::parse("color(display-p3 1 0 0)").unwrap();
+        assert!(!color.is_in_gamut());
+        let mapped = color.to_gamut();
+        assert!(mapped.is_in_gamut());
+        assert_eq!("#ff0b0c", mapped.to_hex());
+        assert_eq!(
+            "#ffffff",
+            CssColor::parse("oklch(100% 0.3 120)").unwrap().to_hex()
+        );
+        assert_eq!(
+            "#00000080",
+            CssColor::parse("lab(0 50 50 / 0.5)").unwrap().to_hex()
+        );
+    }
+
+    #[test]
+    fn contrast_should_follow_wcag() {
+        let white = CssColor::parse("#fff").unwrap();
+        for (input, expected) in [
+            ("#000", 21.0),
+            ("#fff", 1.0),
+            ("#777", 4.48),
+            ("#767676", 4.54),
+        ] {
+            let color = CssColor::parse(input).unwrap();
+            assert_eq!(
+                expected,
+                (color.contrast(&white) * 100.0).round() / 100.0,
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn delta_e_should_follow_ciede2000() {
+        // from the test data of Sharma, Wu and Dalal
+        for (lab1, lab2, expected) in [
+            ("lab(50 2.6772 -79.7751)", "lab(50 0 -82.7485)", 2.0425),
+            ("lab(50 -1.3802 -84.2814)", "lab(50 0 -82.7485)", 1.0),
+            ("lab(50 2.5 0)", "lab(73 25 -18)", 27.1492),
+            (
+                "lab(60.2574 -34.0099 36.2677)",
+                "lab(60.4626 -34.1751 39.4387)",
+                1.2644,
+            ),
+        ] {
+            let (c1, c2) = (
+                CssColor::parse(lab1).unwrap(),
+                CssColor::parse(lab2).unwrap(),
+            );
+            assert_eq!(
+                expected,
+                (c1.delta_e(&c2) * 10000.0).round() / 10000.0,
+                "{}",
+                lab1
+            );
+        }
+    }
+
+    #[test]
+    fn cluster_should_group_the_near_duplicates() {
+        let colors: Vec<_> = ["#336699", "#ff0000", "#346699", "red", "#336698", "#00ff00"]
+            .iter()
+            .filter_map(|s| CssColor::parse(s))
+            .collect();
+        assert_eq!(
+            vec![vec![0, 2, 4], vec![1, 3], vec![5]],
+            cluster(&colors, 2.0)
+        );
+        assert_eq!(6, cluster(&colors, 0.0).len() + 1);
+    }
 }
@@ -0,0 +1,697 @@
+//! Comment detection for source code.
+//!
+//! This module provides a [`Lexer`] that tells the comments of a source file apart from its code
+//! and string literals, so that a search can be restricted to the comments (e.g. for codetags).
+//! Line comments, block comments (nested or not) and docstrings are supported for the languages
+//! listed by [`Language::from_name`].
+//!
+//! # Example
+//!
+//! ```
+//! use squeeze::comment::{Language, Lexer};
+//!
+//! let mut lexer = Lexer::new(Language::from_name("rust").unwrap());
+//! let line = r#"let s = "// TODO: not a comment"; /* TODO: a comment */"#;
+//! let comments: Vec<_> = lexer.comments(line).into_iter().map(|r| &line[r]).collect();
+//! assert_eq!(vec![" TODO: a comment "], comments);
+//! ```
+
+use std::ops::Range;
+use std::path::Path;
+
+/// A string literal delimiter.
+struct Quote {
+    delimiter: &'static str,
+    // whether a backslash escapes the next character
+    escape: bool,
+    // whether the literal can span several lines
+    multiline: bool,
+    // whether the literal is a docstring when it starts a statement
+    doc: bool,
+    // whether the delimiter following a letter or a digit is an apostrophe (e.g. `don't`) rather
+    // than the start of a literal
+    apostrophe: bool,
+}
+
+const DOUBLE: Quote = Quote {
+    delimiter: "\"",
+    escape: true,
+    multiline: false,
+    doc: false,
+    apostrophe: false,
+};
+const SINGLE: Quote = Quote {
+    delimiter: "'",
+    escape: true,
+    multiline: false,
+    doc: false,
+    apostrophe: false,
+};
+const DOUBLE_MULTILINE: Quote = Quote {
+    delimiter: "\"",
+    escape: true,
+    multiline: true,
+    doc: false,
+    apostrophe: false,
+};
+const SINGLE_RAW: Quote = Quote {
+    delimiter: "'",
+    escape: false,
+    multiline: true,
+    doc: false,
+    apostrophe: false,
+};
+const BACKTICK: Quote = Quote {
+    delimiter: "`",
+    escape: true,
+    multiline: true,
+    doc: false,
+    apostrophe: false,
+};
+const BACKTICK_RAW: Quote = Quote {
+    delimiter: "`",
+    escape: false,
+    multiline: true,
+    doc: false,
+    apostrophe: false,
+};
+const TRIPLE_DOUBLE: Quote = Quote {
+    delimiter: "\"\"\"",
+    escape: true,
+    multiline: true,
+    doc: false,
+    apostrophe: false,
+};
+const TRIPLE_SINGLE: Quote = Quote {
+    delimiter: "'''",
+    escape: true,
+    multiline: true,
+    doc: false,
+    apostrophe: false,
+};
+const SINGLE_APOSTROPHE: Quote = Quote {
+    apostrophe: true,
+    ..SINGLE
+};
+const DOCSTRING_DOUBLE: Quote = Quote {
+    doc: true,
+    ..TRIPLE_DOUBLE
+};
+const DOCSTRING_SINGLE: Quote = Quote {
+    doc: true,
+    ..TRIPLE_SINGLE
+};
+
+const C_BLOCK: &[(&str, &str)] = &[("/*", "*/")];
+const XML_BLOCK: &[(&str, &str)] = &[("<!--", "-->")];
+
+/// The comment syntax of a programming language.
+pub struct Language {
+    /// The name of the language (e.g. `rust`).
+    pub name: &'static str,
+    extensions: &'static [&'static str],
+    filenames: &'static [&'static str],
+    line: &'static [&'static str],
+    block: &'static [(&'static str, &'static str)],
+    nested: bool,
+    quotes: &'static [Quote],
+    // whether the single quotes delimit char literals, told apart from the lifetimes and labels
+    chars: bool,
+}
+
+impl Language {
+    const fn new(name: &'static str, extensions: &'static [&'static str]) -> Self {
+        Language {
+            name,
+            extensions,
+            filenames: &[],
+            line: &[],
+            block: &[],
+            nested: false,
+            quotes: &[],
+            chars: false,
+        }
+    }
+
+    const fn c_like(name: &'static str, extensions: &'static [&'static str]) -> Self {
+        Language {
+            line: &["//"],
+            block: C_BLOCK,
+            quotes: &[DOUBLE, SINGLE],
+            ..Language::new(name, extensions)
+        }
+    }
+
+    const fn hash(name: &'static str, extensions: &'static [&'static str]) -> Self {
+        Language {
+            line: &["#"],
+            quotes: &[DOUBLE, SINGLE],
+            ..Language::new(name, extensions)
+        }
+    }
+
+    const fn markup(name: &'static str, extensions: &'static [&'static str]) -> Self {
+        Language {
+            block: XML_BLOCK,
+            ..Language::new(name, extensions)
+        }
+    }
+
+    /// Returns the language with the given name or file extension (e.g. `python` or `py`),
+    /// case-insensitively.
+    pub fn from_name(name: &str) -> Option<&'static Language> {
+        let name = name.to_lowercase();
+        LANGUAGES
+            .iter()
+            .find(|l| l.name == name)
+            .or_else(|| LANGUAGES.iter().find(|l| l.extensions.contains(&&*name)))
+    }
+
+    /// Returns the language of the given file, detected from its name or extension.
+    pub fn from_path(path: &Path) -> Option<&'static Language> {
+        let filename = path.file_name()?.to_str()?;
+        if let Some(language) = LANGUAGES.iter().find(|l| l.filenames.contains(&filename)) {
+            return Some(language);
+        }
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        LANGUAGES
+            .iter()
+            .find(|l| l.extensions.contains(&&*extension))
+    }
+}
+
+static LANGUAGES: &[Language] = &[
+    Language::c_like("c", &["c", "h"]),
+    Language::c_like("cpp", &["cc", "cpp", "cxx", "hh", "hpp", "hxx", "ino"]),
+    Language::c_like("csharp", &["cs"]),
+    Language::c_like("java", &["java"]),
+    Language {
+        quotes: &[DOUBLE, SINGLE, BACKTICK],
+        ..Language::c_like("javascript", &["js", "cjs", "mjs", "jsx"])
+    },
+    Language {
+        quotes: &[DOUBLE, SINGLE, BACKTICK],
+        ..Language::c_like("typescript", &["ts", "cts", "mts", "tsx"])
+    },
+    Language {
+        quotes: &[DOUBLE, SINGLE, BACKTICK_RAW],
+        ..Language::c_like("go", &["go"])
+    },
+    Language {
+        nested: true,
+        quotes: &[DOUBLE_MULTILINE],
+        chars: true,
+        ..Language::c_like("rust", &["rs"])
+    },
+    Language {
+        nested: true,
+        quotes: &[TRIPLE_DOUBLE, DOUBLE, SINGLE],
+        ..Language::c_like("kotlin", &["kt", "kts"])
+    },
+    Language {
+        nested: true,
+        quotes: &[TRIPLE_DOUBLE, DOUBLE, SINGLE],
+        ..Language::c_like("scala", &["scala", "sc"])
+    },
+    Language {
+        nested: true,
+        quotes: &[TRIPLE_DOUBLE, DOUBLE],
+        ..Language::c_like("swift", &["swift"])
+    },
+    Language {
+        quotes: &[TRIPLE_DOUBLE, TRIPLE_SINGLE, DOUBLE, SINGLE],
+        ..Language::c_like("dart", &["dart"])
+    },
+    Language {
+        line: &["//", "#"],
+        quotes: &[DOUBLE_MULTILINE, SINGLE_RAW],
+        ..Language::c_like("php", &["php"])
+    },
+    Language {
+        line: &[],
+        ..Language::c_like("css", &["css"])
+    },
+    Language::c_like("scss", &["scss", "less"]),
+    Language {
+        quotes: &[DOCSTRING_DOUBLE, DOCSTRING_SINGLE, DOUBLE, SINGLE],
+        ..Language::hash("python", &["py", "pyi", "pyw"])
+    },
+    Language {
+        filenames: &["Gemfile", "Rakefile"],
+        quotes: &[DOUBLE_MULTILINE, SINGLE_RAW],
+        ..Language::hash("ruby", &["rb"])
+    },
+    Language {
+        quotes: &[DOUBLE_MULTILINE, SINGLE_RAW],
+        ..Language::hash("shell", &["sh", "bash", "zsh", "fish", "ksh"])
+    },
+    Language {
+        quotes: &[DOUBLE, SINGLE_APOSTROPHE],
+        ..Language::hash("perl", &["pl", "pm"])
+    },
+    Language {
+        quotes: &[DOUBLE, SINGLE_APOSTROPHE],
+        ..Language::hash("r", &["r"])
+    },
+    Language {
+        quotes: &[DOUBLE, SINGLE_APOSTROPHE],
+        ..Language::hash("yaml", &["yaml", "yml"])
+    },
+    Language {
+        quotes: &[TRIPLE_DOUBLE, TRIPLE_SINGLE, DOUBLE, SINGLE],
+        ..Language::hash("toml", &["toml"])
+    },
+    Language {
+        filenames: &["Makefile", "makefile", "GNUmakefile"],
+        quotes: &[],
+        ..Language::hash("make", &["mk"])
+    },
+    Language {
+        filenames: &["Dockerfile", "Containerfile"],
+        quotes: &[],
+        ..Language::hash("dockerfile", &["dockerfile"])
+    },
+    Language {
+        filenames: &["CMakeLists.txt"],
+        quotes: &[DOUBLE],
+        ..Language::hash("cmake", &["cmake"])
+    },
+    Language {
+        block: C_BLOCK,
+        quotes: &[DOUBLE],
+        ..Language::hash("nix", &["nix"])
+    },
+    Language {
+        quotes: &[TRIPLE_DOUBLE, DOUBLE],
+        ..Language::hash("elixir", &["ex", "exs"])
+    },
+    Language {
+        line: &["--"],
+        block: C_BLOCK,
+        quotes: &[SINGLE, DOUBLE],
+        ..Language::new("sql", &["sql"])
+    },
+    Language {
+        line: &["--"],
+        block: &[("--[[", "]]")],
+        quotes: &[DOUBLE, SINGLE],
+        ..Language::new("lua", &["lua"])
+    },
+    Language {
+        line: &["--"],
+        block: &[("{-", "-}")],
+        nested: true,
+        quotes: &[DOUBLE],
+        ..Language::new("haskell", &["hs", "elm"])
+    },
+    Language {
+        block: &[("(*", "*)")],
+        nested: true,
+        quotes: &[DOUBLE],
+        ..Language::new("ocaml", &["ml", "mli"])
+    },
+    Language {
+        line: &[";"],
+        quotes: &[DOUBLE_MULTILINE],
+        ..Language::new("lisp", &["lisp", "el", "clj", "cljs", "scm"])
+    },
+    Language {
+        line: &["%"],
+        quotes: &[DOUBLE],
+        ..Language::new("erlang", &["erl", "hrl"])
+    },
+    Language {
+        line: &["%"],
+        ..Language::new("tex", &["tex", "sty", "cls"])
+    },
+    Language {
+        line: &[";", "#"],
+        ..Language::new("ini", &["ini", "cfg"])
+    },
+    Language::markup("html", &["html", "htm", "xhtml", "vue", "svelte"]),
+    Language::markup("xml", &["xml", "svg", "xsl", "plist"]),
+    Language::markup("markdown", &["md", "markdown"]),
+];
+
+#[derive(Clone, Copy)]
+enum State {
+    Code,
+    Block {
+        open: &'static str,
+        close: &'static str,
+        depth: usize,
+    },
+    String {
+        quote: &'static Quote,
+        doc: bool,
+    },
+}
+
+/// A lexer that finds the comments of a source file, fed one line at a time.
+///
+/// The lexer keeps track of the block comments and string literals spanning several lines, so
+/// the lines of a file must all be given, in order.
+pub struct Lexer {
+    language: &'static Language,
+    state: State,
+}
+
+impl Lexer {
+    pub fn new(language: &'static Language) -> Self {
+        Lexer {
+            language,
+            state: State::Code,
+        }
+    }
+
+    /// Returns whether the last line ended inside a block comment or a docstring.
+    pub fn in_comment(&self) -> bool {
+        matches!(
+            self.state,
+            State::Block { .. } | State::String { doc: true, .. }
+        )
+    }
+
+    /// Returns the ranges of the comments (and docstrings) in the given line, excluding their
+    /// delimiters.
+    pub fn comments(&mut self, line: &str) -> Vec<Range<usize>> {
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        let mut i = 0;
+
+        while i < line.len() {
+            let rest = &line[i..];
+            match self.state {
+                State::Code => {
+                    if let Some(&(open, close)) = self
+                        .language
+                        .block
+                        .iter()
+                        .find(|(o, _)| rest.starts_with(o))
+                    {
+                        i += open.len();
+                        start = i;
+                        self.state = State::Block {
+                            open,
+                            close,
+                            depth: 1,
+                        };
+                        continue;
+                    }
+                    if let Some(open) = self.language.line.iter().find(|o| rest.starts_with(*o)) {
+                        ranges.push(i + open.len()..line.len());
+                        return ranges;
+                    }
+                    if self.language.chars {
+                        if let Some(len) = char_literal(rest) {
+                            i += len;
+                            continue;
+                        }
+                    }
+                    let after_word = line[..i]
+                        .chars()
+                        .next_back()
+                        .is_some_and(char::is_alphanumeric);
+                    if let Some(quote) =
+                        self.language.quotes.iter().find(|q| {
+                            rest.starts_with(q.delimiter) && !(q.apostrophe && after_word)
+                        })
+                    {
+                        let doc = quote.doc && line[..i].trim().is_empty();
+                        i += quote.delimiter.len();
+                        start = i;
+                        self.state = State::String { quote, doc };
+                        continue;
+                    }
+                }
+                State::Block { open, close, depth } => {
+                    if rest.starts_with(close) {
+                        if depth == 1 {
+                            ranges.push(start..i);
+                            self.state = State::Code;
+                        } else {
+                            self.state = State::Block {
+                                open,
+                                close,
+                                depth: depth - 1,
+                            };
+                        }
+                        i += close.len();
+                        continue;
+                    }
+                    if self.language.nested && rest.starts_with(open) {
+                        self.state = State::Block {
+                            open,
+                            close,
+                            depth: depth + 1,
+                        };
+                        i += open.len();
+                        continue;
+                    }
+                }
+                State::String { quote, doc } => {
+                    if quote.escape && rest.starts_with('\\') {
+                        i += 1;
+                        if i >= line.len() {
+                            break;
+                        }
+                    } else if rest.starts_with(quote.delimiter) {
+                        if doc {
+                            ranges.push(start..i);
+                        }
+                        i += quote.delimiter.len();
+                        self.state = State::Code;
+                        continue;
+                    }
+                }
+            }
+            i += line[i..].chars().next().map_or(1, char::len_utf8);
+        }
+
+        match self.state {
+            State::Code => {}
+            State::Block { .. } => ranges.push(start..line.len()),
+            State::String { quote, doc } => {
+                if doc {
+                    ranges.push(start..line.len());
+                }
+                if !quote.multiline {
+                    self.state = State::Code;
+                }
+            }
+        }
+        ranges
+    }
+}
+
+// Returns the length of the char (or byte) literal the text starts with, e.g. `'x'` or `'\''`, as
+// opposed to a lifetime or a label (`'a`).
+fn char_literal(text: &str) -> Option<usize> {
+    let rest = text.strip_prefix('\'')?;
+    let mut chars = rest.char_indices();
+    let (_, c) = chars.next()?;
+    let end = match c {
+        '\'' => return None,
+        // '\n', '\'', '\x7f' or '\u{1F600}'
+        '\\' => {
+            let (i, escaped) = chars.next()?;
+            let start = i + escaped.len_utf8();
+            start + rest[start..].find('\'').filter(|&len| len <= 8)?
+        }
+        _ => {
+            let end = c.len_utf8();
+            rest[end..].starts_with('\'').then_some(end)?
+        }
+    };
+    Some(1 + end + 1)
+}
+
+#[cfg(test)]
+#[allow(clippy::useless_vec)]
+mod tests {
+    use super::*;
+
+    fn comments<'a>(language: &str, lines: &[&'a str]) -> Vec<&'a str> {
+        let mut lexer = Lexer::new(Language::from_name(language).unwrap());
+        lines
+            .iter()
+            .flat_map(|line| {
+                lexer
+                    .comments(line)
+                    .into_iter()
+                    .map(move |range| &line[range])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn it_should_find_line_comments() {
+        for (language, input, expected) in vec![
+            ("c", "int a; // TODO: b", vec![" TODO: b"]),
+            ("python", "a = 1  # TODO: b", vec![" TODO: b"]),
+            ("sql", "SELECT 1; -- TODO: b", vec![" TODO: b"]),
+            ("lisp", "(a) ; TODO: b", vec![" TODO: b"]),
+            ("tex", "a % TODO: b", vec![" TODO: b"]),
+            ("rust", "let a = 1;", vec![]),
+        ] {
+            assert_eq!(expected, comments(language, &[input]), "{}", input);
+        }
+    }
+
+    #[test]
+    fn it_should_find_block_comments() {
+        for (language, input, expected) in vec![
+            ("c", "a /* TODO: b */ c /* d */", vec![" TODO: b ", " d "]),
+            ("html", "<p><!-- TODO: b --></p>", vec![" TODO: b "]),
+            ("lua", "a --[[ TODO: b ]] c", vec![" TODO: b "]),
+            ("haskell", "{- TODO: b -}", vec![" TODO: b "]),
+            ("ocaml", "(* TODO: b *)", vec![" TODO: b "]),
+        ] {
+            assert_eq!(expected, comments(language, &[input]), "{}", input);
+        }
+    }
+
+    #[test]
+    fn it_should_find_block_comments_spanning_several_lines() {
+        assert_eq!(
+            vec!["* a", " * TODO: b", " c "],
+            comments("java", &["/** a", " * TODO: b", " c */ d();"])
+        );
+    }
+
+    #[test]
+    fn it_should_handle_nested_block_comments() {
+        assert_eq!(
+            vec![" a /* b */ c "],
+            comments("rust", &["/* a /* b */ c */ d"])
+        );
+        assert_eq!(vec![" a /* b "], comments("c", &["/* a /* b */ c */ d"]));
+    }
+
+    #[test]
+    fn it_should_ignore_comment_delimiters_in_strings() {
+        for (language, input, expected) in vec![
+            ("c", r#"puts("// TODO: a"); // b"#, vec![" b"]),
+            ("c", r#"puts("\" // TODO: a"); // b"#, vec![" b"]),
+            ("python", "s = 'the TODO: list' # b", vec![" b"]),
+            ("javascript", "s = `/* a */`", vec![]),
+            ("go", r"s := `\` // a", vec![" a"]),
+        ] {
+            assert_eq!(expected, comments(language, &[input]), "{}", input);
+        }
+    }
+
+    #[test]
+    fn it_should_handle_strings_spanning_several_lines() {
+        assert_eq!(
+            vec![" c"],
+            comments("javascript", &["s = `a", "// TODO: b", "`; // c"])
+        );
+        // unterminated single line strings end with the line
+        assert_eq!(vec![" b"], comments("c", &["s = \"a", "// b"]));
+    }
+
+    #[test]
+    fn it_should_tell_char_literals_from_lifetimes() {
+        for input in [
+            r#"fn a() -> char { '"' }"#,
+            r#"let b = b'"';"#,
+            r#"let c = ['\'', '"', '\\', '\u{22}'];"#,
+            r#"fn d<'a>(s: &'a str) -> bool { s == "'" }"#,
+            r#"'outer: loop { break 'outer; }"#,
+        ] {
+            assert_eq!(
+                vec![" TODO: first", " FIXME: second"],
+                comments(
+                    "rust",
+                    &[input, "// TODO: first", "let e = '\"'; // FIXME: second"]
+                ),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_ignore_apostrophes() {
+        for (language, input) in [
+            ("yaml", "title: Don't panic # TODO: first"),
+            ("perl", "print $it's; # TODO: first"),
+            ("r", "x <- paste0(it's) # TODO: first"),
+        ] {
+            assert_eq!(
+                vec![" TODO: first", " FIXME: second"],
+                comments(language, &[input, "# FIXME: second"]),
+                "{}",
+                input
+            );
+        }
+        // the quotes still delimit strings
+        assert_eq!(vec![" b"], comments("yaml", &["a: '# TODO: a' # b"]));
+    }
+
+    #[test]
+    fn it_should_find_docstrings() {
+        assert_eq!(
+            vec!["Compute.", "    TODO: b", "    "],
+            comments(
+                "python",
+                &[
+                    "def f():",
+                    "    \"\"\"Compute.",
+                    "    TODO: b",
+                    "    \"\"\"",
+                    "    x = 1"
+                ]
+            )
+        );
+        assert_eq!(vec!["TODO: a"], comments("python", &["'''TODO: a'''"]));
+        // triple-quoted strings which are not statements are not docstrings
+        assert_eq!(
+            Vec::<&str>::new(),
+            comments("python", &["s = \"\"\"TODO: a\"\"\""])
+        );
+    }
+
+    #[test]
+    fn it_should_handle_unicode() {
+        assert_eq!(vec![" é"], comments("c", &["\"à\" // é"]));
+    }
+
+    #[test]
+    fn from_name_should_accept_names_and_extensions() {
+        for (input, expected) in vec![
+            ("rust", Some("rust")),
+            ("RS", Some("rust")),
+            ("py", Some("python")),
+            ("tsx", Some("typescript")),
+            ("brainfuck", None),
+        ] {
+            assert_eq!(
+                expected,
+                Language::from_name(input).map(|l| l.name),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn from_path_should_detect_the_language() {
+        for (input, expected) in vec![
+            ("src/main.rs", Some("rust")),
+            ("lib/App.JSX", Some("javascript")),
+            ("Makefile", Some("make")),
+            ("build/CMakeLists.txt", Some("cmake")),
+            ("notes.txt", None),
+            ("README", None),
+        ] {
+            assert_eq!(
+                expected,
+                Language::from_path(Path::new(input)).map(|l| l.name),
+                "{}",
+                input
+            );
+        }
+    }
+}
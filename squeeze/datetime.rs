@@ -1,77 +1,651 @@
 use super::Finder;
+use std::collections::HashSet;
 use std::ops::Range;
+use std::time::{SystemTime, UNIX_EPOCH};
 
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+// The time zones of RFC 2822, in hours
+const ZONES: &[(&str, i32)] = &[
+    ("UT", 0),
+    ("UTC", 0),
+    ("GMT", 0),
+    ("Z", 0),
+    ("EST", -5),
+    ("EDT", -4),
+    ("CST", -6),
+    ("CDT", -5),
+    ("MST", -7),
+    ("MDT", -6),
+    ("PST", -8),
+    ("PDT", -7),
+];
+
+/// Finds the dates and datetimes written in the given formats, see [`Timestamp::parse`] to parse
+/// them. All the formats but [`Format::Epoch`] and [`Format::Duration`] are searched by default.
 #[derive(Default)]
-pub struct Datetime {}
+pub struct Datetime {
+    formats: HashSet<Format>,
+}
 
-impl Datetime {
-    fn try_date(input: &[u8], idx: usize) -> Option<Range<usize>> {
-        // YYYY-MM-DD with optional time component
-        if idx + 10 > input.len() {
+/// A family of datetime formats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// ISO 8601 and RFC 3339 (`2024-01-15` or `2024-01-15T10:30:00Z`), with the week
+    /// (`2024-W03-2T10:00Z`) and ordinal (`2024-015T10:00Z`) dates, only found with a time or in
+    /// an interval, and the intervals (`2024-01-01/2024-02-01`, `2024-01-01/P1M` or
+    /// `P1M/2024-02-01`, see [`Interval`]).
+    Iso,
+    /// The dates of the emails (`Tue, 15 Nov 1994 08:12:31 GMT`, as defined by RFC 2822), and of
+    /// HTTP (the same, and the obsolete `Tuesday, 15-Nov-94 08:12:31 GMT` and
+    /// `Tue Nov 15 08:12:31 1994`).
+    Rfc2822,
+    /// The timestamps of the BSD syslog (`Oct 11 22:14:15`), of the current year.
+    Syslog,
+    /// The timestamps of the Common Log Format of Apache and nginx (`10/Oct/2000:13:55:36 -0700`).
+    Clf,
+    /// The Unix timestamps in seconds (`1700000000`, optionally with a fraction) or milliseconds
+    /// (`1700000000000`), from 2001 to 2286.
+    Epoch,
+    /// The ISO 8601 durations (`PT30M` or `P1Y2M`, see [`Duration`]).
+    Duration,
+}
+
+impl Format {
+    const NAMES: &[(&str, Format)] = &[
+        ("iso", Format::Iso),
+        ("rfc2822", Format::Rfc2822),
+        ("syslog", Format::Syslog),
+        ("clf", Format::Clf),
+        ("epoch", Format::Epoch),
+        ("duration", Format::Duration),
+    ];
+
+    /// Returns the format of the given name (e.g. `rfc2822`), case-insensitively.
+    pub fn from_name(name: &str) -> Option<Format> {
+        Self::NAMES
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, format)| *format)
+    }
+
+    /// Returns the name of the format (e.g. `rfc2822`).
+    pub fn name(&self) -> &'static str {
+        Self::NAMES
+            .iter()
+            .find(|(_, format)| format == self)
+            .map_or("", |(name, _)| name)
+    }
+}
+
+// Reads the tokens of the datetimes written with words.
+struct Cursor<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl Cursor<'_> {
+    fn byte(&mut self, b: u8) -> Option<()> {
+        (self.input.get(self.pos) == Some(&b)).then(|| self.pos += 1)
+    }
+
+    fn spaces(&mut self) -> Option<()> {
+        let count = self.input[self.pos..]
+            .iter()
+            .take_while(|&&b| b == b' ')
+            .count();
+        self.pos += count;
+        (count > 0).then_some(())
+    }
+
+    fn number(&mut self, min: usize, max: usize) -> Option<u32> {
+        let count = self.input[self.pos..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if count < min || count > max {
+            return None;
+        }
+        let number = Datetime::parse_num(self.input, self.pos, count)?;
+        self.pos += count;
+        Some(number)
+    }
+
+    fn word(&mut self) -> &str {
+        let count = self.input[self.pos..]
+            .iter()
+            .take_while(|b| b.is_ascii_alphabetic())
+            .count();
+        let word = std::str::from_utf8(&self.input[self.pos..self.pos + count]).unwrap_or("");
+        self.pos += count;
+        word
+    }
+
+    // A month abbreviation (e.g. Oct), returns its number.
+    fn month(&mut self) -> Option<u8> {
+        let word = self.word();
+        let month = MONTHS
+            .iter()
+            .position(|m| m[..3].eq_ignore_ascii_case(word))?;
+        Some(month as u8 + 1)
+    }
+
+    // A weekday, abbreviated (e.g. Tue) or not, returns its index from Monday.
+    fn weekday(&mut self) -> Option<u8> {
+        let word = self.word();
+        let weekday = WEEKDAYS
+            .iter()
+            .position(|d| d.eq_ignore_ascii_case(word) || d[..3].eq_ignore_ascii_case(word))?;
+        Some(weekday as u8)
+    }
+
+    // HH:MM:SS
+    fn time(&mut self) -> Option<Time> {
+        let hour = self.number(2, 2)?;
+        self.byte(b':')?;
+        let minute = self.number(2, 2)?;
+        self.byte(b':')?;
+        let second = self.number(2, 2)?;
+        if hour > 23 || minute > 59 || second > 60 {
             return None;
         }
+        Some(Time {
+            hour: hour as u8,
+            minute: minute as u8,
+            second: second as u8,
+            nanosecond: 0,
+            precision: 0,
+        })
+    }
+
+    // +HHMM, -HHMM or the name of a zone, returns the offset in seconds.
+    fn zone(&mut self) -> Option<i32> {
+        let sign = match self.input.get(self.pos) {
+            Some(b'+') => 1,
+            Some(b'-') => -1,
+            _ => {
+                let word = self.word();
+                let (_, hours) = ZONES.iter().find(|(name, _)| *name == word)?;
+                return Some(hours * 3600);
+            }
+        };
+        self.pos += 1;
+        let zone = self.number(4, 4)? as i32;
+        if zone / 100 > 23 || zone % 100 > 59 {
+            return None;
+        }
+        Some(sign * (zone / 100 * 3600 + zone % 100 * 60))
+    }
+}
+
+impl Datetime {
+    /// Adds a format to search for.
+    pub fn add_format(&mut self, format: Format) {
+        self.formats.insert(format);
+    }
+
+    fn is_searched(&self, format: Format) -> bool {
+        if self.formats.is_empty() {
+            !matches!(format, Format::Epoch | Format::Duration)
+        } else {
+            self.formats.contains(&format)
+        }
+    }
+
+    // Parses the datetime written in the given format starting at the given position, returns it
+    // along with its end.
+    fn try_format(format: Format, input: &[u8], idx: usize) -> Option<(Timestamp, usize)> {
+        let (timestamp, end) = match format {
+            Format::Iso => Self::try_date(input, idx)?,
+            Format::Rfc2822 => Self::try_rfc2822(input, idx)?,
+            Format::Syslog => Self::try_syslog(input, idx)?,
+            Format::Clf => Self::try_clf(input, idx)?,
+            Format::Epoch => return Self::try_epoch(input, idx),
+            Format::Duration => return None,
+        };
+        if let Some(time) = timestamp.time {
+            if !Self::is_leap_second_valid(&time, timestamp.offset) {
+                return None;
+            }
+        }
+        Some((timestamp, end))
+    }
 
+    // Returns the end of the value of the given format starting at the given position, including
+    // the ones that are not timestamps.
+    fn try_match(format: Format, input: &[u8], idx: usize) -> Option<usize> {
+        match format {
+            Format::Iso => Self::try_interval(input, idx)
+                .map(|(_, end)| end)
+                .or_else(|| {
+                    let (_, end) = Self::try_format(format, input, idx)?;
+                    Self::is_iso_context(input, idx, end).then_some(end)
+                }),
+            Format::Duration => {
+                let (_, end) = Self::try_duration(input, idx)?;
+                Self::is_delimited(input, idx..end).then_some(end)
+            }
+            _ => Some(Self::try_format(format, input, idx)?.1),
+        }
+    }
+
+    // A leap second is inserted at the end of a UTC day, e.g. 23:59:60Z or 15:59:60-08:00
+    fn is_leap_second_valid(time: &Time, offset: Option<i32>) -> bool {
+        let minutes = time.hour as i32 * 60 + time.minute as i32 - offset.unwrap_or(0) / 60;
+        time.second != 60 || minutes.rem_euclid(24 * 60) == 23 * 60 + 59
+    }
+
+    // Whether the ISO date is a calendar one, or a week or ordinal one followed by a T time, the
+    // latter alone being likely numbers (e.g. 2024-123).
+    fn is_iso_context(input: &[u8], idx: usize, end: usize) -> bool {
+        let date_end = if input[idx + 5] == b'W' {
+            idx + 10
+        } else if input[idx + 7] != b'-' {
+            idx + 8
+        } else {
+            return true;
+        };
+        end > date_end && input[date_end] == b'T'
+    }
+
+    // Whether the datetime written with words is delimited.
+    fn is_delimited(input: &[u8], range: Range<usize>) -> bool {
+        (range.start == 0 || !input[range.start - 1].is_ascii_alphanumeric())
+            && (range.end == input.len() || !input[range.end].is_ascii_alphanumeric())
+    }
+
+    fn try_date(input: &[u8], idx: usize) -> Option<(Timestamp, usize)> {
         // Boundary before: not preceded by digit or dash
         if idx > 0 && (input[idx - 1].is_ascii_digit() || input[idx - 1] == b'-') {
             return None;
         }
 
-        // YYYY
-        if !Self::is_4_digits(input, idx) {
+        let (timestamp, end) = Self::try_iso(input, idx)?;
+
+        // Boundary after: not followed by digit or dash
+        if end < input.len() && (input[end].is_ascii_digit() || input[end] == b'-') {
             return None;
         }
-        let year = Self::parse_num(input, idx, 4)?;
-        if !(1000..=9999).contains(&year) {
+
+        Some((timestamp, end))
+    }
+
+    // [Tue, ]15 Nov 1994 08:12:31 GMT, Tuesday, 15-Nov-94 08:12:31 GMT or Tue Nov 15 08:12:31 1994
+    fn try_rfc2822(input: &[u8], idx: usize) -> Option<(Timestamp, usize)> {
+        let mut c = Cursor { input, pos: idx };
+        let weekday = if input[idx].is_ascii_alphabetic() {
+            Some(c.weekday()?)
+        } else {
+            // the dates following a wrong weekday are wrong as well
+            let before = input[..idx].trim_ascii_end();
+            if let Some(before) = before.strip_suffix(b",") {
+                let len = before
+                    .iter()
+                    .rev()
+                    .take_while(|b| b.is_ascii_alphabetic())
+                    .count();
+                let mut c = Cursor {
+                    input: before,
+                    pos: before.len() - len,
+                };
+                if len > 0 && c.weekday().is_some() {
+                    return None;
+                }
+            }
+            None
+        };
+
+        // asctime, the weekday not being followed by a comma
+        let asctime = weekday.is_some() && c.byte(b',').is_none();
+        let (date, time, offset) = if asctime {
+            c.spaces()?;
+            let month = c.month()?;
+            c.spaces()?;
+            let day = c.number(1, 2)?;
+            c.spaces()?;
+            let time = c.time()?;
+            c.spaces()?;
+            let year = c.number(4, 4)?;
+            (Date::new(year as i32, month, day as u8)?, time, 0)
+        } else {
+            if weekday.is_some() {
+                c.spaces()?;
+            }
+            let day = c.number(1, 2)?;
+            let (month, year) = if c.byte(b'-').is_some() {
+                // RFC 850, the two-digit years being of the last century from 70
+                let month = c.month()?;
+                c.byte(b'-')?;
+                let year = c.number(2, 2)?;
+                (month, if year < 70 { 2000 + year } else { 1900 + year })
+            } else {
+                c.spaces()?;
+                let month = c.month()?;
+                c.spaces()?;
+                (month, c.number(4, 4)?)
+            };
+            c.spaces()?;
+            let time = c.time()?;
+            c.spaces()?;
+            let offset = c.zone()?;
+            (Date::new(year as i32, month, day as u8)?, time, offset)
+        };
+
+        if weekday.is_some_and(|weekday| weekday != date.weekday()) {
+            return None;
+        }
+        if !Self::is_delimited(input, idx..c.pos) {
             return None;
         }
+        let timestamp = Timestamp {
+            date,
+            time: Some(time),
+            offset: Some(offset),
+        };
+        Some((timestamp, c.pos))
+    }
 
-        if input[idx + 4] != b'-' {
+    // Oct 11 22:14:15
+    fn try_syslog(input: &[u8], idx: usize) -> Option<(Timestamp, usize)> {
+        let mut c = Cursor { input, pos: idx };
+        let month = c.month()?;
+        c.spaces()?;
+        let day = c.number(1, 2)?;
+        c.spaces()?;
+        let time = c.time()?;
+
+        if !Self::is_delimited(input, idx..c.pos) {
             return None;
         }
+        let timestamp = Timestamp {
+            date: Date::new(current_year(), month, day as u8)?,
+            time: Some(time),
+            offset: None,
+        };
+        Some((timestamp, c.pos))
+    }
 
-        // MM
-        if !Self::is_2_digits(input, idx + 5) {
+    // 10/Oct/2000:13:55:36 -0700
+    fn try_clf(input: &[u8], idx: usize) -> Option<(Timestamp, usize)> {
+        let mut c = Cursor { input, pos: idx };
+        let day = c.number(2, 2)?;
+        c.byte(b'/')?;
+        let month = c.month()?;
+        c.byte(b'/')?;
+        let year = c.number(4, 4)?;
+        c.byte(b':')?;
+        let time = c.time()?;
+        c.byte(b' ')?;
+        if !matches!(input.get(c.pos), Some(b'+' | b'-')) {
             return None;
         }
-        let month = Self::parse_num(input, idx + 5, 2)?;
-        if !(1..=12).contains(&month) {
+        let offset = c.zone()?;
+
+        if !Self::is_delimited(input, idx..c.pos) {
             return None;
         }
+        let timestamp = Timestamp {
+            date: Date::new(year as i32, month, day as u8)?,
+            time: Some(time),
+            offset: Some(offset),
+        };
+        Some((timestamp, c.pos))
+    }
 
-        if input[idx + 7] != b'-' {
+    // 1700000000[.123] or 1700000000000
+    fn try_epoch(input: &[u8], idx: usize) -> Option<(Timestamp, usize)> {
+        if idx > 0 && (input[idx - 1].is_ascii_alphanumeric() || input[idx - 1] == b'.') {
+            return None;
+        }
+        let mut c = Cursor { input, pos: idx };
+        let digits = input[idx..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if input[idx] == b'0' {
             return None;
         }
 
-        // DD
-        if !Self::is_2_digits(input, idx + 8) {
+        let mut timestamp = match digits {
+            10 => {
+                let seconds = c.number(10, 10)?;
+                let mut timestamp = Timestamp::from_unix(seconds as i64);
+                if input.get(c.pos) == Some(&b'.')
+                    && input.get(c.pos + 1).is_some_and(u8::is_ascii_digit)
+                {
+                    c.pos += 1;
+                    let start = c.pos;
+                    let fraction = c.number(1, 9)?;
+                    let precision = (c.pos - start) as u32;
+                    if let Some(ref mut time) = timestamp.time {
+                        time.nanosecond = fraction * 10u32.pow(9 - precision);
+                        time.precision = precision as u8;
+                    }
+                }
+                timestamp
+            }
+            13 => {
+                let millis: i64 = std::str::from_utf8(&input[idx..idx + 13])
+                    .ok()?
+                    .parse()
+                    .ok()?;
+                c.pos += 13;
+                let mut timestamp = Timestamp::from_unix(millis.div_euclid(1000));
+                if let Some(ref mut time) = timestamp.time {
+                    time.nanosecond = millis.rem_euclid(1000) as u32 * 1_000_000;
+                    time.precision = 3;
+                }
+                timestamp
+            }
+            _ => return None,
+        };
+
+        // not followed by another number, e.g. a version
+        let next = &input[c.pos..];
+        if next.first().is_some_and(u8::is_ascii_alphanumeric)
+            || next.starts_with(b".") && next.get(1).is_some_and(u8::is_ascii_digit)
+        {
             return None;
         }
-        let day = Self::parse_num(input, idx + 8, 2)?;
-        if !(1..=31).contains(&day) {
+        timestamp.offset = Some(0);
+        Some((timestamp, c.pos))
+    }
+
+    // P1Y2M10DT2H30M or P3W
+    fn try_duration(input: &[u8], idx: usize) -> Option<(Duration, usize)> {
+        if input.get(idx) != Some(&b'P') {
             return None;
         }
+        let mut duration = Duration::default();
+        let mut pos = idx + 1;
+        for (time, designators) in [(false, "YMWD"), (true, "HMS")] {
+            if time {
+                if input.get(pos) != Some(&b'T') {
+                    break;
+                }
+                pos += 1;
+            }
+            let components = pos;
+            let mut next = 0;
+            loop {
+                let digits = input[pos..]
+                    .iter()
+                    .take_while(|b| b.is_ascii_digit())
+                    .count();
+                if digits == 0 {
+                    break;
+                }
+                let number = Self::parse_num(input, pos, digits)?;
+                pos += digits;
 
-        let mut end = idx + 10;
+                // only the seconds may have a fraction
+                let mut nanosecond = 0;
+                if time && matches!(input.get(pos), Some(b'.' | b',')) {
+                    let start = pos + 1;
+                    let len = input[start..]
+                        .iter()
+                        .take_while(|b| b.is_ascii_digit())
+                        .count();
+                    if len == 0 || input.get(start + len) != Some(&b'S') {
+                        return None;
+                    }
+                    let digits = &input[start..start + len.min(9)];
+                    nanosecond = Self::parse_num(digits, 0, digits.len())?
+                        * 10u32.pow(9 - digits.len() as u32);
+                    pos = start + len;
+                }
 
-        // Optional time component: T or space followed by HH:MM
-        if end < input.len() && (input[end] == b'T' || input[end] == b' ') {
-            if let Some(time_end) = Self::try_time(input, end + 1) {
-                // Only accept space separator if it's 'T' or if followed by valid time
-                if input[end] == b'T' || time_end > end + 1 {
-                    end = time_end;
+                let designator = *input.get(pos)? as char;
+                let i = next + designators[next..].find(designator)?;
+                let field = match (time, designator) {
+                    (false, 'Y') => &mut duration.years,
+                    (false, 'M') => &mut duration.months,
+                    (false, 'W') => &mut duration.weeks,
+                    (false, 'D') => &mut duration.days,
+                    (true, 'H') => &mut duration.hours,
+                    (true, 'M') => &mut duration.minutes,
+                    _ => &mut duration.seconds,
+                };
+                *field = number;
+                duration.nanosecond = nanosecond;
+                next = i + 1;
+                pos += 1;
+            }
+            // P and T are followed by a component at least, P being possibly followed by T
+            if pos == components && (time || input.get(pos) != Some(&b'T')) {
+                return None;
+            }
+        }
+        Some((duration, pos))
+    }
+
+    // 2024-01-01/2024-02-01, 2024-01-01/P1M or P1M/2024-02-01
+    fn try_interval(input: &[u8], idx: usize) -> Option<(Interval, usize)> {
+        let is_part = |b: &u8| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'/');
+        if idx > 0 && is_part(&input[idx - 1]) {
+            return None;
+        }
+
+        let (interval, end) = if let Some((start, end)) = Self::try_iso(input, idx) {
+            if input.get(end) != Some(&b'/') {
+                return None;
+            }
+            match Self::try_iso(input, end + 1) {
+                Some((until, end)) => (Interval::Range(start, until), end),
+                None => {
+                    let (duration, end) = Self::try_duration(input, end + 1)?;
+                    (Interval::Start(start, duration), end)
                 }
             }
+        } else {
+            let (duration, end) = Self::try_duration(input, idx)?;
+            if input.get(end) != Some(&b'/') {
+                return None;
+            }
+            let (until, end) = Self::try_iso(input, end + 1)?;
+            (Interval::End(duration, until), end)
+        };
+
+        if input.get(end).is_some_and(is_part) {
+            return None;
         }
+        Some((interval, end))
+    }
 
-        // Boundary after: not followed by digit or dash
-        if end < input.len() && (input[end].is_ascii_digit() || input[end] == b'-') {
+    // Parses the YYYY-MM-DD date with an optional time component starting at the given position,
+    // returns it along with its end.
+    fn try_iso(input: &[u8], idx: usize) -> Option<(Timestamp, usize)> {
+        if idx + 8 > input.len() {
+            return None;
+        }
+
+        // YYYY
+        if !Self::is_4_digits(input, idx) {
+            return None;
+        }
+        let year = Self::parse_num(input, idx, 4)?;
+        if !(1000..=9999).contains(&year) {
+            return None;
+        }
+
+        if input[idx + 4] != b'-' {
             return None;
         }
 
-        Some(idx..end)
+        let (date, mut end) = if input[idx + 5] == b'W' {
+            // Www-D, the week of the ISO week-numbering year and the day from Monday
+            if idx + 10 > input.len()
+                || !Self::is_2_digits(input, idx + 6)
+                || input[idx + 8] != b'-'
+                || !input[idx + 9].is_ascii_digit()
+            {
+                return None;
+            }
+            let week = Self::parse_num(input, idx + 6, 2)?;
+            let day = Self::parse_num(input, idx + 9, 1)?;
+            (
+                Date::from_week(year as i32, week as u8, day as u8)?,
+                idx + 10,
+            )
+        } else if input[idx + 7] != b'-' {
+            // DDD, the day of the year
+            if !Self::is_2_digits(input, idx + 5) || !input[idx + 7].is_ascii_digit() {
+                return None;
+            }
+            let day = Self::parse_num(input, idx + 5, 3)?;
+            (Date::from_ordinal(year as i32, day as u16)?, idx + 8)
+        } else {
+            // MM-DD, within the month
+            if !Self::is_2_digits(input, idx + 5) || !Self::is_2_digits(input, idx + 8) {
+                return None;
+            }
+            let month = Self::parse_num(input, idx + 5, 2)?;
+            let day = Self::parse_num(input, idx + 8, 2)?;
+            (Date::new(year as i32, month as u8, day as u8)?, idx + 10)
+        };
+
+        let mut timestamp = Timestamp {
+            date,
+            time: None,
+            offset: None,
+        };
+
+        // Optional time component: T or space followed by HH:MM
+        if end < input.len() && (input[end] == b'T' || input[end] == b' ') {
+            if let Some((time, offset, time_end)) = Self::try_time(input, end + 1) {
+                timestamp.time = Some(time);
+                timestamp.offset = offset;
+                end = time_end;
+            }
+        }
+
+        Some((timestamp, end))
     }
 
-    fn try_time(input: &[u8], idx: usize) -> Option<usize> {
+    fn try_time(input: &[u8], idx: usize) -> Option<(Time, Option<i32>, usize)> {
         // HH:MM[:SS[.fractional]]
         if idx + 5 > input.len() {
             return None;
@@ -97,12 +671,20 @@ impl Datetime {
             return None;
         }
 
+        let mut time = Time {
+            hour: hour as u8,
+            minute: minute as u8,
+            second: 0,
+            nanosecond: 0,
+            precision: 0,
+        };
         let mut end = idx + 5;
 
         // Optional :SS
         if end + 3 <= input.len() && input[end] == b':' && Self::is_2_digits(input, end + 1) {
             let second = Self::parse_num(input, end + 1, 2)?;
             if second <= 60 {
+                time.second = second as u8;
                 end += 3;
 
                 // Optional fractional seconds
@@ -113,6 +695,11 @@ impl Datetime {
                         frac_end += 1;
                     }
                     if frac_end > frac_start {
+                        // the digits beyond the nanoseconds are truncated
+                        let digits = &input[frac_start..frac_end.min(frac_start + 9)];
+                        let nanos = Self::parse_num(digits, 0, digits.len())?;
+                        time.nanosecond = nanos * 10u32.pow(9 - digits.len() as u32);
+                        time.precision = (frac_end - frac_start).min(u8::MAX as usize) as u8;
                         end = frac_end;
                     }
                 }
@@ -120,7 +707,9 @@ impl Datetime {
         }
 
         // Optional timezone: Z, +HH:MM, -HH:MM
+        let mut offset = None;
         if end < input.len() && input[end] == b'Z' {
+            offset = Some(0);
             end += 1;
         } else if end + 6 <= input.len()
             && (input[end] == b'+' || input[end] == b'-')
@@ -128,10 +717,20 @@ impl Datetime {
             && input[end + 3] == b':'
             && Self::is_2_digits(input, end + 4)
         {
-            end += 6;
+            let hours = Self::parse_num(input, end + 1, 2)? as i32;
+            let minutes = Self::parse_num(input, end + 4, 2)? as i32;
+            if hours <= 23 && minutes <= 59 {
+                let sign = if input[end] == b'-' { -1 } else { 1 };
+                offset = Some(sign * (hours * 3600 + minutes * 60));
+                end += 6;
+            }
         }
 
-        Some(end)
+        if !Self::is_leap_second_valid(&time, offset) {
+            return None;
+        }
+
+        Some((time, offset, end))
     }
 
     fn is_2_digits(input: &[u8], pos: usize) -> bool {
@@ -163,10 +762,15 @@ impl Finder for Datetime {
         let input = s.as_bytes();
         let mut idx = 0;
 
-        while idx + 10 <= input.len() {
-            if input[idx].is_ascii_digit() {
-                if let Some(range) = Self::try_date(input, idx) {
-                    return Some(range);
+        while idx < input.len() {
+            if input[idx].is_ascii_alphanumeric() {
+                for (_, format) in Format::NAMES {
+                    if !self.is_searched(*format) {
+                        continue;
+                    }
+                    if let Some(end) = Self::try_match(*format, input, idx) {
+                        return Some(idx..end);
+                    }
                 }
             }
             idx += 1;
@@ -176,6 +780,450 @@ impl Finder for Datetime {
     }
 }
 
+fn current_year() -> i32 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs() as i64);
+    Date::from_days_since_epoch(now.div_euclid(86400)).year
+}
+
+/// Returns whether the year is a leap year of the Gregorian calendar.
+pub fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Returns the number of days of the month (1-based) of the year.
+pub fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Returns the number of weeks (52 or 53) of the ISO week-numbering year.
+pub fn weeks_in_year(year: i32) -> u8 {
+    // the long years start on a Thursday, or on a Wednesday if they are leap years
+    let january_1 = Date {
+        year,
+        month: 1,
+        day: 1,
+    }
+    .weekday();
+    if january_1 == 3 || january_1 == 2 && is_leap_year(year) {
+        53
+    } else {
+        52
+    }
+}
+
+/// Parses a UTC offset (`Z`, `+02:00`, `-0500`, `+09` or the name of a zone of RFC 2822 like
+/// `UTC` or `EST`), returns it in seconds.
+pub fn parse_offset(s: &str) -> Option<i32> {
+    let sign = match s.as_bytes().first() {
+        Some(b'+') => 1,
+        Some(b'-') => -1,
+        _ => {
+            let (_, hours) = ZONES
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(s))?;
+            return Some(hours * 3600);
+        }
+    };
+    // the offsets are ASCII, and are sliced by bytes
+    if !s.is_ascii() {
+        return None;
+    }
+    let (hours, minutes) = match s[1..].split_once(':') {
+        Some((hours, minutes)) if minutes.len() == 2 => (hours, minutes),
+        Some(_) => return None,
+        None if s.len() == 5 => s[1..].split_at(2),
+        None => (&s[1..], "00"),
+    };
+    if hours.len() != 2 || !(hours.bytes().chain(minutes.bytes())).all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes): (i32, i32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+// Returns the name with its first letter uppercased, optionally abbreviated (e.g. Oct).
+fn title(name: &str, abbreviated: bool) -> String {
+    let name = if abbreviated { &name[..3] } else { name };
+    name[..1].to_ascii_uppercase() + &name[1..]
+}
+
+/// A date of the proleptic Gregorian calendar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    /// From 1 to 12.
+    pub month: u8,
+    /// From 1 to the number of days of the month.
+    pub day: u8,
+}
+
+impl Date {
+    /// Returns the date, if it exists (e.g. not `2023-02-29`).
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Date> {
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Date { year, month, day })
+    }
+
+    /// Returns the given day (from 1) of the year, if it exists (e.g. not `2023-366`).
+    pub fn from_ordinal(year: i32, day: u16) -> Option<Date> {
+        let days = if is_leap_year(year) { 366 } else { 365 };
+        if !(1..=days).contains(&day) {
+            return None;
+        }
+        let first = Date::new(year, 1, 1)?.days_since_epoch();
+        Some(Date::from_days_since_epoch(first + day as i64 - 1))
+    }
+
+    /// Returns the given day (from 1 for Monday to 7) of the week of the ISO week-numbering year,
+    /// if it exists (e.g. not `2024-W53-1`). The first week of a year is the one with its first
+    /// Thursday, so that its first days may be of the previous calendar year.
+    pub fn from_week(year: i32, week: u8, day: u8) -> Option<Date> {
+        if !(1..=weeks_in_year(year)).contains(&week) || !(1..=7).contains(&day) {
+            return None;
+        }
+        // the 4th of January is always in the first week
+        let january_4 = Date::new(year, 1, 4)?;
+        let monday = january_4.days_since_epoch() - january_4.weekday() as i64;
+        Some(Date::from_days_since_epoch(
+            monday + (week as i64 - 1) * 7 + day as i64 - 1,
+        ))
+    }
+
+    /// Returns the date the given number of days after the Unix epoch (1970-01-01).
+    pub fn from_days_since_epoch(days: i64) -> Date {
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        Date {
+            year: year as i32,
+            month: month as u8,
+            day: day as u8,
+        }
+    }
+
+    /// Returns the day of the week, from 0 for Monday to 6 for Sunday.
+    pub fn weekday(&self) -> u8 {
+        // 1970-01-01 was a Thursday
+        (self.days_since_epoch() + 3).rem_euclid(7) as u8
+    }
+
+    /// Returns the number of days since the Unix epoch (1970-01-01), negative before it.
+    pub fn days_since_epoch(&self) -> i64 {
+        // http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let year = self.year as i64 - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let month = self.month as i64;
+        let doy =
+            (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+}
+
+/// A time of day.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+    /// From 0 to 23.
+    pub hour: u8,
+    /// From 0 to 59.
+    pub minute: u8,
+    /// From 0 to 60, 60 being a leap second.
+    pub second: u8,
+    pub nanosecond: u32,
+    /// The number of fractional digits written, e.g. 3 for `10:30:00.120`.
+    pub precision: u8,
+}
+
+/// A duration of ISO 8601 (e.g. `P1Y2M10DT2H30M`), as found by the [`Datetime`] finder.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Duration {
+    pub years: u32,
+    pub months: u32,
+    pub weeks: u32,
+    pub days: u32,
+    pub hours: u32,
+    pub minutes: u32,
+    pub seconds: u32,
+    /// The fraction of the seconds (e.g. 500000000 for `PT1.5S`).
+    pub nanosecond: u32,
+}
+
+impl Duration {
+    /// Parses a duration of ISO 8601, its components being in order (e.g. not `PT30S10M`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use squeeze::datetime::Duration;
+    ///
+    /// assert_eq!(Some(1800), Duration::parse("PT30M").and_then(|d| d.seconds()));
+    /// assert_eq!(Some(2), Duration::parse("P1Y2M").map(|d| d.months));
+    /// ```
+    pub fn parse(s: &str) -> Option<Duration> {
+        match Datetime::try_duration(s.as_bytes(), 0)? {
+            (duration, end) if end == s.len() => Some(duration),
+            _ => None,
+        }
+    }
+
+    /// Returns the duration in seconds, rounded down, the days being of 24 hours. The years and
+    /// months having no fixed length, the durations with any have no number of seconds.
+    pub fn seconds(&self) -> Option<i64> {
+        if self.years > 0 || self.months > 0 {
+            return None;
+        }
+        let days = self.weeks as i64 * 7 + self.days as i64;
+        Some(
+            days * 86400
+                + self.hours as i64 * 3600
+                + self.minutes as i64 * 60
+                + self.seconds as i64,
+        )
+    }
+}
+
+/// A time interval of ISO 8601, as found by the [`Datetime`] finder.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Interval {
+    /// From a timestamp to another (e.g. `2024-01-01/2024-02-01`).
+    Range(Timestamp, Timestamp),
+    /// From a timestamp, lasting a duration (e.g. `2024-01-01/P1M`).
+    Start(Timestamp, Duration),
+    /// Lasting a duration, until a timestamp (e.g. `P1M/2024-02-01`).
+    End(Duration, Timestamp),
+}
+
+impl Interval {
+    /// Parses a time interval of ISO 8601, its timestamps being written in full.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use squeeze::datetime::{Duration, Interval, Timestamp};
+    ///
+    /// assert_eq!(
+    ///     Some(Interval::Start(
+    ///         Timestamp::parse("2024-01-01").unwrap(),
+    ///         Duration::parse("P1M").unwrap()
+    ///     )),
+    ///     Interval::parse("2024-01-01/P1M")
+    /// );
+    /// ```
+    pub fn parse(s: &str) -> Option<Interval> {
+        match Datetime::try_interval(s.as_bytes(), 0)? {
+            (interval, end) if end == s.len() => Some(interval),
+            _ => None,
+        }
+    }
+}
+
+/// A date, optionally with a time and a UTC offset, as found by the [`Datetime`] finder.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Timestamp {
+    pub date: Date,
+    pub time: Option<Time>,
+    /// The offset from UTC in seconds, positive east of Greenwich, unknown for the local times.
+    pub offset: Option<i32>,
+}
+
+impl Timestamp {
+    /// Parses a date or datetime as found by the [`Datetime`] finder, in any of its formats,
+    /// validated against the calendar (e.g. not `2023-02-31`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use squeeze::datetime::Timestamp;
+    ///
+    /// let timestamp = Timestamp::parse("2024-02-29T10:30:00.250+01:00").unwrap();
+    /// assert_eq!(29, timestamp.date.day);
+    /// assert_eq!(Some(3600), timestamp.offset);
+    /// assert_eq!(Some(1709199000), timestamp.unix());
+    /// assert!(Timestamp::parse("2023-02-29").is_none());
+    /// ```
+    pub fn parse(s: &str) -> Option<Timestamp> {
+        if s.is_empty() {
+            return None;
+        }
+        Format::NAMES.iter().find_map(|(_, format)| {
+            match Datetime::try_format(*format, s.as_bytes(), 0)? {
+                (timestamp, end) if end == s.len() => Some(timestamp),
+                _ => None,
+            }
+        })
+    }
+
+    /// Returns the UTC timestamp of the given number of seconds since the Unix epoch.
+    pub fn from_unix(seconds: i64) -> Timestamp {
+        let time = seconds.rem_euclid(86400);
+        Timestamp {
+            date: Date::from_days_since_epoch(seconds.div_euclid(86400)),
+            time: Some(Time {
+                hour: (time / 3600) as u8,
+                minute: (time / 60 % 60) as u8,
+                second: (time % 60) as u8,
+                nanosecond: 0,
+                precision: 0,
+            }),
+            offset: Some(0),
+        }
+    }
+
+    /// Returns the timestamp, the given offset being assumed if it has none.
+    pub fn assume_offset(self, offset: i32) -> Timestamp {
+        Timestamp {
+            offset: self.offset.or(Some(offset)),
+            ..self
+        }
+    }
+
+    /// Returns the number of seconds since the Unix epoch, a date alone being at midnight. The
+    /// local times have no Unix time, see [`Timestamp::assume_offset`].
+    pub fn unix(&self) -> Option<i64> {
+        let time = self.time.map_or(0, |t| {
+            t.hour as i64 * 3600 + t.minute as i64 * 60 + t.second as i64
+        });
+        Some(self.date.days_since_epoch() * 86400 + time - self.offset? as i64)
+    }
+
+    /// Returns the same instant in UTC, a date alone being at midnight, or `None` for the local
+    /// times (see [`Timestamp::assume_offset`]).
+    pub fn to_utc(&self) -> Option<Timestamp> {
+        let time = self.time.unwrap_or_default();
+        // the leap seconds have no Unix time of their own
+        let leap = time.second == 60;
+        let mut utc = Timestamp::from_unix(self.unix()? - i64::from(leap));
+        if let Some(ref mut t) = utc.time {
+            t.second += u8::from(leap);
+            t.nanosecond = time.nanosecond;
+            t.precision = time.precision;
+        }
+        Some(utc)
+    }
+
+    /// Returns the timestamp in the RFC 3339 format (e.g. `2024-01-15T10:30:00.250+01:00`), the
+    /// fractional seconds being written with their original precision.
+    pub fn to_rfc3339(&self) -> String {
+        let mut s = format!(
+            "{:04}-{:02}-{:02}",
+            self.date.year, self.date.month, self.date.day
+        );
+        let Some(time) = self.time else {
+            return s;
+        };
+        s += &format!("T{:02}:{:02}:{:02}", time.hour, time.minute, time.second);
+        if time.precision > 0 {
+            let digits = format!("{:09}", time.nanosecond);
+            s += ".";
+            s += &digits[..(time.precision as usize).min(9)];
+        }
+        match self.offset {
+            Some(0) => s += "Z",
+            Some(offset) => s += &Self::format_offset(offset, ":"),
+            None => {}
+        }
+        s
+    }
+
+    /// Formats the timestamp with the `strftime` conversion specifications: `%Y`, `%C`, `%y`,
+    /// `%m`, `%d`, `%e`, `%j`, `%H`, `%I`, `%M`, `%S`, `%f` (the nanoseconds), `%p`, `%a`, `%A`,
+    /// `%b`, `%B`, `%u`, `%z`, `%:z`, `%s`, `%F`, `%T`, `%D` and `%%`. Returns `None` for an
+    /// unknown specification, or for `%z`, `%:z` and `%s` if the timestamp has no offset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use squeeze::datetime::Timestamp;
+    ///
+    /// let timestamp = Timestamp::parse("Tue, 15 Nov 1994 08:12:31 -0500").unwrap();
+    /// assert_eq!(
+    ///     Some("15/Nov/1994:08:12:31 -0500".to_string()),
+    ///     timestamp.strftime("%d/%b/%Y:%T %z")
+    /// );
+    /// ```
+    pub fn strftime(&self, pattern: &str) -> Option<String> {
+        let date = self.date;
+        let time = self.time.unwrap_or_default();
+        let mut s = String::new();
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                s.push(c);
+                continue;
+            }
+            s += &match chars.next()? {
+                'Y' if (0..=9999).contains(&date.year) => format!("{:04}", date.year),
+                'Y' => date.year.to_string(),
+                'C' => format!("{:02}", date.year.div_euclid(100)),
+                'y' => format!("{:02}", date.year.rem_euclid(100)),
+                'm' => format!("{:02}", date.month),
+                'd' => format!("{:02}", date.day),
+                'e' => format!("{:2}", date.day),
+                'j' => {
+                    let first = Date::new(date.year, 1, 1)?;
+                    format!(
+                        "{:03}",
+                        date.days_since_epoch() - first.days_since_epoch() + 1
+                    )
+                }
+                'H' => format!("{:02}", time.hour),
+                'I' => format!("{:02}", (time.hour + 11) % 12 + 1),
+                'M' => format!("{:02}", time.minute),
+                'S' => format!("{:02}", time.second),
+                'f' => format!("{:09}", time.nanosecond),
+                'p' => (if time.hour < 12 { "AM" } else { "PM" }).to_string(),
+                'a' => title(WEEKDAYS[date.weekday() as usize], true),
+                'A' => title(WEEKDAYS[date.weekday() as usize], false),
+                'b' => title(MONTHS[date.month as usize - 1], true),
+                'B' => title(MONTHS[date.month as usize - 1], false),
+                'u' => (date.weekday() + 1).to_string(),
+                'z' => Self::format_offset(self.offset?, ""),
+                ':' if chars.next()? == 'z' => Self::format_offset(self.offset?, ":"),
+                's' => self.unix()?.to_string(),
+                'F' => self.strftime("%Y-%m-%d")?,
+                'T' => self.strftime("%H:%M:%S")?,
+                'D' => self.strftime("%m/%d/%y")?,
+                '%' => "%".to_string(),
+                _ => return None,
+            };
+        }
+        Some(s)
+    }
+
+    // +HH:MM or -HHMM, with the given separator
+    fn format_offset(offset: i32, separator: &str) -> String {
+        let sign = if offset < 0 { '-' } else { '+' };
+        let minutes = offset.abs() / 60;
+        format!(
+            "{}{:02}{}{:02}",
+            sign,
+            minutes / 60,
+            separator,
+            minutes % 60
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -361,4 +1409,449 @@ mod tests {
         let range = finder.find(input).unwrap();
         assert_eq!("2024-01-15 10:30:00", &input[range]);
     }
+
+    #[test]
+    fn find_should_validate_the_calendar() {
+        let finder = Datetime::default();
+        for input in [
+            "2023-02-29",
+            "2023-02-31",
+            "2024-04-31",
+            "2100-02-29",
+            "2024-06-31",
+        ] {
+            assert!(finder.find(input).is_none(), "{}", input);
+        }
+        for input in ["2024-02-29", "2000-02-29", "2023-01-31", "2023-12-31"] {
+            assert_eq!(Some(0..10), finder.find(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn find_should_only_accept_leap_seconds_at_the_end_of_utc_days() {
+        let finder = Datetime::default();
+        for (input, expected) in [
+            ("2016-12-31T23:59:60Z", "2016-12-31T23:59:60Z"),
+            ("2016-12-31T15:59:60-08:00", "2016-12-31T15:59:60-08:00"),
+            ("2016-12-31T23:59:60", "2016-12-31T23:59:60"),
+            ("2016-12-31T12:30:60Z", "2016-12-31"),
+            ("2016-12-31T23:59:60+01:00", "2016-12-31"),
+            // the offset is not consumed
+            ("2024-01-15T10:30:00+24:00", "2024-01-15T10:30:00"),
+        ] {
+            let range = finder.find(input).unwrap();
+            assert_eq!(expected, &input[range], "{}", input);
+        }
+    }
+
+    #[test]
+    fn timestamp_parse_should_return_typed_values() {
+        assert_eq!(
+            Some(Timestamp {
+                date: Date {
+                    year: 2024,
+                    month: 1,
+                    day: 15
+                },
+                time: Some(Time {
+                    hour: 10,
+                    minute: 30,
+                    second: 5,
+                    nanosecond: 120_000_000,
+                    precision: 3,
+                }),
+                offset: Some(-(5 * 3600 + 30 * 60)),
+            }),
+            Timestamp::parse("2024-01-15T10:30:05.120-05:30")
+        );
+        assert_eq!(
+            Some(Timestamp {
+                date: Date {
+                    year: 2024,
+                    month: 1,
+                    day: 15
+                },
+                time: None,
+                offset: None,
+            }),
+            Timestamp::parse("2024-01-15")
+        );
+        let time = Timestamp::parse("2024-01-15 10:30:00.1234567891")
+            .unwrap()
+            .time;
+        assert_eq!(
+            Some((123_456_789, 10)),
+            time.map(|t| (t.nanosecond, t.precision))
+        );
+        for input in [
+            "",
+            "2024-01-15T",
+            "2024-01-15 foo",
+            "x2024-01-15",
+            "2023-02-29",
+        ] {
+            assert_eq!(None, Timestamp::parse(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn timestamp_unix_should_return_the_seconds_since_the_epoch() {
+        for (input, expected) in [
+            ("1970-01-01T00:00:00Z", Some(0)),
+            ("1970-01-01", None),
+            ("2000-03-01T00:00:00Z", Some(951868800)),
+            ("2024-02-29T10:30:00+01:00", Some(1709199000)),
+            ("1969-12-31T23:59:59Z", Some(-1)),
+            ("1900-01-01T00:00:00Z", Some(-2208988800)),
+            ("2016-12-31T23:59:60Z", Some(1483228800)),
+            ("9999-12-31T23:59:59Z", Some(253402300799)),
+        ] {
+            assert_eq!(
+                expected,
+                Timestamp::parse(input).unwrap().unix(),
+                "{}",
+                input
+            );
+        }
+        let timestamp = Timestamp::parse("1970-01-02").unwrap();
+        assert_eq!(Some(86400 - 3600), timestamp.assume_offset(3600).unix());
+        let timestamp = Timestamp::parse("1970-01-01T00:00:00Z").unwrap();
+        assert_eq!(Some(0), timestamp.assume_offset(3600).unix());
+    }
+
+    #[test]
+    fn date_days_since_epoch_should_count_the_leap_years() {
+        for (year, month, day, expected) in [
+            (1970, 1, 1, 0),
+            (1972, 3, 1, 790),
+            (1600, 2, 29, -135081),
+            (2024, 12, 31, 20088),
+        ] {
+            let date = Date::new(year, month, day).unwrap();
+            assert_eq!(expected, date.days_since_epoch(), "{:?}", date);
+        }
+        assert!(is_leap_year(2000));
+        assert!(!is_leap_year(1900));
+        assert_eq!(29, days_in_month(2024, 2));
+        assert_eq!(28, days_in_month(2023, 2));
+    }
+
+    #[test]
+    fn find_should_extract_the_formats_of_logs_and_emails() {
+        let finder = Datetime::default();
+        for (input, expected) in [
+            (
+                "Date: Tue, 15 Nov 1994 08:12:31 GMT",
+                "Tue, 15 Nov 1994 08:12:31 GMT",
+            ),
+            (
+                "Date: 2 Jan 2006 15:04:05 -0700\r",
+                "2 Jan 2006 15:04:05 -0700",
+            ),
+            (
+                "Expires: Sunday, 06-Nov-94 08:49:37 GMT",
+                "Sunday, 06-Nov-94 08:49:37 GMT",
+            ),
+            ("Sun Nov  6 08:49:37 1994", "Sun Nov  6 08:49:37 1994"),
+            (
+                "Oct 11 22:14:15 mymachine su: 'su root' failed",
+                "Oct 11 22:14:15",
+            ),
+            ("Oct  1 02:04:05 host", "Oct  1 02:04:05"),
+            (
+                r#"127.0.0.1 - - [10/Oct/2000:13:55:36 -0700] "GET / HTTP/1.0""#,
+                "10/Oct/2000:13:55:36 -0700",
+            ),
+        ] {
+            let range = finder.find(input).unwrap();
+            assert_eq!(expected, &input[range], "{}", input);
+        }
+    }
+
+    #[test]
+    fn find_should_reject_invalid_formats_of_logs_and_emails() {
+        let finder = Datetime::default();
+        for input in [
+            // Wednesday
+            "Tue, 16 Nov 1994 08:12:31 GMT",
+            "Tue, 31 Nov 1994 08:12:31 GMT",
+            "15 Nov 1994 08:12:31 XYZ",
+            "Octopus 11 22:14:15",
+            "Oct 11 24:14:15",
+            "10/Oct/2000:13:55:36",
+            "10/Foo/2000:13:55:36 -0700",
+            "1700000000",
+        ] {
+            assert_eq!(None, finder.find(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn find_should_only_search_the_added_formats() {
+        let mut finder = Datetime::default();
+        finder.add_format(Format::Epoch);
+        for (input, expected) in [
+            ("\"ts\":1700000000,", Some("1700000000")),
+            ("\"ts\":1700000000.123}", Some("1700000000.123")),
+            ("\"ts\":1700000000123}", Some("1700000000123")),
+            ("ends with 1700000000.", Some("1700000000")),
+            ("2024-01-15", None),
+            ("170000000", None),
+            ("17000000000", None),
+            ("0700000000", None),
+            ("v1700000000", None),
+            ("1.1700000000", None),
+        ] {
+            assert_eq!(
+                expected,
+                finder.find(input).map(|range| &input[range]),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn format_from_name_should_be_case_insensitive() {
+        assert_eq!(Some(Format::Rfc2822), Format::from_name("RFC2822"));
+        assert_eq!(Some(Format::Clf), Format::from_name("clf"));
+        assert_eq!(None, Format::from_name("unknown"));
+        assert_eq!("syslog", Format::Syslog.name());
+    }
+
+    #[test]
+    fn timestamp_parse_should_return_the_same_value_for_all_the_formats() {
+        for input in [
+            "1994-11-06T08:49:37Z",
+            "1994-11-06T00:49:37-08:00",
+            "Sun, 06 Nov 1994 08:49:37 GMT",
+            "Sun, 6 Nov 1994 03:49:37 EST",
+            "Sunday, 06-Nov-94 08:49:37 GMT",
+            "Sun Nov  6 08:49:37 1994",
+            "06/Nov/1994:09:49:37 +0100",
+        ] {
+            assert_eq!(
+                Some(784111777),
+                Timestamp::parse(input).and_then(|t| t.unix()),
+                "{}",
+                input
+            );
+        }
+        let timestamp = Timestamp::parse("1700000000123").unwrap();
+        assert_eq!(Some(1700000000), timestamp.unix());
+        assert_eq!(
+            Some((123_000_000, 3)),
+            timestamp.time.map(|t| (t.nanosecond, t.precision))
+        );
+        let timestamp = Timestamp::parse("Oct 11 22:14:15").unwrap();
+        assert_eq!(
+            (10, 11, None),
+            (timestamp.date.month, timestamp.date.day, timestamp.offset)
+        );
+    }
+
+    #[test]
+    fn date_from_days_since_epoch_should_be_the_inverse_of_days_since_epoch() {
+        for days in [-719468, -135081, -1, 0, 59, 790, 11016, 20088, 2932896] {
+            assert_eq!(days, Date::from_days_since_epoch(days).days_since_epoch());
+        }
+        assert_eq!(3, Date::new(1970, 1, 1).unwrap().weekday());
+        assert_eq!(6, Date::new(1994, 11, 6).unwrap().weekday());
+    }
+
+    #[test]
+    fn parse_offset_should_accept_the_offsets_and_the_zone_names() {
+        for (input, expected) in [
+            ("Z", Some(0)),
+            ("utc", Some(0)),
+            ("EST", Some(-5 * 3600)),
+            ("+02:00", Some(7200)),
+            ("-0530", Some(-19800)),
+            ("+09", Some(32400)),
+            ("+24:00", None),
+            ("+02:0", None),
+            ("+2", None),
+            ("Europe/Paris", None),
+            ("+1é2", None),
+            ("-é", None),
+            ("+0é", None),
+        ] {
+            assert_eq!(expected, parse_offset(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn to_utc_should_keep_the_instant() {
+        for (input, expected) in [
+            (
+                "2024-01-15T10:30:00.250+01:00",
+                Some("2024-01-15T09:30:00.250Z"),
+            ),
+            ("2024-01-15", None),
+            ("Sun, 6 Nov 1994 23:49:37 EST", Some("1994-11-07T04:49:37Z")),
+            ("2016-12-31T15:59:60-08:00", Some("2016-12-31T23:59:60Z")),
+            ("1700000000123", Some("2023-11-14T22:13:20.123Z")),
+        ] {
+            let timestamp = Timestamp::parse(input).unwrap();
+            assert_eq!(
+                expected,
+                timestamp.to_utc().map(|t| t.to_rfc3339()).as_deref(),
+                "{}",
+                input
+            );
+        }
+        let timestamp = Timestamp::parse("2024-01-15").unwrap().assume_offset(3600);
+        assert_eq!(
+            "2024-01-14T23:00:00Z",
+            timestamp.to_utc().unwrap().to_rfc3339()
+        );
+    }
+
+    #[test]
+    fn strftime_should_format_the_specifications() {
+        let timestamp = Timestamp::parse("2024-02-29T15:04:05.5-07:00").unwrap();
+        for (pattern, expected) in [
+            ("%F %T", Some("2024-02-29 15:04:05")),
+            ("%a %A %b %B %u", Some("Thu Thursday Feb February 4")),
+            ("%C %y %j %e %D", Some("20 24 060 29 02/29/24")),
+            ("%I:%M %p", Some("03:04 PM")),
+            ("%S.%f", Some("05.500000000")),
+            ("%z %:z %s", Some("-0700 -07:00 1709244245")),
+            ("100%%", Some("100%")),
+            ("%Q", None),
+            ("%", None),
+        ] {
+            assert_eq!(
+                expected,
+                timestamp.strftime(pattern).as_deref(),
+                "{}",
+                pattern
+            );
+        }
+        let timestamp = Timestamp::parse("Oct 11 22:14:15").unwrap();
+        assert_eq!(None, timestamp.strftime("%s"));
+    }
+
+    #[test]
+    fn find_should_extract_week_and_ordinal_dates() {
+        let finder = Datetime::default();
+        for (input, expected) in [
+            ("week 2024-W03-2T09:00 starts", Some("2024-W03-2T09:00")),
+            ("on 2024-015T10:00Z", Some("2024-015T10:00Z")),
+            ("2020-W53-7T00:00", Some("2020-W53-7T00:00")),
+            ("2024-W03-2/P2W", Some("2024-W03-2/P2W")),
+            ("2021-W53-1T00:00", None),
+            ("2024-W03-8T00:00", None),
+            ("2024-366T00:00 2023-366T00:00", Some("2024-366T00:00")),
+            ("2023-366T00:00", None),
+            ("2024-0151T00:00", None),
+            ("week 2024-W03-2 starts", None),
+            ("ticket 2024-123", None),
+            ("1999-001 10:00", None),
+            ("2024-015T", None),
+        ] {
+            assert_eq!(
+                expected,
+                finder.find(input).map(|range| &input[range]),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn date_from_week_should_follow_the_iso_week_numbering_years() {
+        for ((year, week, day), expected) in [
+            ((2024, 3, 2), (2024, 1, 16)),
+            ((2020, 1, 1), (2019, 12, 30)),
+            ((2020, 53, 7), (2021, 1, 3)),
+            ((2026, 1, 4), (2026, 1, 1)),
+        ] {
+            let date = Date::from_week(year, week, day).unwrap();
+            assert_eq!(expected, (date.year, date.month, date.day), "{}", year);
+        }
+        assert_eq!(53, weeks_in_year(2015));
+        assert_eq!(52, weeks_in_year(2023));
+        assert_eq!(Date::new(2024, 12, 31), Date::from_ordinal(2024, 366));
+    }
+
+    #[test]
+    fn find_should_extract_durations_and_intervals() {
+        let mut finder = Datetime::default();
+        finder.add_format(Format::Iso);
+        finder.add_format(Format::Duration);
+        for (input, expected) in [
+            ("timeout: PT30M", Some("PT30M")),
+            ("retention=P1Y2M", Some("P1Y2M")),
+            ("P3W or P1DT12H", Some("P3W")),
+            ("PT1.5S", Some("PT1.5S")),
+            ("2024-01-01/2024-02-01", Some("2024-01-01/2024-02-01")),
+            ("2024-01-01T00:00Z/P1M", Some("2024-01-01T00:00Z/P1M")),
+            ("P1M/2024-02-01", Some("P1M/2024-02-01")),
+            ("P1Y/P1M", Some("P1Y")),
+            ("P", None),
+            ("PT", None),
+            ("P1DT", None),
+            ("PT30S10M", None),
+            ("P1.5D", None),
+            ("MP3M", None),
+            ("P2P", None),
+        ] {
+            assert_eq!(
+                expected,
+                finder.find(input).map(|range| &input[range]),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn find_should_only_extract_durations_given_their_format() {
+        let mut finder = Datetime::default();
+        assert_eq!(None, finder.find("P2D"));
+        assert_eq!(Some(4..18), finder.find("for P1M/2024-02-01"));
+        finder.add_format(Format::Epoch);
+        assert_eq!(None, finder.find("PT30M"));
+        finder.add_format(Format::Duration);
+        assert_eq!(Some(0..5), finder.find("PT30M"));
+    }
+
+    #[test]
+    fn duration_seconds_should_only_be_returned_for_fixed_lengths() {
+        for (input, expected) in [
+            ("PT30M", Some(1800)),
+            ("P1W2D", Some(9 * 86400)),
+            ("P1DT2H3M4.5S", Some(93784)),
+            ("P1Y2M", None),
+            ("P0Y1D", Some(86400)),
+        ] {
+            assert_eq!(
+                expected,
+                Duration::parse(input).and_then(|d| d.seconds()),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(
+            Some(500_000_000),
+            Duration::parse("PT1,5S").map(|d| d.nanosecond)
+        );
+    }
+
+    #[test]
+    fn interval_parse_should_return_its_parts() {
+        let start = Timestamp::parse("2024-01-01").unwrap();
+        let end = Timestamp::parse("2024-W05-4").unwrap();
+        assert_eq!(
+            Some(Interval::Range(start, end)),
+            Interval::parse("2024-01-01/2024-W05-4")
+        );
+        assert_eq!(
+            Some(Interval::End(Duration::parse("PT1H").unwrap(), start)),
+            Interval::parse("PT1H/2024-01-01")
+        );
+        assert_eq!(None, Interval::parse("P1D/P2D"));
+        assert_eq!(None, Interval::parse("2024-01-01"));
+    }
 }
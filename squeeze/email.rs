@@ -1,8 +1,54 @@
+use super::rules::RuleError;
 use super::Finder;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::ops::Range;
+use std::sync::OnceLock;
 
+const DEFAULT_RULES: &str = include_str!("email-rules.txt");
+
+// The words spelling `@` and `.` in the obfuscated addresses, the localized dots being only
+// recognized along with the localized `@` to keep the false positives low.
+const OBFUSCATIONS: &[(&str, &str)] = &[
+    ("at", "dot"),
+    // French
+    ("chez", "point"),
+    // Spanish, Portuguese
+    ("arroba", "punto|ponto"),
+];
+
+// The generic TLDs of the spelled addresses, the two-letter country codes being all accepted.
+const TLDS: &str = "com org net edu gov mil int info biz name pro mobi app dev tech \
+    online site xyz email mail cloud shop store blog news club live";
+
+// The words of sentences which are unlikely to be a local part or a domain label of an address
+// with a spelled `@` (e.g. `I am at home dot com`).
+const PROSE_WORDS: &str = "a am an and are as be been being but by do does did for from get \
+    got go goes going went had has have he her here him his home i if in is it its me my no not \
+    of on or our out she so that the their them then there these they this those to too up us \
+    was we were what when where which who will with work you your \
+    je tu il elle nous vous ils elles suis est sont moi toi lui le la les un une des du de et \
+    yo el ella nosotros ellos estoy esta estamos eu ele ela estou casa";
+
+/// Finds the email addresses (e.g. `john@example.com`), and optionally the mailboxes as written in
+/// the mail headers, see [`parse_addresses`] to parse them.
 #[derive(Default)]
-pub struct Email {}
+pub struct Email {
+    /// Whether to find the mailboxes as defined by
+    /// [RFC 5322](https://www.rfc-editor.org/rfc/rfc5322#section-3.4), along with their display
+    /// name (`"John Smith" <john@example.com>`) or comment (`john@example.com (John Smith)`), and
+    /// the addresses with a quoted local part (`"odd local"@example.com`) or an address literal
+    /// (`john@[192.0.2.1]`).
+    pub rfc5322: bool,
+    /// Whether to also find the obfuscated addresses (`john at example dot com`,
+    /// `john[at]example[dot]com` or `john (at) example.com`), see [`deobfuscate`] to get their
+    /// address. The `@` written as a word between spaces must be followed by a domain whose dots
+    /// are written as words as well and which ends with a TLD, and none of the words may be a
+    /// common one of sentences, `john at example.com` or `I am at home dot com` being more likely
+    /// to be prose.
+    pub obfuscated: bool,
+}
 
 impl Email {
     fn is_local_char(b: u8) -> bool {
@@ -30,6 +76,95 @@ impl Email {
                     | b'-'
             )
     }
+
+    // Returns the end of the domain name starting at the given position, its labels being
+    // internationalized if asked to.
+    fn domain_end(s: &str, domain_start: usize, unicode: bool) -> Option<usize> {
+        let is_label_char =
+            |c: char| c.is_ascii_alphanumeric() || c == '-' || unicode && c.is_alphanumeric();
+
+        // Walk forwards for domain
+        let rest = &s[domain_start..];
+        let mut domain_end = domain_start
+            + rest
+                .find(|c| !is_label_char(c) && c != '.')
+                .unwrap_or(rest.len());
+
+        // Strip trailing dots/hyphens
+        while domain_end > domain_start && matches!(s.as_bytes()[domain_end - 1], b'.' | b'-') {
+            domain_end -= 1;
+        }
+
+        let domain = &s[domain_start..domain_end];
+
+        // Must have at least one dot
+        if !domain.contains('.') {
+            return None;
+        }
+
+        // Validate each label
+        let valid = domain.split('.').all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(is_label_char)
+        });
+
+        if !valid {
+            return None;
+        }
+
+        // TLD must be >= 2 chars and all alpha, either ASCII or internationalized (e.g. .рф)
+        let tld = domain.rsplit('.').next().unwrap();
+        if tld.chars().count() < 2
+            || !(tld.chars().all(|c| c.is_ascii_alphabetic())
+                || tld.chars().all(|c| !c.is_ascii() && c.is_alphabetic()))
+        {
+            return None;
+        }
+
+        Some(domain_end)
+    }
+
+    // Returns the end of the address literal (`[192.0.2.1]`) starting at the given position.
+    fn literal_end(input: &[u8], start: usize) -> Option<usize> {
+        if input.get(start) != Some(&b'[') {
+            return None;
+        }
+        let len = input[start + 1..]
+            .iter()
+            .position(|&b| !b.is_ascii_graphic() || matches!(b, b'[' | b']' | b'\\'))?;
+        (len > 0 && input[start + 1 + len] == b']').then_some(start + len + 2)
+    }
+
+    // Returns the mailbox around the address at the given range: the display name before it and
+    // the angle brackets around it, and the comments following it.
+    fn mailbox(s: &str, address: Range<usize>) -> Range<usize> {
+        let mut tokens: Vec<_> = Lexer::new(&s[..address.start]).collect();
+        let mut start = address.start;
+        let mut end = address.end;
+
+        if let (Some((open, Token::Special('<'))), Some((close, Token::Special('>')))) =
+            (tokens.pop(), Lexer::new(s).starting_at(end).next())
+        {
+            start = open.start;
+            end = close.end;
+            while let Some((range, Token::Atom(_) | Token::Quoted(_) | Token::Comment(_))) =
+                tokens.pop()
+            {
+                start = range.start;
+            }
+        }
+
+        for (range, token) in Lexer::new(s).starting_at(end) {
+            if !matches!(token, Token::Comment(_)) {
+                break;
+            }
+            end = range.end;
+        }
+
+        start..end
+    }
 }
 
 impl Finder for Email {
@@ -38,12 +173,38 @@ impl Finder for Email {
     }
 
     fn find(&self, s: &str) -> Option<Range<usize>> {
+        let found = self.find_address(s);
+        if !self.obfuscated {
+            return found;
+        }
+        match (found, find_obfuscated(s)) {
+            (Some(found), Some((obfuscated, _))) if obfuscated.start < found.start => {
+                Some(obfuscated)
+            }
+            (None, Some((obfuscated, _))) => Some(obfuscated),
+            (found, _) => found,
+        }
+    }
+}
+
+impl Email {
+    fn find_address(&self, s: &str) -> Option<Range<usize>> {
         let input = s.as_bytes();
         let mut idx = 0;
 
         while idx < input.len() {
             let at_pos = idx + input[idx..].iter().position(|&b| b == b'@')?;
 
+            // A quoted local part, e.g. "odd local"@example.com
+            let quoted = if self.rfc5322 && at_pos > 0 && input[at_pos - 1] == b'"' {
+                match Lexer::new(&s[..at_pos]).last() {
+                    Some((range, Token::Quoted(_))) => Some(range.start),
+                    _ => None,
+                }
+            } else {
+                None
+            };
+
             // Walk backwards for local part
             let mut local_start = at_pos;
             while local_start > idx && Self::is_local_char(input[local_start - 1]) {
@@ -51,67 +212,782 @@ impl Finder for Email {
             }
 
             // Local part must be non-empty and not start/end with '.'
-            if local_start == at_pos || input[local_start] == b'.' || input[at_pos - 1] == b'.' {
-                idx = at_pos + 1;
+            let local_start = match quoted {
+                Some(start) => start,
+                None if local_start == at_pos
+                    || input[local_start] == b'.'
+                    || input[at_pos - 1] == b'.' =>
+                {
+                    idx = at_pos + 1;
+                    continue;
+                }
+                None => local_start,
+            };
+
+            // the text following an address (e.g. in Chinese) is not part of its domain
+            let domain_end = Self::domain_end(s, at_pos + 1, true)
+                .or_else(|| Self::domain_end(s, at_pos + 1, false));
+            let domain_end = match domain_end {
+                Some(end) => end,
+                None if self.rfc5322 => match Self::literal_end(input, at_pos + 1) {
+                    Some(end) => end,
+                    None => {
+                        idx = at_pos + 1;
+                        continue;
+                    }
+                },
+                None => {
+                    idx = at_pos + 1;
+                    continue;
+                }
+            };
+
+            if self.rfc5322 {
+                return Some(Self::mailbox(s, local_start..domain_end));
+            }
+            return Some(local_start..domain_end);
+        }
+
+        None
+    }
+}
+
+struct Obfuscation {
+    address: Regex,
+    dot: Regex,
+}
+
+fn obfuscations() -> &'static [Obfuscation] {
+    static OBFUSCATION_REGEXES: OnceLock<Vec<Obfuscation>> = OnceLock::new();
+    OBFUSCATION_REGEXES.get_or_init(|| {
+        // a word written between brackets (`[at]`, `(at)`, `{at}` or `<at>`), or between spaces
+        let spelled = |words: &str| {
+            format!(
+                r"(?:\s*[\[({{<]\s*(?:{0})\s*[\])}}>]\s*|\s+(?:{0})\s+)",
+                words
+            )
+        };
+        OBFUSCATIONS
+            .iter()
+            .map(|(at, dot)| {
+                let atom = r"[a-z0-9_%+-]+";
+                let label = r"[a-z0-9](?:[a-z0-9-]*[a-z0-9])?";
+                let dot = spelled(dot);
+                let address = format!(
+                    r"(?i)(?:^|[^a-z0-9_%+.-])(?P<local>{atom}(?:(?:\.|{dot}){atom})*)(?P<at>{at})(?P<domain>{label}(?:(?:\.|{dot}){label})*(?:\.|{dot})[a-z]{{2,}})\b",
+                    atom = atom,
+                    label = label,
+                    dot = dot,
+                    at = spelled(at),
+                );
+                Obfuscation {
+                    address: Regex::new(&address).unwrap(),
+                    dot: Regex::new(&format!("(?i){}", dot)).unwrap(),
+                }
+            })
+            .collect()
+    })
+}
+
+// Finds the first obfuscated address, returns its range along with the address.
+fn find_obfuscated(s: &str) -> Option<(Range<usize>, String)> {
+    let mut first: Option<(Range<usize>, String)> = None;
+    for obfuscation in obfuscations() {
+        for captures in obfuscation.address.captures_iter(s) {
+            let (Some(local), Some(at), Some(domain)) = (
+                captures.name("local"),
+                captures.name("at"),
+                captures.name("domain"),
+            ) else {
+                continue;
+            };
+            // the words between spaces must be an address rather than prose: the domain must
+            // end with a TLD following a spelled dot, and no word may be a common one of sentences
+            let bracketed = at.as_str().contains(['[', '(', '{', '<']);
+            if !bracketed && !is_spelled_address(&obfuscation.dot, local.as_str(), domain.as_str())
+            {
                 continue;
             }
+            if first
+                .as_ref()
+                .is_none_or(|(range, _)| local.start() < range.start)
+            {
+                let address = format!(
+                    "{}@{}",
+                    obfuscation.dot.replace_all(local.as_str(), "."),
+                    obfuscation.dot.replace_all(domain.as_str(), "."),
+                );
+                first = Some((local.start()..domain.end(), address));
+            }
+            break;
+        }
+    }
+    first
+}
+
+// Whether the address with a spelled `@` between spaces is likely to be one, see `find_obfuscated`.
+fn is_spelled_address(dot: &Regex, local: &str, domain: &str) -> bool {
+    if !dot.is_match(domain) {
+        return false;
+    }
+    let words: Vec<_> = dot
+        .split(local)
+        .chain(dot.split(domain))
+        .flat_map(|word| word.split('.'))
+        .map(str::to_lowercase)
+        .collect();
+    let tld = words.last().map_or("", String::as_str);
+    let is_tld = tld.len() == 2 && tld.bytes().all(|b| b.is_ascii_alphabetic())
+        || TLDS.split_whitespace().any(|t| t == tld);
+    is_tld
+        && !words
+            .iter()
+            .any(|w| PROSE_WORDS.split_whitespace().any(|p| p == w))
+}
+
+/// Returns the address of the first obfuscated address found in the text (see
+/// [`Email::obfuscated`]).
+///
+/// # Example
+///
+/// ```
+/// use squeeze::email;
+///
+/// assert_eq!(
+///     Some("john.smith@example.com".to_string()),
+///     email::deobfuscate("write to john dot smith [at] example [dot] com"),
+/// );
+/// assert_eq!(None, email::deobfuscate("look at example.com"));
+/// ```
+pub fn deobfuscate(s: &str) -> Option<String> {
+    find_obfuscated(s).map(|(_, address)| address)
+}
+
+/// A mailbox as written in the mail headers, e.g. `"John Smith" <john@example.com>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mailbox {
+    /// The display name, either the phrase before the address or the comment following it, with
+    /// its [RFC 2047](https://www.rfc-editor.org/rfc/rfc2047) encoded words decoded.
+    pub name: Option<String>,
+    /// The local part of the address, unquoted (e.g. `odd local` for `"odd local"@example.com`).
+    pub local: String,
+    /// The domain of the address, as written (e.g. `example.com` or `[192.0.2.1]`).
+    pub domain: String,
+}
+
+impl Mailbox {
+    /// Parses a single mailbox, e.g. `"John Smith" <john@example.com>`, `john@example.com (John
+    /// Smith)` or `john@example.com`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use squeeze::email::Mailbox;
+    ///
+    /// let mailbox = Mailbox::parse("=?UTF-8?Q?J=C3=B6rg?= <jorg@example.com>").unwrap();
+    /// assert_eq!(Some("Jörg"), mailbox.name.as_deref());
+    /// assert_eq!("jorg@example.com", mailbox.address());
+    /// ```
+    pub fn parse(s: &str) -> Option<Mailbox> {
+        let tokens: Vec<_> = Lexer::new(s).map(|(_, token)| token).collect();
+        // the colons of the obsolete routes are within the angle brackets
+        let open = tokens.iter().position(|t| *t == Token::Special('<'));
+        let is_list = tokens.iter().enumerate().any(|(i, t)| match t {
+            Token::Special(',' | ';') => true,
+            Token::Special(':') => open.is_none_or(|open| i < open),
+            _ => false,
+        });
+        if is_list {
+            return None;
+        }
+        mailbox(&tokens)
+    }
 
-            // Walk forwards for domain
-            let domain_start = at_pos + 1;
-            let mut domain_end = domain_start;
-            while domain_end < input.len()
-                && (input[domain_end].is_ascii_alphanumeric()
-                    || input[domain_end] == b'-'
-                    || input[domain_end] == b'.')
+    /// Returns the address of the mailbox, the local part being quoted if needed (e.g.
+    /// `"odd local"@example.com`).
+    pub fn address(&self) -> String {
+        let is_dot_atom = !self.local.is_empty()
+            && self
+                .local
+                .split('.')
+                .all(|atom| !atom.is_empty() && atom.bytes().all(Email::is_local_char));
+        if is_dot_atom {
+            format!("{}@{}", self.local, self.domain)
+        } else {
+            format!("{}@{}", quote(&self.local), self.domain)
+        }
+    }
+}
+
+impl fmt::Display for Mailbox {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.name {
+            Some(ref name)
+                if name
+                    .chars()
+                    .all(|c| c == ' ' || (is_atom_char(c) && c != '.')) =>
             {
-                domain_end += 1;
+                write!(f, "{} <{}>", name, self.address())
             }
+            Some(ref name) => write!(f, "{} <{}>", quote(name), self.address()),
+            None => write!(f, "{}", self.address()),
+        }
+    }
+}
+
+/// An address of an address list, either a mailbox or a group of mailboxes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Address {
+    Mailbox(Mailbox),
+    /// A named list of mailboxes, possibly empty (e.g. `Undisclosed-recipients:;`).
+    Group {
+        name: String,
+        mailboxes: Vec<Mailbox>,
+    },
+}
+
+/// Parses an address list as found in the `From`, `To` or `Cc` mail headers, as defined by
+/// [RFC 5322](https://www.rfc-editor.org/rfc/rfc5322#section-3.4). The invalid addresses are
+/// skipped.
+///
+/// # Example
+///
+/// ```
+/// use squeeze::email::{parse_addresses, Address};
+///
+/// let addresses = parse_addresses(r#""Smith, John" <john@example.com>, Undisclosed-recipients:;"#);
+/// assert_eq!(2, addresses.len());
+/// assert!(matches!(&addresses[0], Address::Mailbox(m) if m.name.as_deref() == Some("Smith, John")));
+/// assert!(matches!(&addresses[1], Address::Group { mailboxes, .. } if mailboxes.is_empty()));
+/// ```
+pub fn parse_addresses(s: &str) -> Vec<Address> {
+    let mut addresses = Vec::new();
+    let mut group: Option<(String, Vec<Mailbox>)> = None;
+    let mut tokens = Vec::new();
+
+    for (_, token) in Lexer::new(s) {
+        match token {
+            // the colons of the obsolete routes (`<@relay.example.com:john@example.com>`) are
+            // within the angle brackets
+            Token::Special(':') if group.is_none() && !tokens.contains(&Token::Special('<')) => {
+                group = Some((phrase(&tokens).unwrap_or_default(), Vec::new()));
+                tokens.clear();
+            }
+            Token::Special(c @ (',' | ';')) => {
+                let mailbox = mailbox(&tokens);
+                tokens.clear();
+                match group {
+                    Some((_, ref mut mailboxes)) => mailboxes.extend(mailbox),
+                    None => addresses.extend(mailbox.map(Address::Mailbox)),
+                }
+                if c == ';' {
+                    if let Some((name, mailboxes)) = group.take() {
+                        addresses.push(Address::Group { name, mailboxes });
+                    }
+                }
+            }
+            token => tokens.push(token),
+        }
+    }
 
-            if domain_end == domain_start {
-                idx = at_pos + 1;
+    let mailbox = mailbox(&tokens);
+    match group {
+        // the group is not terminated
+        Some((name, mut mailboxes)) => {
+            mailboxes.extend(mailbox);
+            addresses.push(Address::Group { name, mailboxes });
+        }
+        None => addresses.extend(mailbox.map(Address::Mailbox)),
+    }
+    addresses
+}
+
+/// Normalizes email addresses, so that the addresses of the same mailbox compare equal (e.g.
+/// `First.Last+tag@GMail.com` and `firstlast@gmail.com`): the domains are lowercased and their
+/// internationalized labels converted to Punycode, and the rules of the email providers are
+/// applied. The local parts are case-sensitive and their subaddresses (`+tag`) provider-specific,
+/// so they are only lowercased and stripped for the providers of the rules.
+///
+/// The default instance uses the embedded rules, which can be extended with
+/// [`Normalizer::add_rules`]. Use [`Normalizer::empty`] to start from scratch.
+///
+/// # Rules
+///
+/// Rules are written one per line, blank lines and lines starting with `#` are ignored:
+///
+/// ```text
+/// # the mailboxes of googlemail.com are the ones of gmail.com
+/// alias googlemail.com gmail.com
+/// # Gmail ignores the case of the local parts, their subaddress (+tag) and their dots
+/// lowercase gmail.com
+/// plus gmail.com
+/// dots gmail.com
+/// ```
+///
+/// The rules apply to the domains the aliases resolve to.
+///
+/// # Example
+///
+/// ```
+/// use squeeze::email::Normalizer;
+///
+/// let normalizer = Normalizer::default();
+/// assert_eq!(
+///     Some("firstlast@gmail.com".to_string()),
+///     normalizer.normalize("First.Last+tag@GoogleMail.com"),
+/// );
+/// assert_eq!(
+///     Some("John+tag@xn--bcher-kva.de".to_string()),
+///     normalizer.normalize("John <John+tag@Bücher.de>"),
+/// );
+/// ```
+pub struct Normalizer {
+    aliases: HashMap<String, String>,
+    lowercase: HashSet<String>,
+    plus: HashSet<String>,
+    dots: HashSet<String>,
+}
+
+impl Default for Normalizer {
+    fn default() -> Self {
+        let mut normalizer = Self::empty();
+        normalizer
+            .add_rules(DEFAULT_RULES)
+            .expect("implementation error: the default email rules are invalid");
+        normalizer
+    }
+}
+
+impl Normalizer {
+    /// Creates a normalizer without any rule.
+    pub fn empty() -> Self {
+        Normalizer {
+            aliases: HashMap::new(),
+            lowercase: HashSet::new(),
+            plus: HashSet::new(),
+            dots: HashSet::new(),
+        }
+    }
+
+    /// Parses `rules` (see the [type documentation](Normalizer)) and adds them to the normalizer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on the first invalid rule, in which case no rule is added.
+    pub fn add_rules(&mut self, rules: &str) -> Result<(), RuleError> {
+        let mut aliases = Vec::new();
+        let mut lowercase = Vec::new();
+        let mut plus = Vec::new();
+        let mut dots = Vec::new();
+
+        for (i, line) in rules.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
                 continue;
             }
+            let error = |message: &str| RuleError::new(i + 1, message);
+            let domain = |domain: &str| {
+                to_ascii(domain).ok_or_else(|| error(&format!("invalid domain '{}'", domain)))
+            };
 
-            // Strip trailing dots/hyphens
-            while domain_end > domain_start && matches!(input[domain_end - 1], b'.' | b'-') {
-                domain_end -= 1;
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["alias", alias, canonical] => aliases.push((domain(alias)?, domain(canonical)?)),
+                ["alias", ..] => return Err(error("expected: alias DOMAIN CANONICAL")),
+                ["lowercase", name] => lowercase.push(domain(name)?),
+                ["lowercase", ..] => return Err(error("expected: lowercase DOMAIN")),
+                ["plus", name] => plus.push(domain(name)?),
+                ["plus", ..] => return Err(error("expected: plus DOMAIN")),
+                ["dots", name] => dots.push(domain(name)?),
+                ["dots", ..] => return Err(error("expected: dots DOMAIN")),
+                [kind, ..] => return Err(error(&format!("unknown rule '{}'", kind))),
+                [] => unreachable!(),
             }
+        }
 
-            let domain = &s[domain_start..domain_end];
+        self.aliases.extend(aliases);
+        self.lowercase.extend(lowercase);
+        self.plus.extend(plus);
+        self.dots.extend(dots);
+        Ok(())
+    }
 
-            // Must have at least one dot
-            if !domain.contains('.') {
-                idx = at_pos + 1;
-                continue;
+    /// Returns the normalized address of a mailbox (see [`Mailbox::parse`]), without its display
+    /// name.
+    pub fn normalize(&self, s: &str) -> Option<String> {
+        let mailbox = Mailbox::parse(s)?;
+
+        let domain = if mailbox.domain.starts_with('[') {
+            mailbox.domain.to_ascii_lowercase()
+        } else {
+            to_ascii(&mailbox.domain)?
+        };
+        let domain = self.aliases.get(&domain).cloned().unwrap_or(domain);
+
+        let mut local = mailbox.local;
+        if self.lowercase.contains(&domain) {
+            local = local.to_lowercase();
+        }
+        // the subaddress of +tag@example.com is the whole local part
+        if let Some(plus) = local.get(1..).and_then(|l| l.find('+')) {
+            if self.plus.contains(&domain) {
+                local.truncate(plus + 1);
             }
+        }
+        if self.dots.contains(&domain) {
+            local.retain(|c| c != '.');
+        }
 
-            // Validate each label
-            let valid = domain.split('.').all(|label| {
-                !label.is_empty()
-                    && !label.starts_with('-')
-                    && !label.ends_with('-')
-                    && label
-                        .bytes()
-                        .all(|b| b.is_ascii_alphanumeric() || b == b'-')
-            });
+        let mailbox = Mailbox {
+            name: None,
+            local,
+            domain,
+        };
+        Some(mailbox.address())
+    }
+}
 
-            if !valid {
-                idx = at_pos + 1;
-                continue;
+// Converts a domain to lowercase ASCII, its internationalized labels being encoded with Punycode,
+// e.g. `xn--bcher-kva.de` for `Bücher.de`.
+fn to_ascii(domain: &str) -> Option<String> {
+    // the ideographic full stops are dots as well
+    let domain = domain
+        .trim_end_matches(['.', '\u{3002}', '\u{ff0e}', '\u{ff61}'])
+        .to_lowercase();
+    let labels: Option<Vec<_>> = domain
+        .split(['.', '\u{3002}', '\u{ff0e}', '\u{ff61}'])
+        .map(|label| match label {
+            "" => None,
+            label if label.is_ascii() => Some(label.to_string()),
+            label => punycode(label).map(|label| format!("xn--{}", label)),
+        })
+        .collect();
+    Some(labels?.join("."))
+}
+
+// Encodes a label with the Punycode algorithm of RFC 3492.
+fn punycode(label: &str) -> Option<String> {
+    const BASE: u32 = 36;
+    const T_MIN: u32 = 1;
+    const T_MAX: u32 = 26;
+
+    fn adapt(delta: u32, points: u32, first: bool) -> u32 {
+        let mut delta = if first { delta / 700 } else { delta / 2 };
+        delta += delta / points;
+        let mut k = 0;
+        while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+            delta /= BASE - T_MIN;
+            k += BASE;
+        }
+        k + (BASE - T_MIN + 1) * delta / (delta + 38)
+    }
+
+    fn digit(d: u32) -> char {
+        match d {
+            0..=25 => (b'a' + d as u8) as char,
+            _ => (b'0' + (d - 26) as u8) as char,
+        }
+    }
+
+    let input: Vec<u32> = label.chars().map(u32::from).collect();
+    let mut output: String = label.chars().filter(char::is_ascii).collect();
+    let basic = output.len() as u32;
+    if basic > 0 {
+        output.push('-');
+    }
+
+    let (mut n, mut delta, mut bias, mut handled) = (0x80, 0u32, 72, basic);
+    while (handled as usize) < input.len() {
+        let m = *input.iter().filter(|&&c| c >= n).min()?;
+        delta = delta.checked_add((m - n).checked_mul(handled + 1)?)?;
+        n = m;
+        for &c in &input {
+            if c < n {
+                delta = delta.checked_add(1)?;
+            }
+            if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = k.saturating_sub(bias).clamp(T_MIN, T_MAX);
+                    if q < t {
+                        break;
+                    }
+                    output.push(digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                output.push(digit(q));
+                bias = adapt(delta, handled + 1, handled == basic);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta += 1;
+        n += 1;
+    }
+    Some(output)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Token<'a> {
+    Atom(&'a str),
+    // the text between the quotes, still escaped
+    Quoted(&'a str),
+    // the text between the parentheses, still escaped
+    Comment(&'a str),
+    // including the brackets
+    Literal(&'a str),
+    Special(char),
+}
+
+// Splits a header into the tokens of RFC 5322, the quoted strings and comments not terminated
+// being split into specials.
+struct Lexer<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(s: &'a str) -> Self {
+        Lexer { s, pos: 0 }
+    }
+
+    fn starting_at(mut self, pos: usize) -> Self {
+        self.pos = pos;
+        self
+    }
+
+    // Returns the end of the delimited token starting at the current position, the delimiters
+    // being nested when `open` is given.
+    fn delimited(&self, open: Option<u8>, close: u8) -> Option<usize> {
+        let input = self.s.as_bytes();
+        let mut depth = 0;
+        let mut pos = self.pos + 1;
+        while pos < input.len() {
+            match input[pos] {
+                b'\\' => pos += 1,
+                b if b == close && depth == 0 => return Some(pos + 1),
+                b if b == close => depth -= 1,
+                b if Some(b) == open => depth += 1,
+                _ => {}
+            }
+            pos += 1;
+        }
+        None
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = (Range<usize>, Token<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.s[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+
+        let start = self.pos;
+        let c = self.s[start..].chars().next()?;
+        let end = match c {
+            '"' => self.delimited(None, b'"'),
+            '(' => self.delimited(Some(b'('), b')'),
+            '[' => self.delimited(None, b']'),
+            _ => None,
+        };
+
+        let token = match (c, end) {
+            ('"', Some(end)) => Token::Quoted(&self.s[start + 1..end - 1]),
+            ('(', Some(end)) => Token::Comment(&self.s[start + 1..end - 1]),
+            ('[', Some(end)) => Token::Literal(&self.s[start..end]),
+            (c, _) if is_atom_char(c) => {
+                let len = self.s[start..]
+                    .find(|c| !is_atom_char(c))
+                    .unwrap_or(self.s.len() - start);
+                Token::Atom(&self.s[start..start + len])
             }
+            (c, _) => Token::Special(c),
+        };
 
-            // TLD must be >= 2 chars and all alpha
-            let tld = domain.rsplit('.').next().unwrap();
-            if tld.len() < 2 || !tld.bytes().all(|b| b.is_ascii_alphabetic()) {
-                idx = at_pos + 1;
+        self.pos = match token {
+            Token::Atom(atom) => start + atom.len(),
+            Token::Special(c) => start + c.len_utf8(),
+            _ => end.unwrap_or(start),
+        };
+        Some((start..self.pos, token))
+    }
+}
+
+// The non-ASCII characters are allowed by RFC 6532, the dots by the obsolete syntax of the
+// phrases.
+fn is_atom_char(c: char) -> bool {
+    if c.is_ascii() {
+        Email::is_local_char(c as u8)
+    } else {
+        !c.is_whitespace()
+    }
+}
+
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        unescaped.extend(if c == '\\' { chars.next() } else { Some(c) });
+    }
+    unescaped
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// Parses the tokens of a single mailbox, between the commas of an address list.
+fn mailbox(tokens: &[Token]) -> Option<Mailbox> {
+    let (name, spec) = match tokens.iter().position(|t| *t == Token::Special('<')) {
+        Some(open) => {
+            let close = open
+                + tokens[open..]
+                    .iter()
+                    .position(|t| *t == Token::Special('>'))?;
+            let mut spec = &tokens[open + 1..close];
+            // skip the obsolete route, e.g. <@relay.example.com:john@example.com>
+            if let Some(colon) = spec.iter().position(|t| *t == Token::Special(':')) {
+                spec = &spec[colon + 1..];
+            }
+            (phrase(&tokens[..open]), spec)
+        }
+        // the comment following an address is its display name by convention
+        None => {
+            let comment = tokens.iter().rev().find_map(|t| match t {
+                Token::Comment(comment) => Some(*comment),
+                _ => None,
+            });
+            let name = comment.and_then(|comment| {
+                let comment = unescape(comment);
+                let words: Vec<_> = comment.split_whitespace().map(Token::Atom).collect();
+                phrase(&words)
+            });
+            (name, tokens)
+        }
+    };
+
+    let mut spec = spec.iter().filter(|t| !matches!(t, Token::Comment(_)));
+    let mut local = String::new();
+    for token in spec.by_ref() {
+        match token {
+            Token::Atom(atom) => local.push_str(atom),
+            Token::Quoted(quoted) => local.push_str(&unescape(quoted)),
+            Token::Special('@') => break,
+            _ => return None,
+        }
+    }
+    let mut domain = String::new();
+    for token in spec {
+        match token {
+            Token::Atom(atom) | Token::Literal(atom) => domain.push_str(atom),
+            _ => return None,
+        }
+    }
+
+    if local.is_empty() || domain.is_empty() {
+        return None;
+    }
+    Some(Mailbox {
+        name,
+        local,
+        domain,
+    })
+}
+
+// Returns the text of a display name, the adjacent encoded words being joined without spaces.
+fn phrase(tokens: &[Token]) -> Option<String> {
+    let mut phrase = String::new();
+    let mut encoded = false;
+    let mut glued = true;
+    for token in tokens {
+        let (word, is_encoded) = match token {
+            Token::Atom(atom) => match decode_word(atom) {
+                Some(decoded) => (decoded, true),
+                None => (atom.to_string(), false),
+            },
+            Token::Quoted(quoted) => (unescape(quoted), false),
+            Token::Literal(literal) => (literal.to_string(), false),
+            // e.g. the @ of an address used as a display name
+            Token::Special(c) => {
+                phrase.push(*c);
+                glued = true;
                 continue;
             }
+            Token::Comment(_) => continue,
+        };
+        // the whitespace between encoded words is not part of the text
+        let joined = glued || (encoded && is_encoded);
+        if !joined {
+            phrase.push(' ');
+        }
+        phrase.push_str(&word);
+        encoded = is_encoded;
+        glued = false;
+    }
+    (!phrase.is_empty()).then_some(phrase)
+}
 
-            return Some(local_start..domain_end);
+// Decodes an RFC 2047 encoded word, e.g. `=?UTF-8?Q?J=C3=B6rg?=`.
+fn decode_word(word: &str) -> Option<String> {
+    let word = word.strip_prefix("=?")?.strip_suffix("?=")?;
+    let mut parts = word.splitn(3, '?');
+    let (charset, encoding, text) = (parts.next()?, parts.next()?, parts.next()?);
+    // the language of RFC 2231 follows the charset, e.g. UTF-8*fr
+    let charset = charset.split('*').next()?.to_ascii_lowercase();
+
+    let bytes = match encoding {
+        "B" | "b" => decode_base64(text)?,
+        "Q" | "q" => decode_quoted_printable(text)?,
+        _ => return None,
+    };
+
+    match charset.as_str() {
+        "utf-8" | "utf8" | "us-ascii" => String::from_utf8(bytes).ok(),
+        "iso-8859-1" | "latin1" => Some(bytes.into_iter().map(char::from).collect()),
+        _ => None,
+    }
+}
+
+fn decode_base64(s: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(s.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for b in s.trim_end_matches('=').bytes() {
+        let value = match b {
+            b'A'..=b'Z' => b - b'A',
+            b'a'..=b'z' => b - b'a' + 26,
+            b'0'..=b'9' => b - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buffer = buffer << 6 | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
         }
+    }
+    Some(bytes)
+}
 
-        None
+fn decode_quoted_printable(s: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut input = s.bytes();
+    while let Some(b) = input.next() {
+        match b {
+            b'_' => bytes.push(b' '),
+            b'=' => {
+                let hex = [input.next()?, input.next()?];
+                bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+            }
+            b => bytes.push(b),
+        }
     }
+    Some(bytes)
 }
 
 #[cfg(test)]
@@ -288,4 +1164,362 @@ mod tests {
         let range = finder.find(input).unwrap();
         assert_eq!("real@example.com", &input[range]);
     }
+
+    #[test]
+    fn find_should_extract_rfc5322_mailboxes() {
+        let finder = Email {
+            rfc5322: true,
+            ..Default::default()
+        };
+        for (input, expected) in [
+            (
+                r#"From: "John Smith" <john@example.com>"#,
+                r#""John Smith" <john@example.com>"#,
+            ),
+            (
+                "To: John Q. Public <john@example.com>, x",
+                "John Q. Public <john@example.com>",
+            ),
+            ("Cc: <john@example.com>", "<john@example.com>"),
+            (
+                "john@example.com (John Smith) said",
+                "john@example.com (John Smith)",
+            ),
+            (
+                r#"To: "odd local"@example.com"#,
+                r#""odd local"@example.com"#,
+            ),
+            ("To: john@[192.0.2.1]", "john@[192.0.2.1]"),
+            ("Team: alice@example.com;", "alice@example.com"),
+            (
+                "=?UTF-8?Q?J=C3=B6rg?= <jorg@example.com>",
+                "=?UTF-8?Q?J=C3=B6rg?= <jorg@example.com>",
+            ),
+            // the angle brackets are not closed
+            ("John <john@example.com", "john@example.com"),
+        ] {
+            let range = finder.find(input).unwrap();
+            assert_eq!(expected, &input[range], "{}", input);
+        }
+        assert!(finder.find("Undisclosed-recipients:;").is_none());
+    }
+
+    #[test]
+    fn find_should_not_extract_rfc5322_syntax_by_default() {
+        let finder = Email::default();
+        let input = r#""John Smith" <john@example.com>"#;
+        let range = finder.find(input).unwrap();
+        assert_eq!("john@example.com", &input[range]);
+        assert!(finder.find(r#""odd local"@example.com"#).is_none());
+        assert!(finder.find("john@[192.0.2.1]").is_none());
+    }
+
+    #[test]
+    fn mailbox_parse_should_extract_names_and_addresses() {
+        for (input, name, address) in [
+            ("john@example.com", None, "john@example.com"),
+            (
+                r#""Smith, John" <john@example.com>"#,
+                Some("Smith, John"),
+                "john@example.com",
+            ),
+            (
+                "John (Jack) Smith <john@example.com>",
+                Some("John Smith"),
+                "john@example.com",
+            ),
+            (
+                "john@example.com (John Smith)",
+                Some("John Smith"),
+                "john@example.com",
+            ),
+            (
+                r#""odd \"local\""@example.com"#,
+                None,
+                r#""odd \"local\""@example.com"#,
+            ),
+            (
+                "<@relay.example.com:john@example.com>",
+                None,
+                "john@example.com",
+            ),
+            (
+                "=?UTF-8?B?SsO2cmc=?= =?UTF-8?Q?_M=C3=BCller?= <jorg@example.com>",
+                Some("Jörg Müller"),
+                "jorg@example.com",
+            ),
+            (
+                "=?ISO-8859-1?Q?Andr=E9?= Pirard <andre@example.com>",
+                Some("André Pirard"),
+                "andre@example.com",
+            ),
+            (
+                "=?UNKNOWN?Q?x?= <john@example.com>",
+                Some("=?UNKNOWN?Q?x?="),
+                "john@example.com",
+            ),
+        ] {
+            let mailbox = Mailbox::parse(input).unwrap();
+            assert_eq!(name, mailbox.name.as_deref(), "{}", input);
+            assert_eq!(address, mailbox.address(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn mailbox_parse_should_reject_invalid_mailboxes() {
+        for input in [
+            "",
+            "john",
+            "john@",
+            "@example.com",
+            "John <john@example.com",
+            "a@example.com, b@example.com",
+        ] {
+            assert_eq!(None, Mailbox::parse(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn mailbox_should_display_as_in_headers() {
+        for (input, expected) in [
+            ("john@example.com", "john@example.com"),
+            (
+                "John Smith <john@example.com>",
+                "John Smith <john@example.com>",
+            ),
+            (
+                "\"Smith, John\" <john@example.com>",
+                "\"Smith, John\" <john@example.com>",
+            ),
+            (
+                "John Q. Public <john@example.com>",
+                "\"John Q. Public\" <john@example.com>",
+            ),
+        ] {
+            assert_eq!(expected, Mailbox::parse(input).unwrap().to_string());
+        }
+    }
+
+    #[test]
+    fn parse_addresses_should_extract_mailboxes_and_groups() {
+        let mailbox = |name: Option<&str>, local: &str| Mailbox {
+            name: name.map(str::to_string),
+            local: local.to_string(),
+            domain: "example.com".to_string(),
+        };
+        assert_eq!(
+            vec![
+                Address::Mailbox(mailbox(Some("Smith, John"), "john")),
+                Address::Group {
+                    name: "Undisclosed-recipients".to_string(),
+                    mailboxes: Vec::new(),
+                },
+                Address::Group {
+                    name: "Team".to_string(),
+                    mailboxes: vec![mailbox(None, "alice"), mailbox(Some("Bob"), "bob")],
+                },
+                Address::Mailbox(mailbox(None, "carol")),
+            ],
+            parse_addresses(
+                r#""Smith, John" <john@example.com>, Undisclosed-recipients:;, Team: alice@example.com, Bob <bob@example.com>;, invalid, carol@example.com"#
+            )
+        );
+    }
+
+    #[test]
+    fn find_should_extract_internationalized_domains() {
+        let finder = Email::default();
+        for (input, expected) in [
+            ("write to user@bücher.de now", "user@bücher.de"),
+            ("user@пример.рф", "user@пример.рф"),
+            ("请联系user@example.com获取", "user@example.com"),
+        ] {
+            let range = finder.find(input).unwrap();
+            assert_eq!(expected, &input[range], "{}", input);
+        }
+    }
+
+    #[test]
+    fn normalize_should_canonicalize_addresses() {
+        let normalizer = Normalizer::default();
+        for (input, expected) in [
+            ("john@example.com", "john@example.com"),
+            ("John.Smith@EXAMPLE.com", "John.Smith@example.com"),
+            ("john+newsletter@example.com", "john+newsletter@example.com"),
+            ("John+newsletter@Outlook.com", "john@outlook.com"),
+            ("+tag@gmail.com", "+tag@gmail.com"),
+            ("First.Last+tag@GMail.com", "firstlast@gmail.com"),
+            ("first.last@googlemail.com", "firstlast@gmail.com"),
+            ("John.Smith+tag@me.com", "john.smith@icloud.com"),
+            ("john@Example.com.", "john@example.com"),
+            ("john@[192.0.2.1]", "john@[192.0.2.1]"),
+            (r#""John Smith" <John@Example.com>"#, "John@example.com"),
+            (r#""Odd Local"@example.com"#, r#""Odd Local"@example.com"#),
+            ("user@Bücher.de", "user@xn--bcher-kva.de"),
+            ("user@münchen.de", "user@xn--mnchen-3ya.de"),
+            ("user@пример.рф", "user@xn--e1afmkfd.xn--p1ai"),
+            ("user@例え。テスト", "user@xn--r8jz45g.xn--zckzah"),
+        ] {
+            assert_eq!(
+                Some(expected.to_string()),
+                normalizer.normalize(input),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(None, normalizer.normalize("not an address"));
+    }
+
+    #[test]
+    fn normalize_should_apply_the_added_rules() {
+        let mut normalizer = Normalizer::empty();
+        assert_eq!(
+            Some("First.Last@googlemail.com".to_string()),
+            normalizer.normalize("First.Last@GoogleMail.com")
+        );
+        normalizer
+            .add_rules("# comment\n\nalias example.org example.com\ndots Example.com\n")
+            .unwrap();
+        assert_eq!(
+            Some("FirstLast+tag@example.com".to_string()),
+            normalizer.normalize("First.Last+tag@example.org")
+        );
+        normalizer
+            .add_rules("lowercase example.com\nplus example.com\n")
+            .unwrap();
+        assert_eq!(
+            Some("firstlast@example.com".to_string()),
+            normalizer.normalize("First.Last+tag@example.org")
+        );
+    }
+
+    #[test]
+    fn add_rules_should_reject_invalid_rules() {
+        for (rules, line) in [
+            ("alias example.org", 1),
+            ("# comment\ndots", 2),
+            ("dots example.com\nunknown example.com", 2),
+            ("dots a..b", 1),
+            ("lowercase", 1),
+            ("plus example.com example.org", 1),
+        ] {
+            let mut normalizer = Normalizer::empty();
+            assert_eq!(
+                line,
+                normalizer.add_rules(rules).unwrap_err().line,
+                "{}",
+                rules
+            );
+            assert!(normalizer.dots.is_empty(), "{}", rules);
+        }
+    }
+
+    #[test]
+    fn find_should_extract_obfuscated_addresses() {
+        let finder = Email {
+            obfuscated: true,
+            ..Default::default()
+        };
+        for (input, expected, address) in [
+            (
+                "mail john at example dot com today",
+                "john at example dot com",
+                "john@example.com",
+            ),
+            (
+                "mail john AT example DOT com today",
+                "john AT example DOT com",
+                "john@example.com",
+            ),
+            (
+                "john DOT smith AT example [dot] org",
+                "john DOT smith AT example [dot] org",
+                "john.smith@example.org",
+            ),
+            (
+                "john[at]example[dot]com",
+                "john[at]example[dot]com",
+                "john@example.com",
+            ),
+            (
+                "contact: john (at) example.com.",
+                "john (at) example.com",
+                "john@example.com",
+            ),
+            (
+                "John.Smith {AT} mail.example {DOT} org",
+                "John.Smith {AT} mail.example {DOT} org",
+                "John.Smith@mail.example.org",
+            ),
+            (
+                "john dot smith <at> example <dot> co <dot> uk",
+                "john dot smith <at> example <dot> co <dot> uk",
+                "john.smith@example.co.uk",
+            ),
+            (
+                "écrire à jean chez exemple point fr",
+                "jean chez exemple point fr",
+                "jean@exemple.fr",
+            ),
+            (
+                "escribe a juan arroba ejemplo punto es",
+                "juan arroba ejemplo punto es",
+                "juan@ejemplo.es",
+            ),
+            (
+                "juan (arroba) ejemplo.com.br",
+                "juan (arroba) ejemplo.com.br",
+                "juan@ejemplo.com.br",
+            ),
+            // the first address is reported first
+            (
+                "jane@example.org or john [at] example.com",
+                "jane@example.org",
+                "jane@example.org",
+            ),
+            (
+                "john [at] example.com or jane@example.org",
+                "john [at] example.com",
+                "john@example.com",
+            ),
+        ] {
+            let range = finder.find(input).unwrap();
+            assert_eq!(expected, &input[range.clone()], "{}", input);
+            assert_eq!(
+                address,
+                deobfuscate(&input[range.clone()]).unwrap_or_else(|| input[range].to_string()),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn find_should_reject_ambiguous_obfuscated_addresses() {
+        let finder = Email {
+            obfuscated: true,
+            ..Default::default()
+        };
+        for input in [
+            "look at example.com",
+            "we met at the office",
+            "john at example dot c",
+            "john[at]example",
+            "jean chez exemple dot fr",
+            "meet me at noon. see example dot com",
+            "john at example dotcom",
+            "I am at home dot com",
+            "she is at work dot net",
+            "we stayed at the hotel dot com site",
+            "dinner at eight dot thirty",
+            "email me at john dot com",
+            "john at example dot notatld",
+            "je suis chez moi point fr",
+        ] {
+            assert_eq!(None, finder.find(input), "{}", input);
+        }
+        assert!(Email::default()
+            .find("john [at] example [dot] com")
+            .is_none());
+    }
 }
@@ -1,5 +1,8 @@
+use super::cidr::Network;
 use super::Finder;
+use std::net::{IpAddr, Ipv4Addr};
 use std::ops::Range;
+use std::sync::OnceLock;
 
 pub struct Ip {
     pub ipv4: bool,
@@ -74,7 +77,17 @@ impl Ip {
         let close_pos = idx + close;
         let inner = &input[idx + 1..close_pos];
 
-        if Self::is_valid_ipv6(inner) {
+        // with an optional zone, e.g. [fe80::1%eth0], or [fe80::1%25eth0] in URIs
+        let (addr, zone) = match inner.iter().position(|&b| b == b'%') {
+            Some(percent) => (&inner[..percent], Some(&inner[percent + 1..])),
+            None => (inner, None),
+        };
+        let zone_len = zone.map_or(0, Self::zone_len);
+        if zone.is_some_and(|zone| zone.is_empty() || zone_len != zone.len()) {
+            return None;
+        }
+
+        if Self::is_valid_ipv6(addr) {
             Some(idx..close_pos + 1)
         } else {
             None
@@ -101,6 +114,11 @@ impl Ip {
             end += 1;
         }
 
+        // Strip trailing dots, e.g. at the end of a sentence
+        while end > start && input[end - 1] == b'.' {
+            end -= 1;
+        }
+
         // Strip trailing colons
         while end > start && input[end - 1] == b':' && !(end >= 2 && input[end - 2] == b':') {
             end -= 1;
@@ -118,11 +136,31 @@ impl Ip {
             return None;
         }
 
-        if Self::is_valid_ipv6(candidate) {
-            Some(start..end)
-        } else {
-            None
+        if !Self::is_valid_ipv6(candidate) {
+            return None;
+        }
+
+        // Zone, e.g. fe80::1%eth0
+        if end < input.len() && input[end] == b'%' {
+            let zone_len = Self::zone_len(&input[end + 1..]);
+            if zone_len > 0 {
+                end += 1 + zone_len;
+            }
         }
+
+        Some(start..end)
+    }
+
+    // Returns the length of the zone starting the input, without its trailing dots.
+    fn zone_len(input: &[u8]) -> usize {
+        let len = input
+            .iter()
+            .position(|&b| !(b.is_ascii_alphanumeric() || b"-_.~".contains(&b)))
+            .unwrap_or(input.len());
+        input[..len]
+            .iter()
+            .rposition(|&b| b != b'.')
+            .map_or(0, |last| last + 1)
     }
 
     fn is_valid_ipv6(bytes: &[u8]) -> bool {
@@ -136,7 +174,6 @@ impl Ip {
             let suffix = &s[last_colon + 1..];
             if suffix.contains('.') {
                 let prefix = &s[..last_colon + 1];
-                let prefix_bytes = prefix.as_bytes();
                 // Validate the IPv4 part
                 let parts: Vec<&str> = suffix.split('.').collect();
                 if parts.len() == 4 {
@@ -150,25 +187,18 @@ impl Ip {
                         p.parse::<u16>().is_ok_and(|v| v <= 255)
                     });
                     if ipv4_valid {
-                        // Validate the prefix as IPv6 groups (should end with :)
-                        // Count the groups in prefix
-                        let trimmed = prefix.trim_end_matches(':');
-                        if trimmed.is_empty() {
-                            // Just "::" prefix — valid
-                            return prefix_bytes.windows(2).any(|w| w == b"::");
-                        }
-                        // Validate prefix groups
-                        return Self::validate_ipv6_groups(trimmed, true);
+                        // The IPv4 address stands for the last 2 groups
+                        return Self::validate_ipv6_groups(&format!("{}0:0", prefix));
                     }
                 }
             }
         }
 
-        Self::validate_ipv6_groups(s, false)
+        Self::validate_ipv6_groups(s)
     }
 
-    fn validate_ipv6_groups(s: &str, has_ipv4_suffix: bool) -> bool {
-        let max_groups = if has_ipv4_suffix { 6 } else { 8 };
+    fn validate_ipv6_groups(s: &str) -> bool {
+        let max_groups = 8;
 
         if s == "::" {
             return true;
@@ -252,6 +282,198 @@ impl Finder for Ip {
     }
 }
 
+/// The scope of an IP address, according to the IANA
+/// [IPv4](https://www.iana.org/assignments/iana-ipv4-special-registry/) and
+/// [IPv6](https://www.iana.org/assignments/iana-ipv6-special-registry/) special-purpose address
+/// registries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    /// `127.0.0.0/8` and `::1`.
+    Loopback,
+    /// The RFC 1918 networks (`10.0.0.0/8`, `172.16.0.0/12` and `192.168.0.0/16`), and the
+    /// unique local addresses (`fc00::/7`).
+    Private,
+    /// `169.254.0.0/16` and `fe80::/10`.
+    LinkLocal,
+    /// `224.0.0.0/4` and `ff00::/8`.
+    Multicast,
+    /// `192.0.2.0/24`, `198.51.100.0/24`, `203.0.113.0/24`, `2001:db8::/32` and `3fff::/20`.
+    Documentation,
+    /// The shared address space of carrier-grade NATs (`100.64.0.0/10`).
+    Cgnat,
+    /// The other addresses which are not globally reachable (e.g. `0.0.0.0/8`, `198.18.0.0/15`,
+    /// `240.0.0.0/4` or `::`).
+    Reserved,
+    /// The global unicast addresses.
+    Public,
+}
+
+impl Scope {
+    const NAMES: &[(&str, Scope)] = &[
+        ("loopback", Scope::Loopback),
+        ("private", Scope::Private),
+        ("link-local", Scope::LinkLocal),
+        ("multicast", Scope::Multicast),
+        ("documentation", Scope::Documentation),
+        ("cgnat", Scope::Cgnat),
+        ("reserved", Scope::Reserved),
+        ("public", Scope::Public),
+    ];
+
+    /// Returns the scope of the given name (e.g. `link-local`), case-insensitively.
+    pub fn from_name(name: &str) -> Option<Scope> {
+        Self::NAMES
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, scope)| *scope)
+    }
+
+    /// Returns the name of the scope (e.g. `link-local`).
+    pub fn name(&self) -> &'static str {
+        Self::NAMES
+            .iter()
+            .find(|(_, scope)| scope == self)
+            .map_or("", |(name, _)| name)
+    }
+}
+
+// The most specific network containing an address gives its scope.
+const SPECIAL_PURPOSE: &[(&str, Scope)] = &[
+    ("0.0.0.0/0", Scope::Public),
+    ("0.0.0.0/8", Scope::Reserved),
+    ("10.0.0.0/8", Scope::Private),
+    ("100.64.0.0/10", Scope::Cgnat),
+    ("127.0.0.0/8", Scope::Loopback),
+    ("169.254.0.0/16", Scope::LinkLocal),
+    ("172.16.0.0/12", Scope::Private),
+    ("192.0.0.0/24", Scope::Reserved),
+    ("192.0.2.0/24", Scope::Documentation),
+    ("192.88.99.0/24", Scope::Reserved),
+    ("192.168.0.0/16", Scope::Private),
+    ("198.18.0.0/15", Scope::Reserved),
+    ("198.51.100.0/24", Scope::Documentation),
+    ("203.0.113.0/24", Scope::Documentation),
+    ("224.0.0.0/4", Scope::Multicast),
+    ("240.0.0.0/4", Scope::Reserved),
+    ("::/0", Scope::Reserved),
+    ("::1/128", Scope::Loopback),
+    ("64:ff9b::/96", Scope::Public),
+    ("64:ff9b:1::/48", Scope::Reserved),
+    ("100::/64", Scope::Reserved),
+    ("2000::/3", Scope::Public),
+    ("2001::/23", Scope::Reserved),
+    ("2001:db8::/32", Scope::Documentation),
+    ("3fff::/20", Scope::Documentation),
+    ("5f00::/16", Scope::Reserved),
+    ("fc00::/7", Scope::Private),
+    ("fe80::/10", Scope::LinkLocal),
+    ("ff00::/8", Scope::Multicast),
+];
+
+pub(crate) fn special_purpose() -> &'static [(Network, Scope)] {
+    static SPECIAL_PURPOSE_NETWORKS: OnceLock<Vec<(Network, Scope)>> = OnceLock::new();
+    SPECIAL_PURPOSE_NETWORKS.get_or_init(|| {
+        SPECIAL_PURPOSE
+            .iter()
+            .filter_map(|(network, scope)| Some((Network::parse(network)?, *scope)))
+            .collect()
+    })
+}
+
+/// Returns the scope of an IP address, the IPv4-mapped IPv6 addresses (`::ffff:10.0.0.1`) having
+/// the scope of their IPv4 address.
+///
+/// # Example
+///
+/// ```
+/// use squeeze::ip::{scope, Scope};
+///
+/// assert_eq!(Scope::Private, scope("192.168.1.1".parse().unwrap()));
+/// assert_eq!(Scope::LinkLocal, scope("fe80::1".parse().unwrap()));
+/// assert_eq!(Scope::Public, scope("2606:4700::1111".parse().unwrap()));
+/// ```
+pub fn scope(addr: IpAddr) -> Scope {
+    let addr = match addr {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(addr, IpAddr::V4),
+        addr => addr,
+    };
+    let host = Network::new(addr, if addr.is_ipv4() { 32 } else { 128 });
+    special_purpose()
+        .iter()
+        .filter(|(network, _)| host.is_some_and(|host| network.contains(&host)))
+        .max_by_key(|(network, _)| network.prefix())
+        .map_or(Scope::Reserved, |(_, scope)| *scope)
+}
+
+/// Parses an IP address as found by the [`Ip`] finder (e.g. `10.0.0.1`, `[::1]` or
+/// `fe80::1%eth0`), and returns it along with its IPv6 zone.
+///
+/// # Example
+///
+/// ```
+/// use squeeze::ip;
+///
+/// let (addr, zone) = ip::parse("[fe80::1%25eth0]").unwrap();
+/// assert_eq!("fe80::1", addr.to_string());
+/// assert_eq!(Some("eth0"), zone);
+/// ```
+pub fn parse(s: &str) -> Option<(IpAddr, Option<&str>)> {
+    let (s, bracketed) = match s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+        Some(s) => (s, true),
+        None => (s, false),
+    };
+    let (addr, zone) = match s.split_once('%') {
+        // the % is percent-encoded in URIs (RFC 6874)
+        Some((addr, zone)) if bracketed && zone.len() > 2 && zone.starts_with("25") => {
+            (addr, Some(&zone[2..]))
+        }
+        Some((addr, zone)) => (addr, Some(zone)),
+        None => (s, None),
+    };
+    let addr: IpAddr = addr.parse().ok()?;
+    if zone.is_some_and(|zone| addr.is_ipv4() || zone.is_empty()) {
+        return None;
+    }
+    Some((addr, zone))
+}
+
+/// Returns the canonical text of an IP address as found by the [`Ip`] finder, without brackets,
+/// so that the equivalent addresses can be compared. IPv6 addresses are written as recommended
+/// by [RFC 5952](https://www.rfc-editor.org/rfc/rfc5952) (e.g. `2001:db8::1` for
+/// `2001:DB8:0:0::1`), and the IPv4-mapped ones (`::ffff:192.0.2.1`) are unwrapped into IPv4
+/// addresses if asked to.
+///
+/// # Example
+///
+/// ```
+/// use squeeze::ip;
+///
+/// assert_eq!(Some("2001:db8::1".to_string()), ip::canonical("[2001:DB8:0:0::1]", false));
+/// assert_eq!(Some("::ffff:192.0.2.1".to_string()), ip::canonical("::FFFF:C000:201", false));
+/// assert_eq!(Some("192.0.2.1".to_string()), ip::canonical("::ffff:192.0.2.1", true));
+/// ```
+pub fn canonical(s: &str, unmap: bool) -> Option<String> {
+    let (addr, zone) = parse(s)?;
+    let v6 = match addr {
+        IpAddr::V4(v4) => return Some(v4.to_string()),
+        IpAddr::V6(v6) => v6,
+    };
+    let mut canonical = match v6.to_ipv4_mapped() {
+        Some(v4) if unmap => return Some(v4.to_string()),
+        _ => v6.to_string(),
+    };
+    // the IPv4-translated addresses keep their IPv4 address in dotted notation, like the
+    // IPv4-mapped ones (RFC 5952, section 5)
+    if let [0x64, 0xff9b, 0, 0, 0, 0, high, low] = v6.segments() {
+        let v4 = Ipv4Addr::from(u32::from(high) << 16 | u32::from(low));
+        canonical = format!("64:ff9b::{}", v4);
+    }
+    if let Some(zone) = zone {
+        canonical = format!("{}%{}", canonical, zone);
+    }
+    Some(canonical)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -444,4 +666,99 @@ mod tests {
         let finder = Ip::default();
         assert!(finder.find("just some text").is_none());
     }
+
+    // Scopes
+    #[test]
+    fn scope_should_classify_special_purpose_addresses() {
+        for (input, expected) in [
+            ("8.8.8.8", Scope::Public),
+            ("0.1.2.3", Scope::Reserved),
+            ("10.1.2.3", Scope::Private),
+            ("100.64.0.1", Scope::Cgnat),
+            ("100.128.0.1", Scope::Public),
+            ("127.0.0.1", Scope::Loopback),
+            ("169.254.1.1", Scope::LinkLocal),
+            ("172.16.0.1", Scope::Private),
+            ("172.32.0.1", Scope::Public),
+            ("192.0.0.8", Scope::Reserved),
+            ("192.0.2.1", Scope::Documentation),
+            ("192.168.0.1", Scope::Private),
+            ("198.19.0.1", Scope::Reserved),
+            ("198.51.100.7", Scope::Documentation),
+            ("203.0.113.255", Scope::Documentation),
+            ("224.0.0.251", Scope::Multicast),
+            ("255.255.255.255", Scope::Reserved),
+            ("::", Scope::Reserved),
+            ("::1", Scope::Loopback),
+            ("::ffff:192.168.1.1", Scope::Private),
+            ("::ffff:8.8.8.8", Scope::Public),
+            ("2001:db8::1", Scope::Documentation),
+            ("2001:4860:4860::8888", Scope::Public),
+            ("2001::1", Scope::Reserved),
+            ("3fff::1", Scope::Documentation),
+            ("fd12:3456::1", Scope::Private),
+            ("fe80::1", Scope::LinkLocal),
+            ("ff02::1", Scope::Multicast),
+            ("4000::1", Scope::Reserved),
+        ] {
+            assert_eq!(expected, scope(input.parse().unwrap()), "{}", input);
+        }
+    }
+
+    #[test]
+    fn scope_should_round_trip_names() {
+        for (_, scope) in Scope::NAMES {
+            assert_eq!(Some(*scope), Scope::from_name(scope.name()));
+        }
+        assert_eq!(Some(Scope::LinkLocal), Scope::from_name("Link-Local"));
+        assert_eq!(None, Scope::from_name("global"));
+    }
+
+    // Zones and canonical forms
+    #[test]
+    fn find_should_extract_zones_and_embedded_ipv4() {
+        let finder = Ip::default();
+        for (input, expected) in [
+            ("ping fe80::1%eth0 now", Some("fe80::1%eth0")),
+            ("ping fe80::1%eth0.", Some("fe80::1%eth0")),
+            ("ping fe80::1% now", Some("fe80::1")),
+            ("http://[fe80::1%25en0]:80/", Some("[fe80::1%25en0]")),
+            ("[fe80::1%]", Some("fe80::1")),
+            ("nat64 64:ff9b::192.0.2.33 ok", Some("64:ff9b::192.0.2.33")),
+            ("mapped ::ffff:192.0.2.1.", Some("::ffff:192.0.2.1")),
+            ("end of 2001:db8::1.", Some("2001:db8::1")),
+        ] {
+            assert_eq!(expected, finder.find(input).map(|r| &input[r]), "{}", input);
+        }
+    }
+
+    #[test]
+    fn canonical_should_follow_rfc_5952() {
+        for (input, unmap, expected) in [
+            ("10.0.0.1", false, Some("10.0.0.1")),
+            ("2001:DB8:0:0::1", false, Some("2001:db8::1")),
+            (
+                "2001:0db8:0000:0000:0001:0000:0000:0001",
+                false,
+                Some("2001:db8::1:0:0:1"),
+            ),
+            ("2001:db8:0:1:1:1:1:1", false, Some("2001:db8:0:1:1:1:1:1")),
+            ("[::1]", false, Some("::1")),
+            ("FE80::1%eth0", false, Some("fe80::1%eth0")),
+            ("[fe80::1%25eth0]", false, Some("fe80::1%eth0")),
+            ("::ffff:c000:201", false, Some("::ffff:192.0.2.1")),
+            ("::ffff:192.0.2.1", true, Some("192.0.2.1")),
+            ("64:ff9b::c000:221", false, Some("64:ff9b::192.0.2.33")),
+            ("10.0.0.1%eth0", false, None),
+            ("fe80::1%", false, None),
+            ("nope", false, None),
+        ] {
+            assert_eq!(
+                expected.map(str::to_string),
+                canonical(input, unmap),
+                "{}",
+                input
+            );
+        }
+    }
 }
@@ -6,9 +6,9 @@
 //! - [`uri::URI`] - Extract URIs/URLs/URNs as defined by [RFC 3986](https://tools.ietf.org/html/rfc3986/)
 //! - [`cidr::Cidr`] - Extract CIDR notation (IPv4/IPv6 network ranges)
 //! - [`codetag::Codetag`] - Extract codetags (TODO, FIXME, etc.) as defined by [PEP 350](https://www.python.org/dev/peps/pep-0350/)
-//! - [`color::Color`] - Extract colors (hex, rgb, hsl)
-//! - [`datetime::Datetime`] - Extract ISO 8601 datetimes
-//! - [`email::Email`] - Extract email addresses
+//! - [`color::Color`] - Extract CSS Color 4 colors (hex, functions, names)
+//! - [`datetime::Datetime`] - Extract ISO 8601 (with durations and intervals), RFC 2822, syslog, CLF and epoch datetimes
+//! - [`email::Email`] - Extract email addresses, and the mailboxes of the mail headers
 //! - [`env::Env`] - Extract environment variable references
 //! - [`hash::Hash`] - Extract hashes (MD5, SHA-1, SHA-256, SHA-512)
 //! - [`ip::Ip`] - Extract IP addresses (IPv4, IPv6)
@@ -21,6 +21,14 @@
 //! - [`uuid::Uuid`] - Extract UUIDs
 //! - [`mirror::Mirror`] - A passthrough finder that returns the entire input
 //!
+//! The [`markup`] module complements the finders by extracting link targets from HTML and
+//! Markdown documents, and the [`clean`] module strips tracking parameters and redirectors from
+//! the URLs they find. The [`comment`] module restricts a search to the comments of source code,
+//! e.g. to only report the codetags written in comments. The [`cidr::Network`] type checks whether
+//! the IPs and CIDRs found are part of a network, and merges or subtracts networks. The
+//! [`semver::Version`] type orders the semantic versions found by precedence, and matches them
+//! against Cargo and npm requirements.
+//!
 //! ## Example
 //!
 //! ```
@@ -35,8 +43,10 @@
 //! ```
 
 pub mod cidr;
+pub mod clean;
 pub mod codetag;
 pub mod color;
+pub mod comment;
 pub mod datetime;
 pub mod email;
 pub mod env;
@@ -45,9 +55,11 @@ pub mod ip;
 pub mod json;
 pub mod jwt;
 pub mod mac;
+pub mod markup;
 pub mod mirror;
 pub mod path;
 pub mod phone;
+pub mod rules;
 pub mod semver;
 pub mod uri;
 pub mod uuid;
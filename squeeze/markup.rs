@@ -0,0 +1,702 @@
+//! Link target extraction from HTML and Markdown documents.
+//!
+//! Finders operate on raw text, which is good enough for most inputs but falls short on markup:
+//! `href` attributes contain HTML entities (`&amp;`), Markdown destinations contain backslash
+//! escapes, and both are full of relative references. This module walks the markup structure
+//! instead, and returns the decoded (and optionally resolved) link targets so that they can be
+//! fed to the finders.
+//!
+//! # Example
+//!
+//! ```
+//! use squeeze::markup;
+//!
+//! let html = r#"<a href="/search?q=rust&amp;page=2">next</a>"#;
+//! assert_eq!(
+//!     markup::html_links(html, Some("https://example.com/")),
+//!     vec!["https://example.com/search?q=rust&page=2"],
+//! );
+//!
+//! let md = "[docs](../api/index.html) and <https://example.com>";
+//! assert_eq!(
+//!     markup::markdown_links(md, Some("https://example.com/guide/")),
+//!     vec!["https://example.com/api/index.html", "https://example.com"],
+//! );
+//! ```
+
+use super::uri;
+use std::borrow::Cow;
+
+// Attributes holding a single URL
+const URL_ATTRIBUTES: &[&str] = &[
+    "action",
+    "background",
+    "cite",
+    "data",
+    "formaction",
+    "href",
+    "poster",
+    "src",
+];
+
+// Elements whose content must not be parsed as markup
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+static NAMED_ENTITIES: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "amp" => "&",
+    "apos" => "'",
+    "colon" => ":",
+    "comma" => ",",
+    "commat" => "@",
+    "dollar" => "$",
+    "equals" => "=",
+    "excl" => "!",
+    "gt" => ">",
+    "lowbar" => "_",
+    "lpar" => "(",
+    "lt" => "<",
+    "nbsp" => "\u{a0}",
+    "num" => "#",
+    "percnt" => "%",
+    "period" => ".",
+    "plus" => "+",
+    "quest" => "?",
+    "quot" => "\"",
+    "rpar" => ")",
+    "semi" => ";",
+    "sol" => "/",
+    "Tab" => "\t",
+    "NewLine" => "\n",
+};
+
+/// Decodes the HTML character references (`&amp;`, `&#38;`, `&#x26;`) found in `s`.
+///
+/// Unknown or malformed references are left untouched.
+///
+/// # Example
+///
+/// ```
+/// use squeeze::markup::decode_entities;
+///
+/// assert_eq!(decode_entities("?a=1&amp;b=2"), "?a=1&b=2");
+/// assert_eq!(decode_entities("&#104;&#x74;tp"), "http");
+/// assert_eq!(decode_entities("AT&T"), "AT&T");
+/// ```
+pub fn decode_entities(s: &str) -> Cow<'_, str> {
+    if !s.contains('&') {
+        return Cow::Borrowed(s);
+    }
+
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp_idx) = rest.find('&') {
+        out.push_str(&rest[..amp_idx]);
+        rest = &rest[amp_idx..];
+        match decode_entity(rest) {
+            Some((decoded, len)) => {
+                out.push_str(&decoded);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+
+    Cow::Owned(out)
+}
+
+// Decodes the character reference at the start of `s` (which starts with '&'), and returns the
+// decoded text along with the number of bytes consumed.
+fn decode_entity(s: &str) -> Option<(Cow<'static, str>, usize)> {
+    let body = &s[1..];
+
+    if let Some(numeric) = body.strip_prefix('#') {
+        let (digits, radix, prefix_len) = match numeric.strip_prefix(['x', 'X']) {
+            Some(hex) => (hex, 16, 3),
+            None => (numeric, 10, 2),
+        };
+        let len = digits
+            .bytes()
+            .take_while(|b| (*b as char).is_digit(radix))
+            .count();
+        if len == 0 || len > 8 {
+            return None;
+        }
+        let c = u32::from_str_radix(&digits[..len], radix)
+            .ok()
+            .and_then(char::from_u32)
+            .filter(|&c| c != '\0')
+            .unwrap_or('\u{fffd}');
+        let semicolon = usize::from(digits[len..].starts_with(';'));
+        return Some((Cow::Owned(c.to_string()), prefix_len + len + semicolon));
+    }
+
+    let len = body.bytes().take_while(u8::is_ascii_alphanumeric).count();
+    if !body[len..].starts_with(';') {
+        return None;
+    }
+    let decoded = NAMED_ENTITIES.get(&body[..len])?;
+    Some((Cow::Borrowed(decoded), len + 2))
+}
+
+/// Extracts the link targets of an HTML document.
+///
+/// Targets are read from the URL-carrying attributes (`href`, `src`, `srcset`, `action`, ...),
+/// with their character references decoded. Comments and the content of raw text elements
+/// (`<script>`, `<style>`, ...) are skipped.
+///
+/// When `base` is provided, or when the document declares a `<base href>`, relative targets are
+/// resolved against it (see [`uri::resolve`]).
+pub fn html_links(doc: &str, base: Option<&str>) -> Vec<String> {
+    let mut base = base.map(|b| b.to_string());
+    let mut base_seen = false;
+    let mut links = Vec::new();
+    let mut idx = 0;
+
+    while let Some(i) = doc[idx..].find('<') {
+        idx += i;
+        let rest = &doc[idx..];
+
+        if rest.starts_with("<!--") {
+            idx += rest.find("-->").map_or(rest.len(), |i| i + 3);
+            continue;
+        }
+
+        let tag = match parse_start_tag(rest) {
+            Some(tag) => tag,
+            None => {
+                idx += 1;
+                continue;
+            }
+        };
+        idx += tag.len;
+
+        if tag.name == "base" {
+            if !base_seen {
+                base_seen = true;
+                if let Some((_, href)) = tag.attributes.iter().find(|(name, _)| name == "href") {
+                    base = Some(resolve(base.as_deref(), href.trim()));
+                }
+            }
+            continue;
+        }
+
+        for (name, value) in &tag.attributes {
+            if URL_ATTRIBUTES.contains(&name.as_str()) {
+                let target = value.trim();
+                if !target.is_empty() {
+                    links.push(resolve(base.as_deref(), target));
+                }
+            } else if name == "srcset" {
+                for candidate in value.split(',') {
+                    if let Some(target) = candidate.split_whitespace().next() {
+                        links.push(resolve(base.as_deref(), target));
+                    }
+                }
+            }
+        }
+
+        if RAW_TEXT_ELEMENTS.contains(&tag.name.as_str()) {
+            let closing = format!("</{}", tag.name);
+            idx += find_ignore_ascii_case(&doc[idx..], &closing).unwrap_or(doc.len() - idx);
+        }
+    }
+
+    links
+}
+
+/// Extracts the link targets of a Markdown document.
+///
+/// Targets are read from inline links and images (`[text](target "title")`), link reference
+/// definitions (`[label]: target`), autolinks (`<https://example.com>`, `<user@example.com>`)
+/// and the URL attributes of inline HTML. Backslash escapes and character references are
+/// decoded. Code spans and fenced code blocks are skipped.
+///
+/// When `base` is provided, relative targets are resolved against it (see [`uri::resolve`]).
+pub fn markdown_links(doc: &str, base: Option<&str>) -> Vec<String> {
+    let mut links = Vec::new();
+    let mut fence: Option<(u8, usize)> = None;
+
+    for line in doc.lines() {
+        let trimmed = line.trim_start_matches(' ');
+        let indent = line.len() - trimmed.len();
+
+        // fenced code blocks
+        if indent <= 3 {
+            if let Some(&c) = trimmed
+                .as_bytes()
+                .first()
+                .filter(|&&c| c == b'`' || c == b'~')
+            {
+                let len = trimmed.bytes().take_while(|&b| b == c).count();
+                match fence {
+                    Some((fc, flen))
+                        if fc == c && len >= flen && trimmed[len..].trim().is_empty() =>
+                    {
+                        fence = None;
+                        continue;
+                    }
+                    None if len >= 3 => {
+                        fence = Some((c, len));
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if fence.is_some() {
+            continue;
+        }
+
+        // link reference definitions
+        if indent <= 3 {
+            if let Some(target) = parse_reference_definition(trimmed) {
+                links.push(resolve(base, &target));
+                continue;
+            }
+        }
+
+        scan_markdown_inline(line, base, &mut links);
+    }
+
+    links
+}
+
+fn scan_markdown_inline(line: &str, base: Option<&str>, links: &mut Vec<String>) {
+    let bytes = line.as_bytes();
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\\' => idx += 2,
+            b'`' => {
+                let run = bytes[idx..].iter().take_while(|&&b| b == b'`').count();
+                let ticks = &line[idx..idx + run];
+                idx += run;
+                // skip to the matching backtick run, if any
+                let mut search = idx;
+                while let Some(i) = line[search..].find(ticks) {
+                    let end = search + i;
+                    let len = bytes[end..].iter().take_while(|&&b| b == b'`').count();
+                    if len == run {
+                        idx = end + len;
+                        break;
+                    }
+                    search = end + len;
+                }
+            }
+            b']' if bytes.get(idx + 1) == Some(&b'(') => {
+                idx += 2;
+                if let Some((target, len)) = parse_link_destination(&line[idx..]) {
+                    if !target.is_empty() {
+                        links.push(resolve(base, &target));
+                    }
+                    idx += len;
+                }
+            }
+            b'<' => {
+                let rest = &line[idx..];
+                if let Some((target, len)) = parse_autolink(rest) {
+                    links.push(target);
+                    idx += len;
+                } else if let Some(tag) = parse_start_tag(rest) {
+                    for (name, value) in &tag.attributes {
+                        let target = value.trim();
+                        if URL_ATTRIBUTES.contains(&name.as_str()) && !target.is_empty() {
+                            links.push(resolve(base, target));
+                        }
+                    }
+                    idx += tag.len;
+                } else {
+                    idx += 1;
+                }
+            }
+            _ => idx += 1,
+        }
+    }
+}
+
+// [label]: destination ["title"]
+fn parse_reference_definition(line: &str) -> Option<String> {
+    let rest = line.strip_prefix('[')?;
+    let close = rest.find("]:")?;
+    let label = &rest[..close];
+    if label.trim().is_empty() || label.contains('[') {
+        return None;
+    }
+    let rest = rest[close + 2..].trim_start();
+    let (target, _) = parse_link_destination_raw(rest)?;
+    if target.is_empty() {
+        return None;
+    }
+    Some(target)
+}
+
+// destination [ title ] ")"
+fn parse_link_destination(s: &str) -> Option<(String, usize)> {
+    let rest = s.trim_start();
+    let (target, len) = parse_link_destination_raw(rest)?;
+
+    let rest = rest[len..].trim_start();
+    let rest = match rest.as_bytes().first() {
+        Some(&q @ (b'"' | b'\'')) => {
+            let close = rest[1..].find(q as char)?;
+            rest[close + 2..].trim_start()
+        }
+        _ => rest,
+    };
+    if !rest.starts_with(')') {
+        return None;
+    }
+
+    Some((target, s.len() - rest.len() + 1))
+}
+
+// "<" *( not "<" / ">" / newline ) ">" / 1*( not space / control ) with balanced parentheses
+fn parse_link_destination_raw(s: &str) -> Option<(String, usize)> {
+    if let Some(inner) = s.strip_prefix('<') {
+        let close = inner.find(['<', '>'])?;
+        if inner.as_bytes()[close] != b'>' {
+            return None;
+        }
+        return Some((unescape_markdown(&inner[..close]), close + 2));
+    }
+
+    let bytes = s.as_bytes();
+    let mut depth = 0usize;
+    let mut idx = 0;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\\' if bytes.get(idx + 1).is_some_and(u8::is_ascii_punctuation) => idx += 1,
+            b'(' => depth += 1,
+            b')' if depth == 0 => break,
+            b')' => depth -= 1,
+            b if b.is_ascii_whitespace() || b.is_ascii_control() => break,
+            _ => {}
+        }
+        idx += 1;
+    }
+    if depth != 0 {
+        return None;
+    }
+
+    Some((unescape_markdown(&s[..idx]), idx))
+}
+
+// "<" scheme ":" *( not space / "<" / ">" ) ">" / "<" email ">"
+fn parse_autolink(s: &str) -> Option<(String, usize)> {
+    let inner = &s[1..];
+    let close = inner.find(|c: char| c == '>' || c == '<' || c.is_whitespace())?;
+    if inner.as_bytes()[close] != b'>' || close == 0 {
+        return None;
+    }
+    let target = &inner[..close];
+
+    if let Some((scheme, _)) = target.split_once(':') {
+        let valid_scheme = (2..=32).contains(&scheme.len())
+            && scheme.as_bytes()[0].is_ascii_alphabetic()
+            && scheme
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.'));
+        if valid_scheme {
+            return Some((target.to_string(), close + 2));
+        }
+    }
+
+    if let Some((local, domain)) = target.split_once('@') {
+        if !local.is_empty() && domain.contains('.') && !domain.contains('@') {
+            return Some((format!("mailto:{}", target), close + 2));
+        }
+    }
+
+    None
+}
+
+fn unescape_markdown(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(&next) = chars.peek().filter(|c| c.is_ascii_punctuation()) {
+                out.push(next);
+                chars.next();
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    decode_entities(&out).into_owned()
+}
+
+struct Tag {
+    name: String,
+    attributes: Vec<(String, String)>,
+    len: usize,
+}
+
+// "<" tag-name *( attribute ) [ "/" ] ">"
+fn parse_start_tag(s: &str) -> Option<Tag> {
+    let bytes = s.as_bytes();
+    if bytes.len() < 2 || !bytes[1].is_ascii_alphabetic() {
+        return None;
+    }
+
+    let mut idx = 1;
+    while idx < bytes.len() && (bytes[idx].is_ascii_alphanumeric() || bytes[idx] == b'-') {
+        idx += 1;
+    }
+    let name = s[1..idx].to_ascii_lowercase();
+    let mut attributes = Vec::new();
+
+    loop {
+        while idx < bytes.len() && (bytes[idx].is_ascii_whitespace() || bytes[idx] == b'/') {
+            idx += 1;
+        }
+        match bytes.get(idx) {
+            None => return None,
+            Some(b'>') => break,
+            Some(b'<') => return None,
+            _ => {}
+        }
+
+        let name_start = idx;
+        while idx < bytes.len()
+            && !bytes[idx].is_ascii_whitespace()
+            && !matches!(bytes[idx], b'=' | b'>' | b'/' | b'<')
+        {
+            idx += 1;
+        }
+        let attr_name = s[name_start..idx].to_ascii_lowercase();
+        if attr_name.is_empty() {
+            idx += 1;
+            continue;
+        }
+
+        while idx < bytes.len() && bytes[idx].is_ascii_whitespace() {
+            idx += 1;
+        }
+        if bytes.get(idx) != Some(&b'=') {
+            attributes.push((attr_name, String::new()));
+            continue;
+        }
+        idx += 1;
+        while idx < bytes.len() && bytes[idx].is_ascii_whitespace() {
+            idx += 1;
+        }
+
+        let value = match bytes.get(idx) {
+            Some(&q @ (b'"' | b'\'')) => {
+                let close = s[idx + 1..].find(q as char)?;
+                let value = &s[idx + 1..idx + 1 + close];
+                idx += close + 2;
+                value
+            }
+            _ => {
+                let start = idx;
+                while idx < bytes.len() && !bytes[idx].is_ascii_whitespace() && bytes[idx] != b'>' {
+                    idx += 1;
+                }
+                &s[start..idx]
+            }
+        };
+        attributes.push((attr_name, decode_entities(value).into_owned()));
+    }
+
+    Some(Tag {
+        name,
+        attributes,
+        len: idx + 1,
+    })
+}
+
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|w| w.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+fn resolve(base: Option<&str>, target: &str) -> String {
+    base.and_then(|b| uri::resolve(b, target))
+        .unwrap_or_else(|| target.to_string())
+}
+
+#[cfg(test)]
+#[allow(clippy::useless_vec)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_entities_should_decode_named_and_numeric_references() {
+        for (input, expected) in vec![
+            ("", ""),
+            ("no entities", "no entities"),
+            ("a&amp;b", "a&b"),
+            ("&lt;&gt;&quot;&apos;", "<>\"'"),
+            ("&#38;&#x26;&#X26;", "&&&"),
+            ("&#38", "&"),
+            ("https&colon;&sol;&sol;x", "https://x"),
+        ] {
+            assert_eq!(expected, decode_entities(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn decode_entities_should_leave_unknown_references_untouched() {
+        for input in vec![
+            "AT&T",
+            "a & b",
+            "&unknown;",
+            "?a=1&b=2",
+            "&amp",
+            "&#;",
+            "&#xZZ;",
+        ] {
+            assert_eq!(input, decode_entities(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn html_links_should_extract_url_attributes() {
+        let doc = r#"
+            <link rel="stylesheet" href="/style.css">
+            <a href="https://example.com/?a=1&amp;b=2">link</a>
+            <img src='logo.png' srcset="logo-2x.png 2x, logo-3x.png 3x">
+            <form action=/submit method=post></form>
+        "#;
+        assert_eq!(
+            vec![
+                "/style.css",
+                "https://example.com/?a=1&b=2",
+                "logo.png",
+                "logo-2x.png",
+                "logo-3x.png",
+                "/submit",
+            ],
+            html_links(doc, None)
+        );
+    }
+
+    #[test]
+    fn html_links_should_resolve_against_the_base() {
+        let doc = r#"<a href="../api/index.html">api</a> <a href="?page=2">next</a>"#;
+        assert_eq!(
+            vec![
+                "https://example.com/api/index.html",
+                "https://example.com/docs/guide.html?page=2",
+            ],
+            html_links(doc, Some("https://example.com/docs/guide.html"))
+        );
+    }
+
+    #[test]
+    fn html_links_should_honor_the_base_element() {
+        let doc = r#"<base href="https://cdn.example.com/assets/"><img src="logo.png">"#;
+        assert_eq!(
+            vec!["https://cdn.example.com/assets/logo.png"],
+            html_links(doc, None)
+        );
+
+        let doc = r#"<base href="/assets/"><img src="logo.png">"#;
+        assert_eq!(
+            vec!["https://example.com/assets/logo.png"],
+            html_links(doc, Some("https://example.com/index.html"))
+        );
+    }
+
+    #[test]
+    fn html_links_should_skip_comments_and_raw_text() {
+        let doc = r#"
+            <!-- <a href="https://commented.com">x</a> -->
+            <script src="/app.js">var a = '<a href="https://script.com">';</script>
+            <a href="https://kept.com">kept</a>
+        "#;
+        assert_eq!(vec!["/app.js", "https://kept.com"], html_links(doc, None));
+    }
+
+    #[test]
+    fn html_links_should_handle_tags_spanning_lines() {
+        let doc = "<a\n  class=\"x\"\n  href=\"https://example.com\"\n>x</a>";
+        assert_eq!(vec!["https://example.com"], html_links(doc, None));
+    }
+
+    #[test]
+    fn html_links_should_ignore_malformed_markup() {
+        for doc in vec!["", "<", "< a href=x>", "<a href=\"unclosed", "1 < 2 > 0"] {
+            assert!(html_links(doc, None).is_empty(), "{}", doc);
+        }
+    }
+
+    #[test]
+    fn markdown_links_should_extract_inline_links_and_images() {
+        let doc = "[a](https://a.com) ![b](/b.png \"title\") [c](<https://c.com/with space>)";
+        assert_eq!(
+            vec!["https://a.com", "/b.png", "https://c.com/with space"],
+            markdown_links(doc, None)
+        );
+    }
+
+    #[test]
+    fn markdown_links_should_handle_parentheses_in_destinations() {
+        let doc = "[wiki](https://en.wikipedia.org/wiki/Rust_(programming_language)).";
+        assert_eq!(
+            vec!["https://en.wikipedia.org/wiki/Rust_(programming_language)"],
+            markdown_links(doc, None)
+        );
+    }
+
+    #[test]
+    fn markdown_links_should_extract_reference_definitions() {
+        let doc =
+            "See [the docs][docs].\n\n[docs]: https://docs.rs/squeeze \"Docs\"\n   [x]: <./x.md>";
+        assert_eq!(
+            vec!["https://docs.rs/squeeze", "./x.md"],
+            markdown_links(doc, None)
+        );
+    }
+
+    #[test]
+    fn markdown_links_should_extract_autolinks() {
+        let doc = "<https://example.com/a?b=c> and <user@example.com> but not <b>";
+        assert_eq!(
+            vec!["https://example.com/a?b=c", "mailto:user@example.com"],
+            markdown_links(doc, None)
+        );
+    }
+
+    #[test]
+    fn markdown_links_should_decode_escapes_and_entities() {
+        let doc = r"[a](https://example.com/?a=1&amp;b=\_2)";
+        assert_eq!(
+            vec!["https://example.com/?a=1&b=_2"],
+            markdown_links(doc, None)
+        );
+    }
+
+    #[test]
+    fn markdown_links_should_skip_code() {
+        let doc = "`[a](https://code.com)` [b](https://b.com)\n```\n[c](https://fenced.com)\n```\n[d](https://d.com)";
+        assert_eq!(
+            vec!["https://b.com", "https://d.com"],
+            markdown_links(doc, None)
+        );
+    }
+
+    #[test]
+    fn markdown_links_should_resolve_against_the_base() {
+        let doc = "[up](../api/index.html) [abs](https://other.com)";
+        assert_eq!(
+            vec!["https://example.com/api/index.html", "https://other.com"],
+            markdown_links(doc, Some("https://example.com/guide/"))
+        );
+    }
+
+    #[test]
+    fn markdown_links_should_extract_inline_html() {
+        let doc = r#"Some <a href="https://example.com/?a&amp;b">html</a>"#;
+        assert_eq!(vec!["https://example.com/?a&b"], markdown_links(doc, None));
+    }
+}
@@ -0,0 +1,30 @@
+//! Errors of the rule files extending the embedded rules, e.g. the cleaning rules of
+//! [`Cleaner`](crate::clean::Cleaner) and the normalization rules of
+//! [`Normalizer`](crate::email::Normalizer).
+
+use std::fmt;
+
+/// An error encountered while parsing rules.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RuleError {
+    /// The 1-based line number of the invalid rule.
+    pub line: usize,
+    message: String,
+}
+
+impl RuleError {
+    pub(crate) fn new(line: usize, message: &str) -> Self {
+        RuleError {
+            line,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for RuleError {}
@@ -1,9 +1,409 @@
 use super::Finder;
+use std::cmp::Ordering;
+use std::fmt;
 use std::ops::Range;
 
+/// Finds the semantic versions (e.g. `1.0.0` or `v2.3.1-rc.1+build.42`), see [`Version::parse`]
+/// to parse them.
 #[derive(Default)]
 pub struct Semver {}
 
+/// A semantic version, as defined by SemVer 2.0.
+///
+/// The versions are ordered by their precedence (see [`Version::precedence`]), then by their
+/// build metadata for the order to be total.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// The identifiers of the pre-release (e.g. `rc` and `1` for `1.0.0-rc.1`).
+    pub pre: Vec<Identifier>,
+    /// The identifiers of the build metadata (e.g. `build` and `42` for `1.0.0+build.42`).
+    pub build: Vec<String>,
+}
+
+/// An identifier of a pre-release, the numeric ones having a lower precedence than the
+/// alphanumeric ones.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Identifier {
+    Numeric(u64),
+    Alphanumeric(String),
+}
+
+impl Version {
+    /// Parses a semantic version as found by the [`Semver`] finder, optionally prefixed with
+    /// `v`, rejecting the numbers with leading zeros and the empty identifiers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use squeeze::semver::Version;
+    ///
+    /// let version = Version::parse("v1.0.0-rc.1+build.42").unwrap();
+    /// assert_eq!((1, 0, 0), (version.major, version.minor, version.patch));
+    /// assert!(version < Version::parse("1.0.0").unwrap());
+    /// assert!(Version::parse("01.0.0").is_none());
+    /// ```
+    pub fn parse(s: &str) -> Option<Version> {
+        let s = s.strip_prefix(['v', 'V']).unwrap_or(s);
+        let (s, build) = match s.split_once('+') {
+            Some((s, build)) => (s, Some(build)),
+            None => (s, None),
+        };
+        let (s, pre) = match s.split_once('-') {
+            Some((s, pre)) => (s, Some(pre)),
+            None => (s, None),
+        };
+        let mut numbers = s.split('.').map(Self::number);
+        let (Some(major), Some(minor), Some(patch), None) = (
+            numbers.next(),
+            numbers.next(),
+            numbers.next(),
+            numbers.next(),
+        ) else {
+            return None;
+        };
+
+        fn identifiers(s: Option<&str>) -> Option<Vec<&str>> {
+            let Some(s) = s else {
+                return Some(Vec::new());
+            };
+            let identifiers: Vec<_> = s.split('.').collect();
+            let is_valid = |i: &&str| {
+                !i.is_empty() && i.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+            };
+            identifiers.iter().all(is_valid).then_some(identifiers)
+        }
+        let pre = identifiers(pre)?
+            .into_iter()
+            .map(|i| match i.bytes().all(|b| b.is_ascii_digit()) {
+                true => Some(Identifier::Numeric(Self::number(i)?)),
+                false => Some(Identifier::Alphanumeric(i.to_string())),
+            })
+            .collect::<Option<_>>()?;
+        let build = identifiers(build)?
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        Some(Version {
+            major: major?,
+            minor: minor?,
+            patch: patch?,
+            pre,
+            build,
+        })
+    }
+
+    // A number without leading zeros.
+    fn number(s: &str) -> Option<u64> {
+        if s.is_empty()
+            || s.len() > 1 && s.starts_with('0')
+            || !s.bytes().all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+        s.parse().ok()
+    }
+
+    /// Compares the precedence of the versions, their build metadata being ignored: the
+    /// pre-releases precede their release, and their identifiers are compared one by one (e.g.
+    /// `1.0.0-alpha < 1.0.0-alpha.1 < 1.0.0-beta < 1.0.0-beta.2 < 1.0.0-beta.11 < 1.0.0`).
+    pub fn precedence(&self, other: &Version) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre.cmp(&other.pre),
+            })
+    }
+
+    fn is_prerelease_of(&self, other: &Version) -> bool {
+        !self.pre.is_empty()
+            && (self.major, self.minor, self.patch) == (other.major, other.minor, other.patch)
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.precedence(other)
+            .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Identifier::Numeric(n) => write!(f, "{}", n),
+            Identifier::Alphanumeric(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        for (i, identifier) in self.pre.iter().enumerate() {
+            write!(f, "{}{}", if i == 0 { '-' } else { '.' }, identifier)?;
+        }
+        if !self.build.is_empty() {
+            write!(f, "+{}", self.build.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+/// A requirement on the versions, in the syntaxes of Cargo and npm: comparators (`>=1.2`,
+/// `<2`, `=1.2.3`), caret (`^1.2`) and tilde (`~1.2.3`) requirements, wildcards (`1.x`, `1.2.*`
+/// or `*`), and hyphen ranges (`1.2 - 2.3.4`). The comparators separated by commas or spaces must
+/// all match, and the alternatives separated by `||` any. A bare version is a caret requirement
+/// as in Cargo (e.g. `1.2.3` is `^1.2.3`).
+///
+/// As in Cargo and npm, a pre-release only matches the requirements with a pre-release of the
+/// same version (e.g. `1.0.0-rc.2` matches `>=1.0.0-rc.1` but not `>=0.9`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionReq {
+    alternatives: Vec<Vec<Comparator>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Comparator {
+    op: Ordering,
+    // whether the versions equal to the bound match as well
+    inclusive: bool,
+    version: Version,
+}
+
+impl Comparator {
+    fn new(op: Ordering, inclusive: bool, (major, minor, patch): (u64, u64, u64)) -> Comparator {
+        Comparator {
+            op,
+            inclusive,
+            version: Version {
+                major,
+                minor,
+                patch,
+                pre: Vec::new(),
+                build: Vec::new(),
+            },
+        }
+    }
+
+    fn matches(&self, version: &Version) -> bool {
+        let ordering = version.precedence(&self.version);
+        ordering == self.op || self.inclusive && ordering == Ordering::Equal
+    }
+}
+
+// A version in a requirement, whose missing or wildcard parts are None.
+struct Partial {
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: Vec<Identifier>,
+    wildcard: bool,
+}
+
+impl Partial {
+    fn parse(s: &str) -> Option<Partial> {
+        let s = s.strip_prefix(['v', 'V']).unwrap_or(s);
+        // the build metadata is ignored
+        let s = s.split_once('+').map_or(s, |(s, _)| s);
+        let (numbers, pre) = match s.split_once('-') {
+            Some((numbers, pre)) => {
+                let version = Version::parse(&format!("0.0.0-{}", pre))?;
+                (numbers, version.pre)
+            }
+            None => (s, Vec::new()),
+        };
+
+        let mut parts = [None; 3];
+        let mut wildcard = false;
+        let mut count = 0;
+        for (i, part) in numbers.split('.').enumerate() {
+            if i == 3 {
+                return None;
+            }
+            count += 1;
+            if matches!(part, "x" | "X" | "*") {
+                wildcard = true;
+            } else if wildcard {
+                // the parts following a wildcard are wildcards as well
+                return None;
+            } else {
+                parts[i] = Some(Version::number(part)?);
+            }
+        }
+        // the pre-releases are only given with full versions
+        if !pre.is_empty() && (count < 3 || wildcard) {
+            return None;
+        }
+        let [major, minor, patch] = parts;
+        Some(Partial {
+            major,
+            minor,
+            patch,
+            pre,
+            wildcard,
+        })
+    }
+
+    // The version, its missing parts being 0.
+    fn lower(&self) -> Version {
+        Version {
+            major: self.major.unwrap_or(0),
+            minor: self.minor.unwrap_or(0),
+            patch: self.patch.unwrap_or(0),
+            pre: self.pre.clone(),
+            build: Vec::new(),
+        }
+    }
+
+    // The first version after the ones matching the partial version (e.g. 1.3.0 for 1.2),
+    // None if it is complete or matches any version.
+    fn upper(&self) -> Option<(u64, u64, u64)> {
+        match (self.major?, self.minor, self.patch) {
+            (major, None, _) => Some((major + 1, 0, 0)),
+            (major, Some(minor), None) => Some((major, minor + 1, 0)),
+            _ => None,
+        }
+    }
+
+    // Returns the comparators of the requirement with the given operator.
+    fn comparators(&self, op: &str) -> Option<Vec<Comparator>> {
+        use Ordering::{Equal, Greater, Less};
+        // the operators other than = and >= need a major version
+        if self.major.is_none() && !matches!(op, "" | "=" | ">=") {
+            return None;
+        }
+        let lower = Comparator {
+            op: Greater,
+            inclusive: true,
+            version: self.lower(),
+        };
+        let below = |bound| Comparator::new(Less, false, bound);
+        let wildcard = || match self.upper() {
+            Some(upper) => vec![lower.clone(), below(upper)],
+            None if self.major.is_none() => Vec::new(),
+            None => vec![Comparator {
+                op: Equal,
+                ..lower.clone()
+            }],
+        };
+        Some(match op {
+            "" if self.wildcard => wildcard(),
+            "=" => wildcard(),
+            ">" => vec![match self.upper() {
+                Some(upper) => Comparator::new(Greater, true, upper),
+                None => Comparator {
+                    op: Greater,
+                    inclusive: false,
+                    ..lower
+                },
+            }],
+            ">=" => vec![lower],
+            "<" => vec![Comparator {
+                op: Less,
+                inclusive: false,
+                ..lower
+            }],
+            "<=" => vec![match self.upper() {
+                Some(upper) => below(upper),
+                None => Comparator { op: Less, ..lower },
+            }],
+            "~" => vec![
+                lower.clone(),
+                below(match (self.major?, self.minor) {
+                    (major, None) => (major + 1, 0, 0),
+                    (major, Some(minor)) => (major, minor + 1, 0),
+                }),
+            ],
+            // the left-most non-zero part may not change
+            "^" | "" => vec![
+                lower.clone(),
+                below(match (self.major?, self.minor, self.patch) {
+                    (0, Some(0), Some(patch)) => (0, 0, patch + 1),
+                    (0, Some(minor), _) => (0, minor + 1, 0),
+                    (major, _, _) => (major + 1, 0, 0),
+                }),
+            ],
+            _ => return None,
+        })
+    }
+}
+
+impl VersionReq {
+    /// Parses a requirement on the versions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use squeeze::semver::{Version, VersionReq};
+    ///
+    /// let requirement = VersionReq::parse(">=1.2, <2").unwrap();
+    /// assert!(requirement.matches(&Version::parse("1.9.0").unwrap()));
+    /// assert!(!requirement.matches(&Version::parse("2.0.0").unwrap()));
+    /// ```
+    pub fn parse(s: &str) -> Option<VersionReq> {
+        let alternatives = s
+            .split("||")
+            .map(Self::parse_comparators)
+            .collect::<Option<_>>()?;
+        Some(VersionReq { alternatives })
+    }
+
+    fn parse_comparators(s: &str) -> Option<Vec<Comparator>> {
+        let tokens: Vec<_> = s
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|token| !token.is_empty())
+            .collect();
+        if tokens.is_empty() {
+            return None;
+        }
+        // 1.2.3 - 2.3.4
+        if let [lower, "-", upper] = tokens[..] {
+            let mut comparators = Partial::parse(lower)?.comparators(">=")?;
+            comparators.extend(Partial::parse(upper)?.comparators("<=")?);
+            return Some(comparators);
+        }
+
+        let mut comparators = Vec::new();
+        let mut tokens = tokens.into_iter();
+        while let Some(token) = tokens.next() {
+            let len = token
+                .find(|c: char| !matches!(c, '<' | '>' | '=' | '^' | '~'))
+                .unwrap_or(token.len());
+            let (op, version) = token.split_at(len);
+            // the operators may be separated from their version
+            let version = match version {
+                "" => tokens.next()?,
+                version => version,
+            };
+            comparators.extend(Partial::parse(version)?.comparators(op)?);
+        }
+        Some(comparators)
+    }
+
+    /// Returns whether the version matches the requirement.
+    pub fn matches(&self, version: &Version) -> bool {
+        self.alternatives.iter().any(|comparators| {
+            comparators.iter().all(|c| c.matches(version))
+                && (version.pre.is_empty()
+                    || comparators
+                        .iter()
+                        .any(|c| c.version.is_prerelease_of(version)))
+        })
+    }
+}
+
 impl Semver {
     fn is_boundary_before(input: &[u8], pos: usize) -> bool {
         if pos == 0 {
@@ -304,4 +704,143 @@ mod tests {
         let finder = Semver::default();
         assert!(finder.find("192.168.1.1").is_none());
     }
+
+    #[test]
+    fn parse_should_return_version() {
+        let version = Version::parse("v1.2.3-rc.1+build.42").unwrap();
+        assert_eq!((1, 2, 3), (version.major, version.minor, version.patch));
+        assert_eq!(
+            vec![
+                Identifier::Alphanumeric("rc".to_string()),
+                Identifier::Numeric(1)
+            ],
+            version.pre
+        );
+        assert_eq!(vec!["build", "42"], version.build);
+        assert_eq!("1.2.3-rc.1+build.42", version.to_string());
+    }
+
+    #[test]
+    fn parse_should_reject_invalid_versions() {
+        for input in [
+            "1.2",
+            "1.2.3.4",
+            "01.2.3",
+            "1.02.3",
+            "1.2.03",
+            "1.2.3-01",
+            "1.2.3-",
+            "1.2.3-rc..1",
+            "1.2.3+",
+            "1.2.3+build.",
+            "1.2.3-rc_1",
+            "x.2.3",
+            "",
+        ] {
+            assert!(Version::parse(input).is_none(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_should_accept_leading_zeros_in_build() {
+        let version = Version::parse("1.2.3-0a.0+001").unwrap();
+        assert_eq!(
+            vec![
+                Identifier::Alphanumeric("0a".to_string()),
+                Identifier::Numeric(0)
+            ],
+            version.pre
+        );
+        assert_eq!(vec!["001"], version.build);
+    }
+
+    #[test]
+    fn versions_should_be_ordered_by_precedence() {
+        let versions = [
+            "0.9.9",
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1",
+            "1.1.0",
+            "1.10.0",
+            "2.0.0",
+        ]
+        .map(|s| Version::parse(s).unwrap());
+        for pair in versions.windows(2) {
+            assert!(pair[0] < pair[1], "{} < {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn precedence_should_ignore_build() {
+        let a = Version::parse("1.0.0+build.1").unwrap();
+        let b = Version::parse("1.0.0+build.2").unwrap();
+        assert_eq!(Ordering::Equal, a.precedence(&b));
+        assert!(a < b);
+    }
+
+    #[test]
+    fn matches_should_apply_requirement() {
+        for (requirement, matching, not_matching) in [
+            ("1.2.3", &["1.2.3", "1.9.0"][..], &["1.2.2", "2.0.0"][..]),
+            ("^1.2", &["1.2.0", "1.9.9"], &["1.1.9", "2.0.0"]),
+            ("^0.2.3", &["0.2.3", "0.2.9"], &["0.2.2", "0.3.0"]),
+            ("^0.0.3", &["0.0.3"], &["0.0.2", "0.0.4"]),
+            ("^0.0", &["0.0.0", "0.0.9"], &["0.1.0"]),
+            ("^0", &["0.0.0", "0.9.0"], &["1.0.0"]),
+            ("~1.2.3", &["1.2.3", "1.2.9"], &["1.2.2", "1.3.0"]),
+            ("~1.2", &["1.2.0", "1.2.9"], &["1.3.0"]),
+            ("~1", &["1.0.0", "1.9.0"], &["2.0.0"]),
+            ("=1.2.3", &["1.2.3"], &["1.2.4"]),
+            ("=1.2", &["1.2.0", "1.2.9"], &["1.3.0"]),
+            (">1.2.3", &["1.2.4"], &["1.2.3"]),
+            (">1.2", &["1.3.0"], &["1.2.9"]),
+            (">1", &["2.0.0"], &["1.9.9"]),
+            (">=1.2", &["1.2.0", "3.0.0"], &["1.1.9"]),
+            ("<1.2.3", &["1.2.2"], &["1.2.3"]),
+            ("<2", &["1.9.9"], &["2.0.0"]),
+            ("<=1.2.3", &["1.2.3"], &["1.2.4"]),
+            ("<=1.2", &["1.2.9"], &["1.3.0"]),
+            ("1.x", &["1.0.0", "1.9.9"], &["0.9.9", "2.0.0"]),
+            ("1.2.*", &["1.2.0", "1.2.9"], &["1.3.0"]),
+            ("*", &["0.0.0", "9.9.9"], &["1.0.0-rc.1"]),
+            (">=1, <2", &["1.0.0", "1.9.9"], &["0.9.9", "2.0.0"]),
+            (">= 1.2 < 1.4", &["1.3.0"], &["1.4.0"]),
+            ("1.2 - 2.3.4", &["1.2.0", "2.3.4"], &["1.1.9", "2.3.5"]),
+            ("1.2 - 2.3", &["2.3.9"], &["2.4.0"]),
+            ("<1 || >=2", &["0.9.0", "2.0.0"], &["1.0.0"]),
+            (
+                ">=1.0.0-rc.1",
+                &["1.0.0-rc.2", "1.0.0", "2.0.0"],
+                &["1.0.0-beta", "2.0.0-rc.1"],
+            ),
+            ("<2", &["1.9.9"], &["2.0.0-rc.1"]),
+            ("v1.2.3+build", &["1.2.3"], &["1.2.2"]),
+        ] {
+            let req = VersionReq::parse(requirement).unwrap();
+            for version in matching {
+                let matches = req.matches(&Version::parse(version).unwrap());
+                assert!(matches, "{} matches {}", version, requirement);
+            }
+            for version in not_matching {
+                let matches = req.matches(&Version::parse(version).unwrap());
+                assert!(!matches, "{} does not match {}", version, requirement);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_should_reject_invalid_requirements() {
+        for input in [
+            "", "1.2.3.4", "01.2", "1.x.3", "1.2-rc", "~", ">", "!1.2", "^*", "<x", "1 -", "1.2 ||",
+        ] {
+            assert!(VersionReq::parse(input).is_none(), "{}", input);
+        }
+    }
 }